@@ -0,0 +1,26 @@
+//! Compares plain `verify` with verifying against a `PreparedPublicKey`.
+//!
+//! Run with `cargo bench --bench verify`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dilithium_raw::ml_dsa_65::{generate_keypair, sign, verify, PreparedPublicKey};
+
+fn bench_verify(c: &mut Criterion) {
+    let msg = b"hello world";
+    // ML-DSA keygen takes a 32 byte seed
+    let mut random = [37u8; 32];
+    let (pk, sk) = generate_keypair(&mut random);
+    let sig = sign(msg, &sk);
+    let prepared = PreparedPublicKey::new(&pk);
+
+    let mut group = c.benchmark_group("ml_dsa_65");
+    group.bench_function("verify", |b| b.iter(|| verify(black_box(msg), &sig, &pk)));
+    group.bench_function("prepare", |b| b.iter(|| PreparedPublicKey::new(&pk)));
+    group.bench_function("verify_prepared", |b| {
+        b.iter(|| prepared.verify(black_box(msg), &sig))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
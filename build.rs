@@ -1,48 +1,47 @@
 macro_rules! build_dilithium_clean {
-    ($level:literal, $feature:expr) => {
+    ($name:literal, $feature:expr) => {
         if $feature {
-            let files_glob = glob::glob(format!("extern/dilithium{}/clean/*.c", $level).as_ref())
+            let files_glob = glob::glob(format!("extern/{}/clean/*.c", $name).as_ref())
                 .expect("glob error")
                 .map(|g| g.expect("glob error"));
 
             cc::Build::new()
                 .include("extern/common")
                 .files(files_glob)
-                .compile(format!("dilithium{}_clean", $level).as_ref());
+                .compile(format!("{}_clean", $name).as_ref());
         }
     };
 }
 
 macro_rules! build_dilithium_avx2 {
-    ($level:literal, $feature:expr) => {
+    ($name:literal, $feature:expr) => {
         if $feature {
-            let files_glob = glob::glob(format!("extern/dilithium{}/avx2/*.[cS]", $level).as_ref())
+            let files_glob = glob::glob(format!("extern/{}/avx2/*.[cS]", $name).as_ref())
                 .expect("glob error")
                 .map(|g| g.expect("glob error"));
 
             let mut build = cc::Build::new();
             build
                 .include("extern/common")
-                .include(format!("extern/dilithium{}/avx2", $level))
+                .include(format!("extern/{}/avx2", $name))
                 .files(files_glob)
                 .flag("-mavx2")
-                .compile(format!("dilithium{}_avx2", $level).as_ref());
+                .compile(format!("{}_avx2", $name).as_ref());
         }
     };
 }
 
 macro_rules! build_dilithium_aarch64 {
-    ($level:literal, $feature:expr) => {
+    ($name:literal, $feature:expr) => {
         if $feature {
-            let files_glob =
-                glob::glob(format!("extern/dilithium{}/aarch64/*.[cS]", $level).as_ref())
-                    .expect("glob error")
-                    .map(|g| g.expect("glob error"));
+            let files_glob = glob::glob(format!("extern/{}/aarch64/*.[cS]", $name).as_ref())
+                .expect("glob error")
+                .map(|g| g.expect("glob error"));
 
             cc::Build::new()
                 .include("extern/common")
                 .files(files_glob)
-                .compile(format!("dilithium{}_aarch64", $level).as_ref());
+                .compile(format!("{}_aarch64", $name).as_ref());
         }
     };
 }
@@ -55,6 +54,9 @@ fn main() {
     let feat_dilithium2 = env::var("CARGO_FEATURE_DILITHIUM2").is_ok();
     let feat_dilithium3 = env::var("CARGO_FEATURE_DILITHIUM3").is_ok();
     let feat_dilithium5 = env::var("CARGO_FEATURE_DILITHIUM5").is_ok();
+    let feat_ml_dsa_44 = env::var("CARGO_FEATURE_ML_DSA_44").is_ok();
+    let feat_ml_dsa_65 = env::var("CARGO_FEATURE_ML_DSA_65").is_ok();
+    let feat_ml_dsa_87 = env::var("CARGO_FEATURE_ML_DSA_87").is_ok();
     let feat_avx2 = env::var("CARGO_FEATURE_AVX2").is_ok();
     let feat_aarch64 = env::var("CARGO_FEATURE_AARCH64").is_ok();
     let feat_dynamic_cpu = env::var("CARGO_FEATURE_DYNAMIC_CPU_FEATURES").is_ok();
@@ -98,19 +100,28 @@ fn main() {
         .files(files_glob)
         .compile("pqclean_common_helpers");
 
-    build_dilithium_clean!(2, feat_dilithium2);
-    build_dilithium_clean!(3, feat_dilithium3);
-    build_dilithium_clean!(5, feat_dilithium5);
+    build_dilithium_clean!("dilithium2", feat_dilithium2);
+    build_dilithium_clean!("dilithium3", feat_dilithium3);
+    build_dilithium_clean!("dilithium5", feat_dilithium5);
+    build_dilithium_clean!("ml_dsa_44", feat_ml_dsa_44);
+    build_dilithium_clean!("ml_dsa_65", feat_ml_dsa_65);
+    build_dilithium_clean!("ml_dsa_87", feat_ml_dsa_87);
 
     if enable_avx2 {
         println!("cargo:rustc-cfg=enable_avx2");
-        build_dilithium_avx2!(2, feat_dilithium2);
-        build_dilithium_avx2!(3, feat_dilithium3);
-        build_dilithium_avx2!(5, feat_dilithium5);
+        build_dilithium_avx2!("dilithium2", feat_dilithium2);
+        build_dilithium_avx2!("dilithium3", feat_dilithium3);
+        build_dilithium_avx2!("dilithium5", feat_dilithium5);
+        build_dilithium_avx2!("ml_dsa_44", feat_ml_dsa_44);
+        build_dilithium_avx2!("ml_dsa_65", feat_ml_dsa_65);
+        build_dilithium_avx2!("ml_dsa_87", feat_ml_dsa_87);
     } else if enable_aarch64 {
         println!("cargo:rustc-cfg=enable_aarch64");
-        build_dilithium_aarch64!(2, feat_dilithium2);
-        build_dilithium_aarch64!(3, feat_dilithium3);
-        build_dilithium_aarch64!(5, feat_dilithium5);
+        build_dilithium_aarch64!("dilithium2", feat_dilithium2);
+        build_dilithium_aarch64!("dilithium3", feat_dilithium3);
+        build_dilithium_aarch64!("dilithium5", feat_dilithium5);
+        build_dilithium_aarch64!("ml_dsa_44", feat_ml_dsa_44);
+        build_dilithium_aarch64!("ml_dsa_65", feat_ml_dsa_65);
+        build_dilithium_aarch64!("ml_dsa_87", feat_ml_dsa_87);
     }
 }
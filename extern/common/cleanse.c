@@ -0,0 +1,19 @@
+#include <stddef.h>
+#include <string.h>
+
+#include "cleanse.h"
+
+void pqclean_cleanse(void *ptr, size_t len) {
+#if defined(__GNUC__) || defined(__clang__)
+    memset(ptr, 0, len);
+    /* Compiler barrier: the asm may read the memory behind ptr, so the
+     * memset above has to be performed. */
+    __asm__ __volatile__("" : : "r"(ptr) : "memory");
+#else
+    volatile unsigned char *p = ptr;
+
+    while (len--) {
+        *p++ = 0;
+    }
+#endif
+}
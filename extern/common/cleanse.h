@@ -0,0 +1,18 @@
+#ifndef PQCLEAN_COMMON_CLEANSE_H
+#define PQCLEAN_COMMON_CLEANSE_H
+
+#include <stddef.h>
+
+/*************************************************
+ * Name:        pqclean_cleanse
+ *
+ * Description: Overwrite len bytes at ptr with zeros. Unlike a plain
+ *              memset, the write is not optimized away when ptr points to
+ *              a local which is dead afterwards.
+ *
+ * Arguments:   - void *ptr: pointer to memory to clear
+ *              - size_t len: number of bytes to clear
+ **************************************************/
+void pqclean_cleanse(void *ptr, size_t len);
+
+#endif
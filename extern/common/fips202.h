@@ -11,42 +11,47 @@
 #define SHA3_512_RATE 72
 
 
-#define PQC_SHAKEINCCTX_BYTES (sizeof(uint64_t)*26)
-#define PQC_SHAKECTX_BYTES (sizeof(uint64_t)*25)
+// The Keccak state is stored inline in the contexts, so none of the functions
+// below allocate. The incremental contexts store the number of buffered bytes
+// in the last word.
+#define PQC_SHAKEINCCTX_WORDS 26
+#define PQC_SHAKECTX_WORDS 25
+#define PQC_SHAKEINCCTX_BYTES (sizeof(uint64_t)*PQC_SHAKEINCCTX_WORDS)
+#define PQC_SHAKECTX_BYTES (sizeof(uint64_t)*PQC_SHAKECTX_WORDS)
 
 // Context for incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKEINCCTX_WORDS];
 } shake128incctx;
 
 // Context for non-incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKECTX_WORDS];
 } shake128ctx;
 
 // Context for incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKEINCCTX_WORDS];
 } shake256incctx;
 
 // Context for non-incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKECTX_WORDS];
 } shake256ctx;
 
 // Context for incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKEINCCTX_WORDS];
 } sha3_256incctx;
 
 // Context for incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKEINCCTX_WORDS];
 } sha3_384incctx;
 
 // Context for incremental API
 typedef struct {
-    uint64_t* ctx;
+    uint64_t ctx[PQC_SHAKEINCCTX_WORDS];
 } sha3_512incctx;
 
 /* Initialize the state and absorb the provided input.
@@ -60,7 +65,7 @@ void shake128_absorb(shake128ctx *state, const uint8_t *input, size_t inlen);
  * Supports being called multiple times
  */
 void shake128_squeezeblocks(uint8_t *output, size_t nblocks, shake128ctx *state);
-/* Free the state */
+/* Release the state; a no-op kept for API compatibility */
 void shake128_ctx_release(shake128ctx *state);
 /* Copy the state. */
 void shake128_ctx_clone(shake128ctx *dest, const shake128ctx *src);
@@ -81,7 +86,7 @@ void shake128_inc_finalize(shake128incctx *state);
 void shake128_inc_squeeze(uint8_t *output, size_t outlen, shake128incctx *state);
 /* Copy the context of the SHAKE128 XOF */
 void shake128_inc_ctx_clone(shake128incctx* dest, const shake128incctx *src);
-/* Free the context of the SHAKE128 XOF */
+/* Release the context of the SHAKE128 XOF; a no-op kept for API compatibility */
 void shake128_inc_ctx_release(shake128incctx *state);
 
 /* Initialize the state and absorb the provided input.
@@ -95,7 +100,7 @@ void shake256_absorb(shake256ctx *state, const uint8_t *input, size_t inlen);
  * Supports being called multiple times
  */
 void shake256_squeezeblocks(uint8_t *output, size_t nblocks, shake256ctx *state);
-/* Free the context held by this XOF */
+/* Release the context held by this XOF; a no-op kept for API compatibility */
 void shake256_ctx_release(shake256ctx *state);
 /* Copy the context held by this XOF */
 void shake256_ctx_clone(shake256ctx *dest, const shake256ctx *src);
@@ -112,7 +117,7 @@ void shake256_inc_finalize(shake256incctx *state);
 void shake256_inc_squeeze(uint8_t *output, size_t outlen, shake256incctx *state);
 /* Copy the state */
 void shake256_inc_ctx_clone(shake256incctx* dest, const shake256incctx *src);
-/* Free the state */
+/* Release the state; a no-op kept for API compatibility */
 void shake256_inc_ctx_release(shake256incctx *state);
 
 /* One-stop SHAKE128 call */
@@ -127,7 +132,7 @@ void shake256(uint8_t *output, size_t outlen,
 void sha3_256_inc_init(sha3_256incctx *state);
 /* Absorb blocks into SHA3 */
 void sha3_256_inc_absorb(sha3_256incctx *state, const uint8_t *input, size_t inlen);
-/* Obtain the output of the function and free `state` */
+/* Obtain the output of the function and release `state` */
 void sha3_256_inc_finalize(uint8_t *output, sha3_256incctx *state);
 /* Copy the context */
 void sha3_256_inc_ctx_clone(sha3_256incctx *dest, const sha3_256incctx *src);
@@ -140,7 +145,7 @@ void sha3_256(uint8_t *output, const uint8_t *input, size_t inlen);
 void sha3_384_inc_init(sha3_384incctx *state);
 /* Absorb blocks into SHA3 */
 void sha3_384_inc_absorb(sha3_384incctx *state, const uint8_t *input, size_t inlen);
-/* Obtain the output of the function and free `state` */
+/* Obtain the output of the function and release `state` */
 void sha3_384_inc_finalize(uint8_t *output, sha3_384incctx *state);
 /* Copy the context */
 void sha3_384_inc_ctx_clone(sha3_384incctx *dest, const sha3_384incctx *src);
@@ -154,7 +159,7 @@ void sha3_384(uint8_t *output, const uint8_t *input, size_t inlen);
 void sha3_512_inc_init(sha3_512incctx *state);
 /* Absorb blocks into SHA3 */
 void sha3_512_inc_absorb(sha3_512incctx *state, const uint8_t *input, size_t inlen);
-/* Obtain the output of the function and free `state` */
+/* Obtain the output of the function and release `state` */
 void sha3_512_inc_finalize(uint8_t *output, sha3_512incctx *state);
 /* Copy the context */
 void sha3_512_inc_ctx_clone(sha3_512incctx *dest, const sha3_512incctx *src);
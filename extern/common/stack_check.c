@@ -0,0 +1,49 @@
+#include <stddef.h>
+#include <stdint.h>
+
+#include "stack_check.h"
+
+#if defined(__GNUC__) || defined(__clang__)
+#define NOINLINE __attribute__((noinline))
+#elif defined(_MSC_VER)
+#define NOINLINE __declspec(noinline)
+#else
+#define NOINLINE
+#endif
+
+/* Clear (and fault in) the stack region the callee is going to use */
+static NOINLINE void paint_stack(void) {
+    volatile uint8_t buf[PQCLEAN_STACK_CHECK_BYTES];
+    size_t i;
+
+    for (i = 0; i < PQCLEAN_STACK_CHECK_BYTES; i++) {
+        buf[i] = 0;
+    }
+    (void) buf[0];
+}
+
+int pqclean_stack_check(void (*fn)(void *), void *arg,
+                        const uint8_t *needle, size_t needlelen) {
+    volatile uint8_t marker = 0;
+    uintptr_t top = (uintptr_t) &marker;
+    uintptr_t bottom = top - PQCLEAN_STACK_CHECK_BYTES;
+    uintptr_t p;
+    size_t i;
+
+    paint_stack();
+    fn(arg);
+
+    /* Only reads through volatile pointers from here on, so the scan does not
+     * touch the region with calls of its own. */
+    for (p = bottom; p + needlelen <= top; p++) {
+        for (i = 0; i < needlelen; i++) {
+            if (*(const volatile uint8_t *) (p + i) != needle[i]) {
+                break;
+            }
+        }
+        if (i == needlelen) {
+            return 1;
+        }
+    }
+    return 0;
+}
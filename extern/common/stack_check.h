@@ -0,0 +1,27 @@
+#ifndef PQCLEAN_COMMON_STACK_CHECK_H
+#define PQCLEAN_COMMON_STACK_CHECK_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* Number of bytes below the caller's frame which are painted and scanned */
+#define PQCLEAN_STACK_CHECK_BYTES (512 * 1024)
+
+/*************************************************
+ * Name:        pqclean_stack_check
+ *
+ * Description: Test helper. Clears PQCLEAN_STACK_CHECK_BYTES of stack below
+ *              the current frame, calls fn(arg) and afterwards scans the
+ *              same stack region for the byte string needle.
+ *
+ * Arguments:   - void (*fn)(void *): function to call
+ *              - void *arg: argument passed to fn
+ *              - const uint8_t *needle: pointer to secret to search for
+ *              - size_t needlelen: length of needle, non-zero
+ *
+ * Returns 1 if the needle was left behind on the stack and 0 otherwise
+ **************************************************/
+int pqclean_stack_check(void (*fn)(void *), void *arg,
+                        const uint8_t *needle, size_t needlelen);
+
+#endif
@@ -13,6 +13,7 @@
 
 #define SEEDBYTES 32
 #define CRHBYTES 64
+#define RNDBYTES 32
 #define N 256
 #define DILITHIUM_Q 8380417
 #define D 13
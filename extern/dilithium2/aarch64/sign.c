@@ -3,9 +3,11 @@
 #include "params.h"
 #include "poly.h"
 #include "polyvec.h"
+#include "cleanse.h"
 #include "sign.h"
 #include "symmetric.h"
 #include <stdint.h>
+#include <string.h>
 
 /*************************************************
 * Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_keypair
@@ -65,11 +67,517 @@ int DILITHIUM_NAMESPACE(crypto_sign_keypair)(
     shake256(tr, SEEDBYTES, pk, CRYPTO_PUBLICKEYBYTES);
     pack_sk(sk, rho, tr, key, &t0, &s1, &s2);
 
+    /* Clear secrets from the stack */
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s1hat, sizeof(s1hat));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
     return 0;
 }
 
 /*************************************************
-* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_pk_from_sk
+*
+* Description: Recomputes the public key from a secret key and checks it
+*              against the hash tr stored in the secret key.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if H(pk) equals tr and -1 otherwise; pk is written in both cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_pk_from_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + SEEDBYTES];
+    uint8_t tr[SEEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    uint8_t diff = 0;
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2, t1, t0;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + SEEDBYTES;
+    unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Expand matrix */
+    polyvec_matrix_expand(mat, rho);
+
+    /* Matrix-vector multiplication */
+    polyvecl_ntt(&s1);
+    polyvec_matrix_pointwise_montgomery(&t1, mat, &s1);
+    polyveck_reduce(&t1);
+    polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    polyveck_caddq(&t1);
+    polyveck_power2round(&t1, &t0, &t1);
+    pack_pk(pk, rho, &t1);
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, SEEDBYTES, pk, CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < SEEDBYTES; ++i) {
+        diff |= tr[i] ^ sktr[i];
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return diff ? -1 : 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_check_sk
+*
+* Description: Checks that a secret key is consistent and recomputes the
+*              public key from it.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if the secret key is consistent, -1 if a coefficient of s1 or s2
+* is outside of [-ETA, ETA], -2 if t0 does not match the low bits of the
+* recomputed t and -3 if H(pk) does not equal tr; pk is written in all cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i, j;
+    int ret = 0;
+    uint8_t seedbuf[2 * SEEDBYTES + SEEDBYTES];
+    uint8_t tr[SEEDBYTES];
+    uint8_t t0buf[POLYT0_PACKEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    const uint8_t *skt0 = sk + SECRETKEYBYTES - K * POLYT0_PACKEDBYTES;
+    uint8_t diff = 0, t0diff = 0;
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2, t1, t0;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + SEEDBYTES;
+    unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Check that the coefficients of s1 and s2 lie in [-ETA, ETA] */
+    if (polyvecl_chknorm(&s1, ETA + 1)
+            || polyveck_chknorm(&s2, ETA + 1)) {
+        ret = -1;
+    }
+
+    /* Expand matrix */
+    polyvec_matrix_expand(mat, rho);
+
+    /* Matrix-vector multiplication */
+    polyvecl_ntt(&s1);
+    polyvec_matrix_pointwise_montgomery(&t1, mat, &s1);
+    polyveck_reduce(&t1);
+    polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    polyveck_caddq(&t1);
+    polyveck_power2round(&t1, &t0, &t1);
+    pack_pk(pk, rho, &t1);
+
+    /* Check the packed t0 of the secret key against the recomputed one */
+    for (i = 0; i < K; ++i) {
+        polyt0_pack(t0buf, &t0.vec[i]);
+        for (j = 0; j < POLYT0_PACKEDBYTES; ++j) {
+            t0diff |= t0buf[j] ^ skt0[i * POLYT0_PACKEDBYTES + j];
+        }
+    }
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, SEEDBYTES, pk, PUBLICKEYBYTES);
+    for (i = 0; i < SEEDBYTES; ++i) {
+        diff |= tr[i] ^ sktr[i];
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(t0buf, sizeof(t0buf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    if (ret == 0 && t0diff) {
+        ret = -2;
+    }
+    if (ret == 0 && diff) {
+        ret = -3;
+    }
+    return ret;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_check_sig
+*
+* Description: Checks that a signature is canonically encoded, i.e. that it
+*              would not be rejected by verification before the challenge is
+*              recomputed.
+*
+* Arguments:   - const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]:
+*                       pointer to signature
+*
+* Returns 0 if the signature is well-formed, -1 if a hint counter is
+* decreasing or unused hint indices are not zero, -2 if the hint indices of a
+* polynomial are not strictly increasing, -3 if there are more than OMEGA
+* hints and -4 if a coefficient of z is outside of (-(GAMMA1 - BETA),
+* GAMMA1 - BETA)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sig)(
+    const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]
+) {
+    unsigned int i, j, k;
+    const uint8_t *hint = sig + SEEDBYTES + L * POLYZ_PACKEDBYTES;
+    poly z;
+
+    /* Decode h, rejecting it like unpack_sig */
+    k = 0;
+    for (i = 0; i < K; ++i) {
+        if (hint[OMEGA + i] > OMEGA) {
+            return -3;
+        }
+        if (hint[OMEGA + i] < k) {
+            return -1;
+        }
+        for (j = k + 1; j < hint[OMEGA + i]; ++j) {
+            if (hint[j] <= hint[j - 1]) {
+                return -2;
+            }
+        }
+        k = hint[OMEGA + i];
+    }
+    for (j = k; j < OMEGA; ++j) {
+        if (hint[j]) {
+            return -1;
+        }
+    }
+
+    /* Check the norm of z */
+    for (i = 0; i < L; ++i) {
+        polyz_unpack(&z, sig + SEEDBYTES + i * POLYZ_PACKEDBYTES);
+        if (poly_chknorm(&z, GAMMA1 - BETA)) {
+            return -4;
+        }
+    }
+
+    return 0;
+}
+
+/* Expanded public key: the matrix A, NTT(t1 * 2^D) and tr = H(pk) */
+typedef struct {
+    polyvecl mat[K];
+    polyveck t1;
+    uint8_t tr[SEEDBYTES];
+} prepared_pk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char prepared_pk_size_check[
+    sizeof(prepared_pk) == DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_prepare_pk
+*
+* Description: Expands a public key once for verifying many signatures with
+*              PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_verify_prepared.
+*
+* Arguments:   - uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to output prepared public key, aligned to
+*                       32 bytes
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_prepare_pk)(
+    uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)],
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {
+    prepared_pk *p = (prepared_pk *) ppk;
+    uint8_t rho[SEEDBYTES];
+
+    unpack_pk(rho, &p->t1, pk);
+    polyvec_matrix_expand(p->mat, rho);
+    polyveck_shiftl(&p->t1);
+    polyveck_ntt(&p->t1);
+    shake256(p->tr, SEEDBYTES, pk, PUBLICKEYBYTES);
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_verify_prepared
+*
+* Description: Verifies signature with a public key prepared by
+*              PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_prepare_pk.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to prepared public key
+*
+* Returns the same values as PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_verify
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify_prepared)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]
+) {
+    unsigned int i;
+    uint8_t buf[K * POLYW1_PACKEDBYTES];
+    uint8_t mu[CRHBYTES];
+    uint8_t c[SEEDBYTES];
+    uint8_t c2[SEEDBYTES];
+    const prepared_pk *p = (const prepared_pk *) ppk;
+    poly cp;
+    polyvecl z;
+    polyveck t1, w1, h;
+    shake256incctx state;
+
+    if (unpack_sig(c, &z, &h, sig)) {
+        return -1;
+    }
+    if (polyvecl_chknorm(&z, GAMMA1 - BETA)) {
+        return -2;
+    }
+
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, p->tr, SEEDBYTES);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Matrix-vector multiplication; compute Az - c2^dt1 */
+    poly_challenge(&cp, c);
+
+    polyvecl_ntt(&z);
+    polyvec_matrix_pointwise_montgomery(&w1, p->mat, &z);
+
+    poly_ntt(&cp);
+    polyveck_pointwise_poly_montgomery(&t1, &cp, &p->t1);
+
+    polyveck_sub(&w1, &w1, &t1);
+    polyveck_reduce(&w1);
+    polyveck_invntt_tomont(&w1);
+
+    /* Reconstruct w1 */
+    polyveck_caddq(&w1);
+    polyveck_use_hint(&w1, &w1, &h);
+    polyveck_pack_w1(buf, &w1);
+
+    /* Call random oracle and verify challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(c2, SEEDBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < SEEDBYTES; ++i) {
+        if (c[i] != c2[i]) {
+            return -3;
+        }
+    }
+
+    return 0;
+}
+
+/* Expanded secret key: the matrix A, NTT(s1), NTT(s2), NTT(t0), key and tr */
+typedef struct {
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2;
+    polyveck t0;
+    uint8_t key[SEEDBYTES];
+    uint8_t tr[SEEDBYTES];
+} expanded_sk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char expanded_sk_size_check[
+    sizeof(expanded_sk) == DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_expand_sk
+*
+* Description: Expands a secret key once for computing many signatures with
+*              PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_expanded_randomized. The
+*              expanded secret key must be cleared by the caller.
+*
+* Arguments:   - uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to output expanded secret key, aligned to
+*                       32 bytes
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_expand_sk)(
+    uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    expanded_sk *e = (expanded_sk *) esk;
+    uint8_t rho[SEEDBYTES];
+
+    unpack_sk(rho, e->tr, e->key, &e->t0, &e->s1, &e->s2, sk);
+    polyvec_matrix_expand(e->mat, rho);
+    polyvecl_ntt(&e->s1);
+    polyveck_ntt(&e->s2);
+    polyveck_ntt(&e->t0);
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_expanded_randomized
+*
+* Description: Computes signature with a secret key expanded by
+*              PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_expand_sk.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* rnd: NULL for deterministic signing, or
+*                       pointer to RNDBYTES bytes of randomness mixed into
+*                       the derivation of rhoprime
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_randomized)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {
+    unsigned int n;
+    const expanded_sk *e = (const expanded_sk *) esk;
+    uint8_t seedbuf[SEEDBYTES + 2 * CRHBYTES];
+    uint8_t *key, *mu, *rhoprime;
+    const uint8_t *tr = e->tr;
+    uint16_t nonce = 0;
+    polyvecl y, z;
+    polyveck w1, w0, h;
+    poly cp;
+    shake256incctx state;
+
+    key = seedbuf;
+    mu = key + SEEDBYTES;
+    rhoprime = mu + CRHBYTES;
+    memcpy(key, e->key, SEEDBYTES);
+
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, tr, SEEDBYTES);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    if (rnd == NULL) {
+        /* Deterministic signing */
+        shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    } else {
+        /* Compute rhoprime = CRH(key, rnd, mu) */
+        shake256_inc_init(&state);
+        shake256_inc_absorb(&state, key, SEEDBYTES);
+        shake256_inc_absorb(&state, rnd, RNDBYTES);
+        shake256_inc_absorb(&state, mu, CRHBYTES);
+        shake256_inc_finalize(&state);
+        shake256_inc_squeeze(rhoprime, CRHBYTES, &state);
+        shake256_inc_ctx_release(&state);
+    }
+
+rej:
+    /* Sample intermediate vector y */
+    polyvecl_uniform_gamma1(&y, rhoprime, nonce++);
+
+    /* Matrix-vector multiplication */
+    z = y;
+    polyvecl_ntt(&z);
+    polyvec_matrix_pointwise_montgomery(&w1, e->mat, &z);
+    polyveck_reduce(&w1);
+    polyveck_invntt_tomont(&w1);
+
+    /* Decompose w and call the random oracle */
+    polyveck_caddq(&w1);
+    polyveck_decompose(&w1, &w0, &w1);
+    polyveck_pack_w1(sig, &w1);
+
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, SEEDBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    poly_challenge(&cp, sig);
+    poly_ntt(&cp);
+
+    /* Compute z, reject if it reveals secret */
+    polyvecl_pointwise_poly_montgomery(&z, &cp, &e->s1);
+    polyvecl_invntt_tomont(&z);
+    polyvecl_add(&z, &z, &y);
+    polyvecl_reduce(&z);
+    if (polyvecl_chknorm(&z, GAMMA1 - BETA)) {
+        goto rej;
+    }
+
+    /* Check that subtracting cs2 does not change high bits of w and low bits
+     * do not reveal secret information */
+    polyveck_pointwise_poly_montgomery(&h, &cp, &e->s2);
+    polyveck_invntt_tomont(&h);
+    polyveck_sub(&w0, &w0, &h);
+    polyveck_reduce(&w0);
+    if (polyveck_chknorm(&w0, GAMMA2 - BETA)) {
+        goto rej;
+    }
+
+    /* Compute hints for w1 */
+    polyveck_pointwise_poly_montgomery(&h, &cp, &e->t0);
+    polyveck_invntt_tomont(&h);
+    polyveck_reduce(&h);
+    if (polyveck_chknorm(&h, GAMMA2)) {
+        goto rej;
+    }
+
+    polyveck_add(&w0, &w0, &h);
+    n = polyveck_make_hint(&h, &w0, &w1);
+    if (n > OMEGA) {
+        goto rej;
+    }
+
+    /* Write signature */
+    pack_sig(sig, sig, &z, &h);
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&y, sizeof(y));
+    pqclean_cleanse(&state, sizeof(state));
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_randomized
 *
 * Description: Computes signature.
 *
@@ -77,14 +585,18 @@ int DILITHIUM_NAMESPACE(crypto_sign_keypair)(
 *                       of CRYPTO_BYTES bytes)
 *              - uint8_t* m:     pointer to message to be signed
 *              - size_t mlen:    length of message
+*              - const uint8_t* rnd: NULL for deterministic signing, or
+*                       pointer to RNDBYTES bytes of randomness mixed into
+*                       the derivation of rhoprime
 *              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
 *                       pointer to bit-packed secret key
 *
 * Returns 0 (success)
 **************************************************/
-int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+int DILITHIUM_NAMESPACE(crypto_sign_signature_randomized)(
     uint8_t* sig,
     const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
     const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
 ) {
     unsigned int n;
@@ -111,7 +623,19 @@ int DILITHIUM_NAMESPACE(crypto_sign_signature)(
     shake256_inc_squeeze(mu, CRHBYTES, &state);
     shake256_inc_ctx_release(&state);
 
-    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    if (rnd == NULL) {
+        /* Deterministic signing */
+        shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    } else {
+        /* Compute rhoprime = CRH(key, rnd, mu) */
+        shake256_inc_init(&state);
+        shake256_inc_absorb(&state, key, SEEDBYTES);
+        shake256_inc_absorb(&state, rnd, RNDBYTES);
+        shake256_inc_absorb(&state, mu, CRHBYTES);
+        shake256_inc_finalize(&state);
+        shake256_inc_squeeze(rhoprime, CRHBYTES, &state);
+        shake256_inc_ctx_release(&state);
+    }
 
     /* Expand matrix and transform vectors */
     polyvec_matrix_expand(mat, rho);
@@ -179,6 +703,14 @@ rej:
 
     /* Write signature */
     pack_sig(sig, sig, &z, &h);
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    pqclean_cleanse(&y, sizeof(y));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }
 
@@ -193,7 +725,9 @@ rej:
 *              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
 *                       pointer to bit-packed public key
 *
-* Returns 0 if signature could be verified correctly and -1 otherwise
+* Returns 0 if signature could be verified correctly, -1 if the hints are
+* malformed, -2 if the norm of z is too large and -3 if the challenge does not
+* match
 **************************************************/
 int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     const uint8_t* sig,
@@ -216,7 +750,7 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
         return -1;
     }
     if (polyvecl_chknorm(&z, GAMMA1 - BETA)) {
-        return -1;
+        return -2;
     }
 
     /* Compute CRH(H(rho, t1), msg) */
@@ -258,9 +792,84 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     shake256_inc_ctx_release(&state);
     for (i = 0; i < SEEDBYTES; ++i) {
         if (c[i] != c2[i]) {
-            return -1;
+            return -3;
         }
     }
 
     return 0;
 }
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature
+*
+* Description: Computes signature deterministically.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_randomized)(sig, m, mlen, NULL, sk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_expanded
+*
+* Description: Computes signature deterministically with an expanded secret
+*              key.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_randomized)(sig, m, mlen, NULL, esk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_seed_keypair
+*
+* Description: Generates public and private key from a seed, which is
+*              expanded with SHAKE256 like in the reference implementation.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to output private key (allocated
+*                       array of CRYPTO_SECRETKEYBYTES bytes)
+*              - const uint8_t seed[SEEDBYTES]: pointer to seed
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_seed_keypair)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)],
+    const uint8_t seed[SEEDBYTES]
+) {
+    uint8_t seedbuf[2 * SEEDBYTES + CRHBYTES];
+    int ret;
+
+    shake256(seedbuf, 2 * SEEDBYTES + CRHBYTES, seed, SEEDBYTES);
+    ret = DILITHIUM_NAMESPACE(crypto_sign_keypair)(pk, sk, seedbuf);
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    return ret;
+}
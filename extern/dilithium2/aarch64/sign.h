@@ -6,6 +6,12 @@
 #include <stddef.h>
 #include <stdint.h>
 
+/* Expanded public key of crypto_sign_prepare_pk, aligned to 32 bytes */
+#define PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES 20512
+
+/* Expanded secret key of crypto_sign_expand_sk, aligned to 32 bytes */
+#define PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES 28736
+
 int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_keypair(
     uint8_t pk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PUBLICKEYBYTES],
     uint8_t sk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_SECRETKEYBYTES],
@@ -24,4 +30,60 @@ int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_verify(
     const uint8_t pk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PUBLICKEYBYTES]
 );
 
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_pk_from_sk(
+    uint8_t pk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_check_sk(
+    uint8_t pk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_check_sig(
+    const uint8_t sig[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_BYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_prepare_pk(
+    uint8_t ppk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES],
+    const uint8_t pk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_verify_prepared(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t ppk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_expand_sk(
+    uint8_t esk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES],
+    const uint8_t sk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_expanded(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t esk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t sk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_signature_expanded_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t esk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_AARCH64_crypto_sign_seed_keypair(
+    uint8_t pk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_DILITHIUM2_AARCH64_CRYPTO_SECRETKEYBYTES],
+    const uint8_t seed[SEEDBYTES]
+);
+
 #endif
@@ -1,4 +1,5 @@
 #include "align.h"
+#include "cleanse.h"
 #include "fips202.h"
 #include "packing.h"
 #include "params.h"
@@ -105,11 +106,578 @@ int DILITHIUM_NAMESPACE(crypto_sign_keypair)(
     /* Compute H(rho, t1) and store in secret key */
     shake256(sk + 2 * SEEDBYTES, SEEDBYTES, pk, PQCLEAN_DILITHIUM2_AVX2_CRYPTO_PUBLICKEYBYTES);
 
+    /* Clear secrets from the stack */
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
     return 0;
 }
 
 /*************************************************
-* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_signature
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_pk_from_sk
+*
+* Description: Recomputes the public key from a secret key and checks it
+*              against the hash tr stored in the secret key.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if H(pk) equals tr and -1 otherwise; pk is written in both cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_pk_from_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + SEEDBYTES];
+    uint8_t tr[SEEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    uint8_t diff = 0;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2, t0;
+    poly t1;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + SEEDBYTES;
+    PQCLEAN_DILITHIUM2_AVX2_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Store rho */
+    memcpy(pk, rho, SEEDBYTES);
+
+    /* Transform s1 */
+    PQCLEAN_DILITHIUM2_AVX2_polyvecl_ntt(&s1);
+
+
+    for (i = 0; i < K; i++) {
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_DILITHIUM2_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_DILITHIUM2_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_DILITHIUM2_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1 */
+        PQCLEAN_DILITHIUM2_AVX2_poly_caddq(&t1);
+        PQCLEAN_DILITHIUM2_AVX2_poly_power2round(&t1, &t0.vec[i], &t1);
+        PQCLEAN_DILITHIUM2_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+    }
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, SEEDBYTES, pk, PQCLEAN_DILITHIUM2_AVX2_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < SEEDBYTES; ++i) {
+        diff |= tr[i] ^ sktr[i];
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return diff ? -1 : 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_check_sk
+*
+* Description: Checks that a secret key is consistent and recomputes the
+*              public key from it.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if the secret key is consistent, -1 if a coefficient of s1 or s2
+* is outside of [-ETA, ETA], -2 if t0 does not match the low bits of the
+* recomputed t and -3 if H(pk) does not equal tr; pk is written in all cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i, j;
+    int ret = 0;
+    uint8_t seedbuf[2 * SEEDBYTES + SEEDBYTES];
+    uint8_t tr[SEEDBYTES];
+    uint8_t t0buf[POLYT0_PACKEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    const uint8_t *skt0 = sk + PQCLEAN_DILITHIUM2_AVX2_CRYPTO_SECRETKEYBYTES - K * POLYT0_PACKEDBYTES;
+    uint8_t diff = 0, t0diff = 0;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2, t0;
+    poly t1;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + SEEDBYTES;
+    PQCLEAN_DILITHIUM2_AVX2_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Check that the coefficients of s1 and s2 lie in [-ETA, ETA] */
+    if (PQCLEAN_DILITHIUM2_AVX2_polyvecl_chknorm(&s1, ETA + 1)
+            || PQCLEAN_DILITHIUM2_AVX2_polyveck_chknorm(&s2, ETA + 1)) {
+        ret = -1;
+    }
+
+    /* Store rho */
+    memcpy(pk, rho, SEEDBYTES);
+
+    /* Transform s1 */
+    PQCLEAN_DILITHIUM2_AVX2_polyvecl_ntt(&s1);
+
+    for (i = 0; i < K; i++) {
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_DILITHIUM2_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_DILITHIUM2_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_DILITHIUM2_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1 */
+        PQCLEAN_DILITHIUM2_AVX2_poly_caddq(&t1);
+        PQCLEAN_DILITHIUM2_AVX2_poly_power2round(&t1, &t0.vec[i], &t1);
+        PQCLEAN_DILITHIUM2_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+
+        /* Check the packed t0 of the secret key against the recomputed one */
+        PQCLEAN_DILITHIUM2_AVX2_polyt0_pack(t0buf, &t0.vec[i]);
+        for (j = 0; j < POLYT0_PACKEDBYTES; ++j) {
+            t0diff |= t0buf[j] ^ skt0[i * POLYT0_PACKEDBYTES + j];
+        }
+    }
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, SEEDBYTES, pk, PQCLEAN_DILITHIUM2_AVX2_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < SEEDBYTES; ++i) {
+        diff |= tr[i] ^ sktr[i];
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(t0buf, sizeof(t0buf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    if (ret == 0 && t0diff) {
+        ret = -2;
+    }
+    if (ret == 0 && diff) {
+        ret = -3;
+    }
+    return ret;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_check_sig
+*
+* Description: Checks that a signature is canonically encoded, i.e. that it
+*              would not be rejected by verification before the challenge is
+*              recomputed.
+*
+* Arguments:   - const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]:
+*                       pointer to signature
+*
+* Returns 0 if the signature is well-formed, -1 if a hint counter is
+* decreasing or unused hint indices are not zero, -2 if the hint indices of a
+* polynomial are not strictly increasing, -3 if there are more than OMEGA
+* hints and -4 if a coefficient of z is outside of (-(GAMMA1 - BETA),
+* GAMMA1 - BETA)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sig)(
+    const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]
+) {
+    unsigned int i, j, k;
+    const uint8_t *hint = sig + SEEDBYTES + L * POLYZ_PACKEDBYTES;
+    poly z;
+
+    /* Decode h, rejecting it like unpack_sig */
+    k = 0;
+    for (i = 0; i < K; ++i) {
+        if (hint[OMEGA + i] > OMEGA) {
+            return -3;
+        }
+        if (hint[OMEGA + i] < k) {
+            return -1;
+        }
+        for (j = k + 1; j < hint[OMEGA + i]; ++j) {
+            if (hint[j] <= hint[j - 1]) {
+                return -2;
+            }
+        }
+        k = hint[OMEGA + i];
+    }
+    for (j = k; j < OMEGA; ++j) {
+        if (hint[j]) {
+            return -1;
+        }
+    }
+
+    /* Check the norm of z */
+    for (i = 0; i < L; ++i) {
+        PQCLEAN_DILITHIUM2_AVX2_polyz_unpack(&z, sig + SEEDBYTES + i * POLYZ_PACKEDBYTES);
+        if (PQCLEAN_DILITHIUM2_AVX2_poly_chknorm(&z, GAMMA1 - BETA)) {
+            return -4;
+        }
+    }
+
+    return 0;
+}
+
+/* Expanded public key: the matrix A, NTT(t1 * 2^D) and tr = H(pk) */
+typedef struct {
+    polyvecl mat[K];
+    polyveck t1;
+    uint8_t tr[SEEDBYTES];
+} prepared_pk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char prepared_pk_size_check[
+    sizeof(prepared_pk) == DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_prepare_pk
+*
+* Description: Expands a public key once for verifying many signatures with
+*              PQCLEAN_DILITHIUM2_AVX2_crypto_sign_verify_prepared.
+*
+* Arguments:   - uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to output prepared public key, aligned to
+*                       32 bytes
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_prepare_pk)(
+    uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)],
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {
+    prepared_pk *p = (prepared_pk *) ppk;
+    unsigned int i;
+
+    for (i = 0; i < K; i++) {
+        PQCLEAN_DILITHIUM2_AVX2_polyt1_unpack(&p->t1.vec[i], pk + SEEDBYTES + i * POLYT1_PACKEDBYTES);
+        PQCLEAN_DILITHIUM2_AVX2_poly_shiftl(&p->t1.vec[i]);
+        PQCLEAN_DILITHIUM2_AVX2_poly_ntt(&p->t1.vec[i]);
+    }
+    PQCLEAN_DILITHIUM2_AVX2_polyvec_matrix_expand(p->mat, pk);
+    shake256(p->tr, SEEDBYTES, pk, PQCLEAN_DILITHIUM2_AVX2_CRYPTO_PUBLICKEYBYTES);
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_verify_prepared
+*
+* Description: Verifies signature with a public key prepared by
+*              PQCLEAN_DILITHIUM2_AVX2_crypto_sign_prepare_pk.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to prepared public key
+*
+* Returns the same values as PQCLEAN_DILITHIUM2_AVX2_crypto_sign_verify
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify_prepared)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]
+) {
+    unsigned int i, j, pos = 0;
+    int znorm = 0;
+    /* PQCLEAN_DILITHIUM2_AVX2_polyw1_pack writes additional 14 bytes */
+    ALIGNED_UINT8(K * POLYW1_PACKEDBYTES + 14) buf;
+    uint8_t mu[CRHBYTES];
+    const uint8_t *hint = sig + SEEDBYTES + L * POLYZ_PACKEDBYTES;
+    const prepared_pk *p = (const prepared_pk *) ppk;
+    polyvecl z;
+    poly c, w1, h;
+    shake256incctx state;
+
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, p->tr, SEEDBYTES);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Expand challenge */
+    PQCLEAN_DILITHIUM2_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_DILITHIUM2_AVX2_poly_ntt(&c);
+
+    /* Unpack z and check its norm, which is reported after the hints */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_DILITHIUM2_AVX2_polyz_unpack(&z.vec[i], sig + SEEDBYTES + i * POLYZ_PACKEDBYTES);
+        znorm |= PQCLEAN_DILITHIUM2_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA);
+        PQCLEAN_DILITHIUM2_AVX2_poly_ntt(&z.vec[i]);
+    }
+
+    for (i = 0; i < K; i++) {
+        /* Compute i-th row of Az - c2^Dt1 */
+        PQCLEAN_DILITHIUM2_AVX2_polyvecl_pointwise_acc_montgomery(&w1, &p->mat[i], &z);
+        PQCLEAN_DILITHIUM2_AVX2_poly_pointwise_montgomery(&h, &c, &p->t1.vec[i]);
+
+        PQCLEAN_DILITHIUM2_AVX2_poly_sub(&w1, &w1, &h);
+        PQCLEAN_DILITHIUM2_AVX2_poly_reduce(&w1);
+        PQCLEAN_DILITHIUM2_AVX2_poly_invntt_tomont(&w1);
+
+        /* Get hint polynomial and reconstruct w1 */
+        memset(h.vec, 0, sizeof(poly));
+        if (hint[OMEGA + i] < pos || hint[OMEGA + i] > OMEGA) {
+            return -1;
+        }
+
+        for (j = pos; j < hint[OMEGA + i]; ++j) {
+            /* Coefficients are ordered for strong unforgeability */
+            if (j > pos && hint[j] <= hint[j - 1]) {
+                return -1;
+            }
+            h.coeffs[hint[j]] = 1;
+        }
+        pos = hint[OMEGA + i];
+
+        PQCLEAN_DILITHIUM2_AVX2_poly_caddq(&w1);
+        PQCLEAN_DILITHIUM2_AVX2_poly_use_hint(&w1, &w1, &h);
+        PQCLEAN_DILITHIUM2_AVX2_polyw1_pack(buf.coeffs + i * POLYW1_PACKEDBYTES, &w1);
+    }
+
+    /* Extra indices are zero for strong unforgeability */
+    for (j = pos; j < OMEGA; ++j) {
+        if (hint[j]) {
+            return -1;
+        }
+    }
+    if (znorm) {
+        return -2;
+    }
+
+    /* Call random oracle and verify challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf.coeffs, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(buf.coeffs, SEEDBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < SEEDBYTES; ++i) {
+        if (buf.coeffs[i] != sig[i]) {
+            return -3;
+        }
+    }
+
+    return 0;
+}
+
+/* Expanded secret key: the matrix A, NTT(s1), NTT(s2), NTT(t0), key and tr */
+typedef struct {
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2;
+    polyveck t0;
+    uint8_t key[SEEDBYTES];
+    uint8_t tr[SEEDBYTES];
+} expanded_sk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char expanded_sk_size_check[
+    sizeof(expanded_sk) == DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_expand_sk
+*
+* Description: Expands a secret key once for computing many signatures with
+*              PQCLEAN_DILITHIUM2_AVX2_crypto_sign_signature_expanded_randomized. The
+*              expanded secret key must be cleared by the caller.
+*
+* Arguments:   - uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to output expanded secret key, aligned to
+*                       32 bytes
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_expand_sk)(
+    uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    expanded_sk *e = (expanded_sk *) esk;
+    uint8_t rho[SEEDBYTES];
+
+    PQCLEAN_DILITHIUM2_AVX2_unpack_sk(rho, e->tr, e->key, &e->t0, &e->s1, &e->s2, sk);
+    PQCLEAN_DILITHIUM2_AVX2_polyvec_matrix_expand(e->mat, rho);
+    PQCLEAN_DILITHIUM2_AVX2_polyvecl_ntt(&e->s1);
+    PQCLEAN_DILITHIUM2_AVX2_polyveck_ntt(&e->s2);
+    PQCLEAN_DILITHIUM2_AVX2_polyveck_ntt(&e->t0);
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_signature_expanded_randomized
+*
+* Description: Computes signature with a secret key expanded by
+*              PQCLEAN_DILITHIUM2_AVX2_crypto_sign_expand_sk.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* rnd: NULL for deterministic signing, or
+*                       pointer to RNDBYTES bytes of randomness mixed into
+*                       the derivation of rhoprime
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_randomized)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {
+    unsigned int i, n, pos;
+    const expanded_sk *e = (const expanded_sk *) esk;
+    uint8_t seedbuf[SEEDBYTES + 2 * CRHBYTES];
+    uint8_t *key, *mu, *rhoprime;
+    const uint8_t *tr = e->tr;
+    uint8_t hintbuf[N];
+    uint8_t *hint = sig + SEEDBYTES + L * POLYZ_PACKEDBYTES;
+    uint64_t nonce = 0;
+    polyvecl z;
+    polyveck w1;
+    poly c, tmp;
+    union {
+        polyvecl y;
+        polyveck w0;
+    } tmpv;
+    shake256incctx state;
+
+    key = seedbuf;
+    mu = key + SEEDBYTES;
+    rhoprime = mu + CRHBYTES;
+    memcpy(key, e->key, SEEDBYTES);
+
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, tr, SEEDBYTES);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    if (rnd == NULL) {
+        /* Deterministic signing */
+        shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    } else {
+        /* Compute rhoprime = CRH(key, rnd, mu) */
+        shake256_inc_init(&state);
+        shake256_inc_absorb(&state, key, SEEDBYTES);
+        shake256_inc_absorb(&state, rnd, RNDBYTES);
+        shake256_inc_absorb(&state, mu, CRHBYTES);
+        shake256_inc_finalize(&state);
+        shake256_inc_squeeze(rhoprime, CRHBYTES, &state);
+        shake256_inc_ctx_release(&state);
+    }
+
+rej:
+    /* Sample intermediate vector y */
+    PQCLEAN_DILITHIUM2_AVX2_poly_uniform_gamma1_4x(&z.vec[0], &z.vec[1], &z.vec[2], &z.vec[3],
+            rhoprime, nonce, nonce + 1, nonce + 2, nonce + 3);
+    nonce += 4;
+
+    /* Matrix-vector product */
+    tmpv.y = z;
+    PQCLEAN_DILITHIUM2_AVX2_polyvecl_ntt(&tmpv.y);
+    PQCLEAN_DILITHIUM2_AVX2_polyvec_matrix_pointwise_montgomery(&w1, e->mat, &tmpv.y);
+    PQCLEAN_DILITHIUM2_AVX2_polyveck_invntt_tomont(&w1);
+
+    /* Decompose w and call the random oracle */
+    PQCLEAN_DILITHIUM2_AVX2_polyveck_caddq(&w1);
+    PQCLEAN_DILITHIUM2_AVX2_polyveck_decompose(&w1, &tmpv.w0, &w1);
+    PQCLEAN_DILITHIUM2_AVX2_polyveck_pack_w1(sig, &w1);
+
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, SEEDBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    PQCLEAN_DILITHIUM2_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_DILITHIUM2_AVX2_poly_ntt(&c);
+
+    /* Compute z, reject if it reveals secret */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_DILITHIUM2_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->s1.vec[i]);
+        PQCLEAN_DILITHIUM2_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_DILITHIUM2_AVX2_poly_add(&z.vec[i], &z.vec[i], &tmp);
+        PQCLEAN_DILITHIUM2_AVX2_poly_reduce(&z.vec[i]);
+        if (PQCLEAN_DILITHIUM2_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA)) {
+            goto rej;
+        }
+    }
+
+    /* Zero hint vector in signature */
+    pos = 0;
+    memset(hint, 0, OMEGA);
+
+    for (i = 0; i < K; i++) {
+        /* Check that subtracting cs2 does not change high bits of w and low bits
+         * do not reveal secret information */
+        PQCLEAN_DILITHIUM2_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->s2.vec[i]);
+        PQCLEAN_DILITHIUM2_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_DILITHIUM2_AVX2_poly_sub(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        PQCLEAN_DILITHIUM2_AVX2_poly_reduce(&tmpv.w0.vec[i]);
+        if (PQCLEAN_DILITHIUM2_AVX2_poly_chknorm(&tmpv.w0.vec[i], GAMMA2 - BETA)) {
+            goto rej;
+        }
+
+        /* Compute hints */
+        PQCLEAN_DILITHIUM2_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->t0.vec[i]);
+        PQCLEAN_DILITHIUM2_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_DILITHIUM2_AVX2_poly_reduce(&tmp);
+        if (PQCLEAN_DILITHIUM2_AVX2_poly_chknorm(&tmp, GAMMA2)) {
+            goto rej;
+        }
+
+        PQCLEAN_DILITHIUM2_AVX2_poly_add(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        n = PQCLEAN_DILITHIUM2_AVX2_poly_make_hint(hintbuf, &tmpv.w0.vec[i], &w1.vec[i]);
+        if (pos + n > OMEGA) {
+            goto rej;
+        }
+
+        /* Store hints in signature */
+        memcpy(&hint[pos], hintbuf, n);
+        hint[OMEGA + i] = pos = pos + n;
+    }
+
+    /* Pack z into signature */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_DILITHIUM2_AVX2_polyz_pack(sig + SEEDBYTES + i * POLYZ_PACKEDBYTES, &z.vec[i]);
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&tmpv, sizeof(tmpv));
+    pqclean_cleanse(&tmp, sizeof(tmp));
+    pqclean_cleanse(&state, sizeof(state));
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_signature_randomized
 *
 * Description: Computes signature.
 *
@@ -117,14 +685,18 @@ int DILITHIUM_NAMESPACE(crypto_sign_keypair)(
 *                       of CRYPTO_BYTES bytes)
 *              - uint8_t* m:     pointer to message to be signed
 *              - size_t mlen:    length of message
+*              - const uint8_t* rnd: NULL for deterministic signing, or
+*                       pointer to RNDBYTES bytes of randomness mixed into
+*                       the derivation of rhoprime
 *              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
 *                       pointer to bit-packed secret key
 *
 * Returns 0 (success)
 **************************************************/
-int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+int DILITHIUM_NAMESPACE(crypto_sign_signature_randomized)(
     uint8_t* sig,
     const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
     const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
 ) {
     unsigned int i, n, pos;
@@ -157,7 +729,19 @@ int DILITHIUM_NAMESPACE(crypto_sign_signature)(
     shake256_inc_squeeze(mu, CRHBYTES, &state);
     shake256_inc_ctx_release(&state);
 
-    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    if (rnd == NULL) {
+        /* Deterministic signing */
+        shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    } else {
+        /* Compute rhoprime = CRH(key, rnd, mu) */
+        shake256_inc_init(&state);
+        shake256_inc_absorb(&state, key, SEEDBYTES);
+        shake256_inc_absorb(&state, rnd, RNDBYTES);
+        shake256_inc_absorb(&state, mu, CRHBYTES);
+        shake256_inc_finalize(&state);
+        shake256_inc_squeeze(rhoprime, CRHBYTES, &state);
+        shake256_inc_ctx_release(&state);
+    }
 
     /* Expand matrix and transform vectors */
     PQCLEAN_DILITHIUM2_AVX2_polyvec_matrix_expand(mat, rho);
@@ -242,6 +826,14 @@ rej:
         PQCLEAN_DILITHIUM2_AVX2_polyz_pack(sig + SEEDBYTES + i * POLYZ_PACKEDBYTES, &z.vec[i]);
     }
 
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    pqclean_cleanse(&tmpv, sizeof(tmpv));
+    pqclean_cleanse(&tmp, sizeof(tmp));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }
 
@@ -256,7 +848,9 @@ rej:
 *              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
 *                       pointer to bit-packed public key
 *
-* Returns 0 if signature could be verified correctly and -1 otherwise
+* Returns 0 if signature could be verified correctly, -1 if the hints are
+* malformed, -2 if the norm of z is too large and -3 if the challenge does not
+* match
 **************************************************/
 int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     const uint8_t* sig,
@@ -264,6 +858,7 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
 ) {
     unsigned int i, j, pos = 0;
+    int znorm = 0;
     /* PQCLEAN_DILITHIUM2_AVX2_polyw1_pack writes additional 14 bytes */
     ALIGNED_UINT8(K * POLYW1_PACKEDBYTES + 14) buf;
     uint8_t mu[CRHBYTES];
@@ -287,9 +882,10 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     PQCLEAN_DILITHIUM2_AVX2_poly_challenge(&c, sig);
     PQCLEAN_DILITHIUM2_AVX2_poly_ntt(&c);
 
-    /* Unpack z; shortness follows from unpacking */
+    /* Unpack z and check its norm, which is reported after the hints */
     for (i = 0; i < L; i++) {
         PQCLEAN_DILITHIUM2_AVX2_polyz_unpack(&z.vec[i], sig + SEEDBYTES + i * POLYZ_PACKEDBYTES);
+        znorm |= PQCLEAN_DILITHIUM2_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA);
         PQCLEAN_DILITHIUM2_AVX2_poly_ntt(&z.vec[i]);
     }
 
@@ -336,6 +932,9 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
             return -1;
         }
     }
+    if (znorm) {
+        return -2;
+    }
 
     /* Call random oracle and verify PQCLEAN_DILITHIUM2_AVX2_challenge */
     shake256_inc_init(&state);
@@ -346,9 +945,84 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     shake256_inc_ctx_release(&state);
     for (i = 0; i < SEEDBYTES; ++i) {
         if (buf.coeffs[i] != sig[i]) {
-            return -1;
+            return -3;
         }
     }
 
     return 0;
 }
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_signature
+*
+* Description: Computes signature deterministically.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_randomized)(sig, m, mlen, NULL, sk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_signature_expanded
+*
+* Description: Computes signature deterministically with an expanded secret
+*              key.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_randomized)(sig, m, mlen, NULL, esk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_DILITHIUM2_AVX2_crypto_sign_seed_keypair
+*
+* Description: Generates public and private key from a seed, which is
+*              expanded with SHAKE256 like in the reference implementation.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to output private key (allocated
+*                       array of CRYPTO_SECRETKEYBYTES bytes)
+*              - const uint8_t seed[SEEDBYTES]: pointer to seed
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_seed_keypair)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)],
+    const uint8_t seed[SEEDBYTES]
+) {
+    uint8_t seedbuf[2 * SEEDBYTES + CRHBYTES];
+    int ret;
+
+    shake256(seedbuf, 2 * SEEDBYTES + CRHBYTES, seed, SEEDBYTES);
+    ret = DILITHIUM_NAMESPACE(crypto_sign_keypair)(pk, sk, seedbuf);
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    return ret;
+}
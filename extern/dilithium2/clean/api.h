@@ -7,6 +7,8 @@
 #define PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PUBLICKEYBYTES 1312
 #define PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_SECRETKEYBYTES 2528
 #define PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_BYTES 2420
+#define PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PREPAREDPUBLICKEYBYTES 20512
+#define PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_EXPANDEDSECRETKEYBYTES 28736
 #define PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_ALGNAME "Dilithium2"
 
 int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_keypair(
@@ -27,4 +29,60 @@ int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_verify(
     const uint8_t pk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PUBLICKEYBYTES]
 );
 
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_pk_from_sk(
+    uint8_t pk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_check_sk(
+    uint8_t pk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_check_sig(
+    const uint8_t sig[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_BYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_prepare_pk(
+    uint8_t ppk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PREPAREDPUBLICKEYBYTES],
+    const uint8_t pk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_verify_prepared(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t ppk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_expand_sk(
+    uint8_t esk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_EXPANDEDSECRETKEYBYTES],
+    const uint8_t sk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_signature_expanded(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t esk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_signature_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t sk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_signature_expanded_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t esk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_DILITHIUM2_CLEAN_crypto_sign_seed_keypair(
+    uint8_t pk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_DILITHIUM2_CLEAN_CRYPTO_SECRETKEYBYTES],
+    const uint8_t seed[32]
+);
+
 #endif
@@ -0,0 +1,103 @@
+#ifndef PQCLEAN_MLDSA44_AARCH64_SIGN_H
+#define PQCLEAN_MLDSA44_AARCH64_SIGN_H
+#include "params.h"
+#include "poly.h"
+#include "polyvec.h"
+#include <stddef.h>
+#include <stdint.h>
+
+/* Expanded public key of crypto_sign_prepare_pk, aligned to 32 bytes */
+#define PQCLEAN_MLDSA44_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES 20544
+
+/* Expanded secret key of crypto_sign_expand_sk, aligned to 32 bytes */
+#define PQCLEAN_MLDSA44_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES 28768
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_keypair(
+    uint8_t pk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_SECRETKEYBYTES],
+    const uint8_t seed[SEEDBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_signature(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t sk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_verify(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t pk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_pk_from_sk(
+    uint8_t pk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_check_sk(
+    uint8_t pk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_check_sig(
+    const uint8_t sig[PQCLEAN_MLDSA44_AARCH64_CRYPTO_BYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_prepare_pk(
+    uint8_t ppk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES],
+    const uint8_t pk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_verify_prepared(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t ppk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_expand_sk(
+    uint8_t esk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_signature_expanded(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t esk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_signature_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[RNDBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_verify_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t pk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_verify_prepared_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t ppk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA44_AARCH64_crypto_sign_signature_expanded_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[RNDBYTES],
+    const uint8_t esk[PQCLEAN_MLDSA44_AARCH64_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+#endif
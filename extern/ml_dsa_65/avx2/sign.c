@@ -0,0 +1,1115 @@
+#include "align.h"
+#include "cleanse.h"
+#include "fips202.h"
+#include "packing.h"
+#include "params.h"
+#include "poly.h"
+#include "polyvec.h"
+#include "sign.h"
+#include "symmetric.h"
+#include <stdint.h>
+#include <string.h>
+
+static inline void polyvec_matrix_expand_row(polyvecl **row, polyvecl buf[2], const uint8_t rho[SEEDBYTES], unsigned int i) {
+    switch (i) {
+    case 0:
+        PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand_row0(buf, buf + 1, rho);
+        *row = buf;
+        break;
+    case 1:
+        PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand_row1(buf + 1, buf, rho);
+        *row = buf + 1;
+        break;
+    case 2:
+        PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand_row2(buf, buf + 1, rho);
+        *row = buf;
+        break;
+    case 3:
+        PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand_row3(buf + 1, buf, rho);
+        *row = buf + 1;
+        break;
+    case 4:
+        PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand_row4(buf, buf + 1, rho);
+        *row = buf;
+        break;
+    case 5:
+        PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand_row5(buf + 1, buf, rho);
+        *row = buf + 1;
+        break;
+    }
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_keypair
+*
+* Description: Generates public and private key.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to output private key (allocated
+*                       array of CRYPTO_SECRETKEYBYTES bytes)
+*              - const uint8_t seed[SEEDBYTES]:
+*                       pointer to seed xi filled with random bytes;
+*                       needs to live until the function returns
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_keypair)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)],
+    const uint8_t seed[SEEDBYTES]
+) {
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + CRHBYTES];
+    const uint8_t *rho, *rhoprime, *key;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2;
+    poly t1, t0;
+
+    /* Expand seed xi to rho, rhoprime and key, domain separated by K and L */
+    memcpy(seedbuf, seed, SEEDBYTES);
+    seedbuf[SEEDBYTES + 0] = K;
+    seedbuf[SEEDBYTES + 1] = L;
+    shake256(seedbuf, 2 * SEEDBYTES + CRHBYTES, seedbuf, SEEDBYTES + 2);
+    rho = seedbuf;
+    rhoprime = rho + SEEDBYTES;
+    key = rhoprime + CRHBYTES;
+
+    /* Store rho, key */
+    memcpy(pk, rho, SEEDBYTES);
+    memcpy(sk, rho, SEEDBYTES);
+    memcpy(sk + SEEDBYTES, key, SEEDBYTES);
+
+    /* Sample short vectors s1 and s2 */
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_eta_4x(&s1.vec[0], &s1.vec[1], &s1.vec[2], &s1.vec[3], rhoprime, 0, 1, 2, 3);
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_eta_4x(&s1.vec[4], &s2.vec[0], &s2.vec[1], &s2.vec[2], rhoprime, 4, 5, 6, 7);
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_eta_4x(&s2.vec[3], &s2.vec[4], &s2.vec[5], &t0, rhoprime, 8, 9, 10, 11);
+
+    /* Pack secret vectors */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyeta_pack(sk + 2 * SEEDBYTES + TRBYTES + i * POLYETA_PACKEDBYTES, &s1.vec[i]);
+    }
+    for (i = 0; i < K; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyeta_pack(sk + 2 * SEEDBYTES + TRBYTES + (L + i)*POLYETA_PACKEDBYTES, &s2.vec[i]);
+    }
+
+    /* Transform s1 */
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&s1);
+
+
+    for (i = 0; i < K; i++) {
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_MLDSA65_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_MLDSA65_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1, t0 */
+        PQCLEAN_MLDSA65_AVX2_poly_caddq(&t1);
+        PQCLEAN_MLDSA65_AVX2_poly_power2round(&t1, &t0, &t1);
+        PQCLEAN_MLDSA65_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+        PQCLEAN_MLDSA65_AVX2_polyt0_pack(sk + 2 * SEEDBYTES + TRBYTES + (L + K)*POLYETA_PACKEDBYTES + i * POLYT0_PACKEDBYTES, &t0);
+    }
+
+    /* Compute H(rho, t1) and store in secret key */
+    shake256(sk + 2 * SEEDBYTES, TRBYTES, pk, PQCLEAN_MLDSA65_AVX2_CRYPTO_PUBLICKEYBYTES);
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_pk_from_sk
+*
+* Description: Recomputes the public key from a secret key and checks it
+*              against the hash tr stored in the secret key.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if H(pk) equals tr and -1 otherwise; pk is written in both cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_pk_from_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + TRBYTES];
+    uint8_t tr[TRBYTES];
+    uint8_t *rho, *sktr, *key;
+    uint8_t diff = 0;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2, t0;
+    poly t1;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + TRBYTES;
+    PQCLEAN_MLDSA65_AVX2_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Store rho */
+    memcpy(pk, rho, SEEDBYTES);
+
+    /* Transform s1 */
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&s1);
+
+
+    for (i = 0; i < K; i++) {
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_MLDSA65_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_MLDSA65_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1 */
+        PQCLEAN_MLDSA65_AVX2_poly_caddq(&t1);
+        PQCLEAN_MLDSA65_AVX2_poly_power2round(&t1, &t0.vec[i], &t1);
+        PQCLEAN_MLDSA65_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+    }
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, TRBYTES, pk, PQCLEAN_MLDSA65_AVX2_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < TRBYTES; ++i) {
+        diff |= tr[i] ^ sktr[i];
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return diff ? -1 : 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_check_sk
+*
+* Description: Checks that a secret key is consistent and recomputes the
+*              public key from it.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if the secret key is consistent, -1 if a coefficient of s1 or s2
+* is outside of [-ETA, ETA], -2 if t0 does not match the low bits of the
+* recomputed t and -3 if H(pk) does not equal tr; pk is written in all cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i, j;
+    int ret = 0;
+    uint8_t seedbuf[2 * SEEDBYTES + TRBYTES];
+    uint8_t tr[TRBYTES];
+    uint8_t t0buf[POLYT0_PACKEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    const uint8_t *skt0 = sk + PQCLEAN_MLDSA65_AVX2_CRYPTO_SECRETKEYBYTES - K * POLYT0_PACKEDBYTES;
+    uint8_t diff = 0, t0diff = 0;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2, t0;
+    poly t1;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + TRBYTES;
+    PQCLEAN_MLDSA65_AVX2_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Check that the coefficients of s1 and s2 lie in [-ETA, ETA] */
+    if (PQCLEAN_MLDSA65_AVX2_polyvecl_chknorm(&s1, ETA + 1)
+            || PQCLEAN_MLDSA65_AVX2_polyveck_chknorm(&s2, ETA + 1)) {
+        ret = -1;
+    }
+
+    /* Store rho */
+    memcpy(pk, rho, SEEDBYTES);
+
+    /* Transform s1 */
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&s1);
+
+    for (i = 0; i < K; i++) {
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_MLDSA65_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_MLDSA65_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1 */
+        PQCLEAN_MLDSA65_AVX2_poly_caddq(&t1);
+        PQCLEAN_MLDSA65_AVX2_poly_power2round(&t1, &t0.vec[i], &t1);
+        PQCLEAN_MLDSA65_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+
+        /* Check the packed t0 of the secret key against the recomputed one */
+        PQCLEAN_MLDSA65_AVX2_polyt0_pack(t0buf, &t0.vec[i]);
+        for (j = 0; j < POLYT0_PACKEDBYTES; ++j) {
+            t0diff |= t0buf[j] ^ skt0[i * POLYT0_PACKEDBYTES + j];
+        }
+    }
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, TRBYTES, pk, PQCLEAN_MLDSA65_AVX2_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < TRBYTES; ++i) {
+        diff |= tr[i] ^ sktr[i];
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(t0buf, sizeof(t0buf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    if (ret == 0 && t0diff) {
+        ret = -2;
+    }
+    if (ret == 0 && diff) {
+        ret = -3;
+    }
+    return ret;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_check_sig
+*
+* Description: Checks that a signature is canonically encoded, i.e. that it
+*              would not be rejected by verification before the challenge is
+*              recomputed.
+*
+* Arguments:   - const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]:
+*                       pointer to signature
+*
+* Returns 0 if the signature is well-formed, -1 if a hint counter is
+* decreasing or unused hint indices are not zero, -2 if the hint indices of a
+* polynomial are not strictly increasing, -3 if there are more than OMEGA
+* hints and -4 if a coefficient of z is outside of (-(GAMMA1 - BETA),
+* GAMMA1 - BETA)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sig)(
+    const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]
+) {
+    unsigned int i, j, k;
+    const uint8_t *hint = sig + CTILDEBYTES + L * POLYZ_PACKEDBYTES;
+    poly z;
+
+    /* Decode h, rejecting it like unpack_sig */
+    k = 0;
+    for (i = 0; i < K; ++i) {
+        if (hint[OMEGA + i] > OMEGA) {
+            return -3;
+        }
+        if (hint[OMEGA + i] < k) {
+            return -1;
+        }
+        for (j = k + 1; j < hint[OMEGA + i]; ++j) {
+            if (hint[j] <= hint[j - 1]) {
+                return -2;
+            }
+        }
+        k = hint[OMEGA + i];
+    }
+    for (j = k; j < OMEGA; ++j) {
+        if (hint[j]) {
+            return -1;
+        }
+    }
+
+    /* Check the norm of z */
+    for (i = 0; i < L; ++i) {
+        PQCLEAN_MLDSA65_AVX2_polyz_unpack(&z, sig + CTILDEBYTES + i * POLYZ_PACKEDBYTES);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&z, GAMMA1 - BETA)) {
+            return -4;
+        }
+    }
+
+    return 0;
+}
+
+/* Expanded public key: the matrix A, NTT(t1 * 2^D) and tr = H(pk) */
+typedef struct {
+    polyvecl mat[K];
+    polyveck t1;
+    uint8_t tr[TRBYTES];
+} prepared_pk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char prepared_pk_size_check[
+    sizeof(prepared_pk) == DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_prepare_pk
+*
+* Description: Expands a public key once for verifying many signatures with
+*              PQCLEAN_MLDSA65_AVX2_crypto_sign_verify_prepared_internal.
+*
+* Arguments:   - uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to output prepared public key, aligned to
+*                       32 bytes
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_prepare_pk)(
+    uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)],
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {
+    prepared_pk *p = (prepared_pk *) ppk;
+    unsigned int i;
+
+    for (i = 0; i < K; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyt1_unpack(&p->t1.vec[i], pk + SEEDBYTES + i * POLYT1_PACKEDBYTES);
+        PQCLEAN_MLDSA65_AVX2_poly_shiftl(&p->t1.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_ntt(&p->t1.vec[i]);
+    }
+    PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand(p->mat, pk);
+    shake256(p->tr, TRBYTES, pk, PQCLEAN_MLDSA65_AVX2_CRYPTO_PUBLICKEYBYTES);
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_verify_prepared_internal
+*
+* Description: Verifies signature with a public key prepared by
+*              PQCLEAN_MLDSA65_AVX2_crypto_sign_prepare_pk.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t* pre: pointer to prefix of M'
+*              - size_t prelen:  length of prefix
+*              - const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to prepared public key
+*
+* Returns the same values as PQCLEAN_MLDSA65_AVX2_crypto_sign_verify_internal
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify_prepared_internal)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]
+) {
+    unsigned int i, j, pos = 0;
+    int znorm = 0;
+    /* PQCLEAN_MLDSA65_AVX2_polyw1_pack writes additional 14 bytes */
+    ALIGNED_UINT8(K * POLYW1_PACKEDBYTES + 14) buf;
+    uint8_t mu[CRHBYTES];
+    const uint8_t *hint = sig + CTILDEBYTES + L * POLYZ_PACKEDBYTES;
+    const prepared_pk *p = (const prepared_pk *) ppk;
+    polyvecl z;
+    poly c, w1, h;
+    shake256incctx state;
+
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, p->tr, TRBYTES);
+    shake256_inc_absorb(&state, pre, prelen);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Expand challenge */
+    PQCLEAN_MLDSA65_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_MLDSA65_AVX2_poly_ntt(&c);
+
+    /* Unpack z and check its norm, which is reported after the hints */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyz_unpack(&z.vec[i], sig + CTILDEBYTES + i * POLYZ_PACKEDBYTES);
+        znorm |= PQCLEAN_MLDSA65_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA);
+        PQCLEAN_MLDSA65_AVX2_poly_ntt(&z.vec[i]);
+    }
+
+    for (i = 0; i < K; i++) {
+        /* Compute i-th row of Az - c2^Dt1 */
+        PQCLEAN_MLDSA65_AVX2_polyvecl_pointwise_acc_montgomery(&w1, &p->mat[i], &z);
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&h, &c, &p->t1.vec[i]);
+
+        PQCLEAN_MLDSA65_AVX2_poly_sub(&w1, &w1, &h);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&w1);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&w1);
+
+        /* Get hint polynomial and reconstruct w1 */
+        memset(h.vec, 0, sizeof(poly));
+        if (hint[OMEGA + i] < pos || hint[OMEGA + i] > OMEGA) {
+            return -1;
+        }
+
+        for (j = pos; j < hint[OMEGA + i]; ++j) {
+            /* Coefficients are ordered for strong unforgeability */
+            if (j > pos && hint[j] <= hint[j - 1]) {
+                return -1;
+            }
+            h.coeffs[hint[j]] = 1;
+        }
+        pos = hint[OMEGA + i];
+
+        PQCLEAN_MLDSA65_AVX2_poly_caddq(&w1);
+        PQCLEAN_MLDSA65_AVX2_poly_use_hint(&w1, &w1, &h);
+        PQCLEAN_MLDSA65_AVX2_polyw1_pack(buf.coeffs + i * POLYW1_PACKEDBYTES, &w1);
+    }
+
+    /* Extra indices are zero for strong unforgeability */
+    for (j = pos; j < OMEGA; ++j) {
+        if (hint[j]) {
+            return -1;
+        }
+    }
+    if (znorm) {
+        return -2;
+    }
+
+    /* Call random oracle and verify challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf.coeffs, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(buf.coeffs, CTILDEBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < CTILDEBYTES; ++i) {
+        if (buf.coeffs[i] != sig[i]) {
+            return -3;
+        }
+    }
+
+    return 0;
+}
+
+/* Expanded secret key: the matrix A, NTT(s1), NTT(s2), NTT(t0), key and tr */
+typedef struct {
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2;
+    polyveck t0;
+    uint8_t key[SEEDBYTES];
+    uint8_t tr[TRBYTES];
+} expanded_sk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char expanded_sk_size_check[
+    sizeof(expanded_sk) == DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_expand_sk
+*
+* Description: Expands a secret key once for computing many signatures with
+*              PQCLEAN_MLDSA65_AVX2_crypto_sign_signature_expanded_internal. The
+*              expanded secret key must be cleared by the caller.
+*
+* Arguments:   - uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to output expanded secret key, aligned to
+*                       32 bytes
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_expand_sk)(
+    uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    expanded_sk *e = (expanded_sk *) esk;
+    uint8_t rho[SEEDBYTES];
+
+    PQCLEAN_MLDSA65_AVX2_unpack_sk(rho, e->tr, e->key, &e->t0, &e->s1, &e->s2, sk);
+    PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand(e->mat, rho);
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&e->s1);
+    PQCLEAN_MLDSA65_AVX2_polyveck_ntt(&e->s2);
+    PQCLEAN_MLDSA65_AVX2_polyveck_ntt(&e->t0);
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_signature_expanded_internal
+*
+* Description: Computes signature with a secret key expanded by
+*              PQCLEAN_MLDSA65_AVX2_crypto_sign_expand_sk.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* pre: pointer to prefix of M'
+*              - size_t prelen:  length of prefix
+*              - const uint8_t rnd[RNDBYTES]: randomness mixed into the
+*                       derivation of rhoprime, all zero for deterministic
+*                       signing
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_internal)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[RNDBYTES],
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {
+    unsigned int i, n, pos;
+    const expanded_sk *e = (const expanded_sk *) esk;
+    uint8_t seedbuf[SEEDBYTES + RNDBYTES + 2 * CRHBYTES];
+    uint8_t *key, *rndbuf, *mu, *rhoprime;
+    const uint8_t *tr = e->tr;
+    uint8_t hintbuf[N];
+    uint8_t *hint = sig + CTILDEBYTES + L * POLYZ_PACKEDBYTES;
+    uint64_t nonce = 0;
+    polyvecl z;
+    polyveck w1;
+    poly c, tmp;
+    union {
+        polyvecl y;
+        polyveck w0;
+    } tmpv;
+    shake256incctx state;
+
+    key = seedbuf;
+    rndbuf = key + SEEDBYTES;
+    mu = rndbuf + RNDBYTES;
+    rhoprime = mu + CRHBYTES;
+    memcpy(key, e->key, SEEDBYTES);
+
+    /* Compute CRH(tr, pre, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, tr, TRBYTES);
+    shake256_inc_absorb(&state, pre, prelen);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Compute rhoprime = CRH(key, rnd, mu) */
+    memcpy(rndbuf, rnd, RNDBYTES);
+    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + RNDBYTES + CRHBYTES);
+
+rej:
+    /* Sample intermediate vector y */
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_gamma1_4x(&z.vec[0], &z.vec[1], &z.vec[2], &z.vec[3],
+            rhoprime, nonce, nonce + 1, nonce + 2, nonce + 3);
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_gamma1(&z.vec[4], rhoprime, nonce + 4);
+    nonce += 5;
+
+    /* Matrix-vector product */
+    tmpv.y = z;
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&tmpv.y);
+    PQCLEAN_MLDSA65_AVX2_polyvec_matrix_pointwise_montgomery(&w1, e->mat, &tmpv.y);
+    PQCLEAN_MLDSA65_AVX2_polyveck_invntt_tomont(&w1);
+
+    /* Decompose w and call the random oracle */
+    PQCLEAN_MLDSA65_AVX2_polyveck_caddq(&w1);
+    PQCLEAN_MLDSA65_AVX2_polyveck_decompose(&w1, &tmpv.w0, &w1);
+    PQCLEAN_MLDSA65_AVX2_polyveck_pack_w1(sig, &w1);
+
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, CTILDEBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    PQCLEAN_MLDSA65_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_MLDSA65_AVX2_poly_ntt(&c);
+
+    /* Compute z, reject if it reveals secret */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->s1.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_add(&z.vec[i], &z.vec[i], &tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&z.vec[i]);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA)) {
+            goto rej;
+        }
+    }
+
+    /* Zero hint vector in signature */
+    pos = 0;
+    memset(hint, 0, OMEGA);
+
+    for (i = 0; i < K; i++) {
+        /* Check that subtracting cs2 does not change high bits of w and low bits
+         * do not reveal secret information */
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->s2.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_sub(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&tmpv.w0.vec[i]);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&tmpv.w0.vec[i], GAMMA2 - BETA)) {
+            goto rej;
+        }
+
+        /* Compute hints */
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->t0.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&tmp);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&tmp, GAMMA2)) {
+            goto rej;
+        }
+
+        PQCLEAN_MLDSA65_AVX2_poly_add(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        n = PQCLEAN_MLDSA65_AVX2_poly_make_hint(hintbuf, &tmpv.w0.vec[i], &w1.vec[i]);
+        if (pos + n > OMEGA) {
+            goto rej;
+        }
+
+        /* Store hints in signature */
+        memcpy(&hint[pos], hintbuf, n);
+        hint[OMEGA + i] = pos = pos + n;
+    }
+
+    /* Pack z into signature */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyz_pack(sig + CTILDEBYTES + i * POLYZ_PACKEDBYTES, &z.vec[i]);
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&tmpv, sizeof(tmpv));
+    pqclean_cleanse(&tmp, sizeof(tmp));
+    pqclean_cleanse(&state, sizeof(state));
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_signature_internal
+*
+* Description: Computes signature.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* pre: pointer to prefix of M'
+*              - size_t prelen:  length of prefix
+*              - const uint8_t rnd[RNDBYTES]: randomness mixed into the
+*                       derivation of rhoprime, all zero for deterministic
+*                       signing
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_internal)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[RNDBYTES],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    unsigned int i, n, pos;
+    uint8_t seedbuf[2 * SEEDBYTES + TRBYTES + RNDBYTES + 2 * CRHBYTES];
+    uint8_t *rho, *tr, *key, *rndbuf, *mu, *rhoprime;
+    uint8_t hintbuf[N];
+    uint8_t *hint = sig + CTILDEBYTES + L * POLYZ_PACKEDBYTES;
+    uint64_t nonce = 0;
+    polyvecl mat[K], s1, z;
+    polyveck t0, s2, w1;
+    poly c, tmp;
+    union {
+        polyvecl y;
+        polyveck w0;
+    } tmpv;
+    shake256incctx state;
+
+    rho = seedbuf;
+    tr = rho + SEEDBYTES;
+    key = tr + TRBYTES;
+    rndbuf = key + SEEDBYTES;
+    mu = rndbuf + RNDBYTES;
+    rhoprime = mu + CRHBYTES;
+    PQCLEAN_MLDSA65_AVX2_unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
+
+    /* Compute CRH(tr, pre, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, tr, TRBYTES);
+    shake256_inc_absorb(&state, pre, prelen);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Compute rhoprime = CRH(key, rnd, mu) */
+    memcpy(rndbuf, rnd, RNDBYTES);
+    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + RNDBYTES + CRHBYTES);
+
+    /* Expand matrix and transform vectors */
+    PQCLEAN_MLDSA65_AVX2_polyvec_matrix_expand(mat, rho);
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&s1);
+    PQCLEAN_MLDSA65_AVX2_polyveck_ntt(&s2);
+    PQCLEAN_MLDSA65_AVX2_polyveck_ntt(&t0);
+
+
+rej:
+    /* Sample intermediate vector y */
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_gamma1_4x(&z.vec[0], &z.vec[1], &z.vec[2], &z.vec[3],
+            rhoprime, nonce, nonce + 1, nonce + 2, nonce + 3);
+    PQCLEAN_MLDSA65_AVX2_poly_uniform_gamma1(&z.vec[4], rhoprime, nonce + 4);
+    nonce += 5;
+
+    /* Matrix-vector product */
+    tmpv.y = z;
+    PQCLEAN_MLDSA65_AVX2_polyvecl_ntt(&tmpv.y);
+    PQCLEAN_MLDSA65_AVX2_polyvec_matrix_pointwise_montgomery(&w1, mat, &tmpv.y);
+    PQCLEAN_MLDSA65_AVX2_polyveck_invntt_tomont(&w1);
+
+    /* Decompose w and call the random oracle */
+    PQCLEAN_MLDSA65_AVX2_polyveck_caddq(&w1);
+    PQCLEAN_MLDSA65_AVX2_polyveck_decompose(&w1, &tmpv.w0, &w1);
+    PQCLEAN_MLDSA65_AVX2_polyveck_pack_w1(sig, &w1);
+
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, CTILDEBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    PQCLEAN_MLDSA65_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_MLDSA65_AVX2_poly_ntt(&c);
+
+    /* Compute z, reject if it reveals secret */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&tmp, &c, &s1.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_add(&z.vec[i], &z.vec[i], &tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&z.vec[i]);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA)) {
+            goto rej;
+        }
+    }
+
+    /* Zero hint vector in signature */
+    pos = 0;
+    memset(hint, 0, OMEGA);
+
+    for (i = 0; i < K; i++) {
+        /* Check that subtracting cs2 does not change high bits of w and low bits
+         * do not reveal secret information */
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&tmp, &c, &s2.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_sub(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&tmpv.w0.vec[i]);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&tmpv.w0.vec[i], GAMMA2 - BETA)) {
+            goto rej;
+        }
+
+        /* Compute hints */
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&tmp, &c, &t0.vec[i]);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&tmp);
+        if (PQCLEAN_MLDSA65_AVX2_poly_chknorm(&tmp, GAMMA2)) {
+            goto rej;
+        }
+
+        PQCLEAN_MLDSA65_AVX2_poly_add(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        n = PQCLEAN_MLDSA65_AVX2_poly_make_hint(hintbuf, &tmpv.w0.vec[i], &w1.vec[i]);
+        if (pos + n > OMEGA) {
+            goto rej;
+        }
+
+        /* Store hints in signature */
+        memcpy(&hint[pos], hintbuf, n);
+        hint[OMEGA + i] = pos = pos + n;
+    }
+
+    /* Pack z into signature */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyz_pack(sig + CTILDEBYTES + i * POLYZ_PACKEDBYTES, &z.vec[i]);
+    }
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    pqclean_cleanse(&tmpv, sizeof(tmpv));
+    pqclean_cleanse(&tmp, sizeof(tmp));
+    pqclean_cleanse(&state, sizeof(state));
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_verify_internal
+*
+* Description: Verifies signature.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t* pre: pointer to prefix of M'
+*              - size_t prelen:  length of prefix
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 if signature could be verified correctly, -1 if the hints are
+* malformed, -2 if the norm of z is too large and -3 if the challenge does not
+* match
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify_internal)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {
+    unsigned int i, j, pos = 0;
+    int znorm = 0;
+    /* PQCLEAN_MLDSA65_AVX2_polyw1_pack writes additional 14 bytes */
+    ALIGNED_UINT8(K * POLYW1_PACKEDBYTES + 14) buf;
+    uint8_t mu[CRHBYTES];
+    const uint8_t *hint = sig + CTILDEBYTES + L * POLYZ_PACKEDBYTES;
+    polyvecl rowbuf[2];
+    polyvecl *row = rowbuf;
+    polyvecl z;
+    poly c, w1, h;
+    shake256incctx state;
+
+    /* Compute CRH(H(rho, t1), msg) */
+    shake256(mu, TRBYTES, pk, PQCLEAN_MLDSA65_AVX2_CRYPTO_PUBLICKEYBYTES);
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, TRBYTES);
+    shake256_inc_absorb(&state, pre, prelen);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Expand PQCLEAN_MLDSA65_AVX2_challenge */
+    PQCLEAN_MLDSA65_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_MLDSA65_AVX2_poly_ntt(&c);
+
+    /* Unpack z and check its norm, which is reported after the hints */
+    for (i = 0; i < L; i++) {
+        PQCLEAN_MLDSA65_AVX2_polyz_unpack(&z.vec[i], sig + CTILDEBYTES + i * POLYZ_PACKEDBYTES);
+        znorm |= PQCLEAN_MLDSA65_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA);
+        PQCLEAN_MLDSA65_AVX2_poly_ntt(&z.vec[i]);
+    }
+
+
+    for (i = 0; i < K; i++) {
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, pk, i);
+
+        /* Compute i-th row of Az - c2^Dt1 */
+        PQCLEAN_MLDSA65_AVX2_polyvecl_pointwise_acc_montgomery(&w1, row, &z);
+
+        PQCLEAN_MLDSA65_AVX2_polyt1_unpack(&h, pk + SEEDBYTES + i * POLYT1_PACKEDBYTES);
+        PQCLEAN_MLDSA65_AVX2_poly_shiftl(&h);
+        PQCLEAN_MLDSA65_AVX2_poly_ntt(&h);
+        PQCLEAN_MLDSA65_AVX2_poly_pointwise_montgomery(&h, &c, &h);
+
+        PQCLEAN_MLDSA65_AVX2_poly_sub(&w1, &w1, &h);
+        PQCLEAN_MLDSA65_AVX2_poly_reduce(&w1);
+        PQCLEAN_MLDSA65_AVX2_poly_invntt_tomont(&w1);
+
+        /* Get hint polynomial and reconstruct w1 */
+        memset(h.vec, 0, sizeof(poly));
+        if (hint[OMEGA + i] < pos || hint[OMEGA + i] > OMEGA) {
+            return -1;
+        }
+
+        for (j = pos; j < hint[OMEGA + i]; ++j) {
+            /* Coefficients are ordered for strong unforgeability */
+            if (j > pos && hint[j] <= hint[j - 1]) {
+                return -1;
+            }
+            h.coeffs[hint[j]] = 1;
+        }
+        pos = hint[OMEGA + i];
+
+        PQCLEAN_MLDSA65_AVX2_poly_caddq(&w1);
+        PQCLEAN_MLDSA65_AVX2_poly_use_hint(&w1, &w1, &h);
+        PQCLEAN_MLDSA65_AVX2_polyw1_pack(buf.coeffs + i * POLYW1_PACKEDBYTES, &w1);
+    }
+
+    /* Extra indices are zero for strong unforgeability */
+    for (j = pos; j < OMEGA; ++j) {
+        if (hint[j]) {
+            return -1;
+        }
+    }
+    if (znorm) {
+        return -2;
+    }
+
+    /* Call random oracle and verify PQCLEAN_MLDSA65_AVX2_challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf.coeffs, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(buf.coeffs, CTILDEBYTES, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < CTILDEBYTES; ++i) {
+        if (buf.coeffs[i] != sig[i]) {
+            return -3;
+        }
+    }
+
+    return 0;
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_signature
+*
+* Description: Computes signature, using the message formatting
+*              M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success) or -1 (context string too long)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {
+    uint8_t pre[2 + 255];
+    uint8_t rnd[RNDBYTES];
+
+    if (ctxlen > 255) {
+        return -1;
+    }
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    /* Deterministic signing: rnd is all zero */
+    memset(rnd, 0, RNDBYTES);
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_internal)(sig, m, mlen, pre, 2 + ctxlen, rnd, sk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_verify
+*
+* Description: Verifies signature, using the message formatting
+*              M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 if signature could be verified correctly, -4 if the context
+* string is too long and otherwise the failure codes of
+* PQCLEAN_MLDSA65_AVX2_crypto_sign_verify_internal
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {
+    uint8_t pre[2 + 255];
+
+    if (ctxlen > 255) {
+        return -4;
+    }
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    return DILITHIUM_NAMESPACE(crypto_sign_verify_internal)(sig, m, mlen, pre, 2 + ctxlen, pk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_verify_prepared
+*
+* Description: Verifies signature with a prepared public key, using the
+*              message formatting M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to prepared public key
+*
+* Returns the same values as PQCLEAN_MLDSA65_AVX2_crypto_sign_verify
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify_prepared)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]
+) {
+    uint8_t pre[2 + 255];
+
+    if (ctxlen > 255) {
+        return -4;
+    }
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    return DILITHIUM_NAMESPACE(crypto_sign_verify_prepared_internal)(sig, m, mlen, pre, 2 + ctxlen, ppk);
+}
+
+/*************************************************
+* Name:        PQCLEAN_MLDSA65_AVX2_crypto_sign_signature_expanded
+*
+* Description: Computes signature with an expanded secret key, using the
+*              message formatting M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success) or -1 (context string too long)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {
+    uint8_t pre[2 + 255];
+    uint8_t rnd[RNDBYTES];
+
+    if (ctxlen > 255) {
+        return -1;
+    }
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    /* Deterministic signing: rnd is all zero */
+    memset(rnd, 0, RNDBYTES);
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_internal)(sig, m, mlen, pre, 2 + ctxlen, rnd, esk);
+}
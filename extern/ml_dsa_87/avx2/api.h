@@ -0,0 +1,102 @@
+#ifndef PQCLEAN_MLDSA87_AVX2_API_H
+#define PQCLEAN_MLDSA87_AVX2_API_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES 2592
+#define PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES 4896
+#define PQCLEAN_MLDSA87_AVX2_CRYPTO_BYTES 4627
+#define PQCLEAN_MLDSA87_AVX2_CRYPTO_PREPAREDPUBLICKEYBYTES 65600
+#define PQCLEAN_MLDSA87_AVX2_CRYPTO_EXPANDEDSECRETKEYBYTES 80992
+#define PQCLEAN_MLDSA87_AVX2_CRYPTO_ALGNAME "ML-DSA-87"
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_keypair(
+    uint8_t pk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES],
+    const uint8_t seed[32]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_signature(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t sk[PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_verify(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t pk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_pk_from_sk(
+    uint8_t pk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_check_sk(
+    uint8_t pk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_check_sig(
+    const uint8_t sig[PQCLEAN_MLDSA87_AVX2_CRYPTO_BYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_prepare_pk(
+    uint8_t ppk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PREPAREDPUBLICKEYBYTES],
+    const uint8_t pk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_verify_prepared(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t ppk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_expand_sk(
+    uint8_t esk[PQCLEAN_MLDSA87_AVX2_CRYPTO_EXPANDEDSECRETKEYBYTES],
+    const uint8_t sk[PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_signature_expanded(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t esk[PQCLEAN_MLDSA87_AVX2_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_signature_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[32],
+    const uint8_t sk[PQCLEAN_MLDSA87_AVX2_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_verify_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t pk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_verify_prepared_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t ppk[PQCLEAN_MLDSA87_AVX2_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_MLDSA87_AVX2_crypto_sign_signature_expanded_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[32],
+    const uint8_t esk[PQCLEAN_MLDSA87_AVX2_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+#endif
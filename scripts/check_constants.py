@@ -9,20 +9,21 @@ def main():
         spec = json.load(json_file)
 
     for param_set in spec["parameter_sets"]:
-        level = param_set["security_level"]
+        name = param_set["name"]
+        ns = param_set["namespace"]
         publickey_bytes = param_set["publickey_bytes"]
         secretkey_bytes = param_set["secretkey_bytes"]
         signature_bytes = param_set["signature_bytes"]
         for impl in param_set["implementations"]:
             assert impl in ["clean", "avx2", "aarch64"]
             IMPL = impl.upper()
-            cmd = ["bindgen", f"extern/dilithium{level}/{impl}/api.h"]
+            cmd = ["bindgen", f"extern/{name}/{impl}/api.h"]
             cmd.extend(options)
             result = subprocess.run(cmd, check = True, text = True, capture_output = True)
             out = result.stdout
-            m_publickey = re.search(f"const\s+PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_PUBLICKEYBYTES\s*:\s*u32\s*=\s*([0-9]+)\s*;", out)
-            m_secretkey = re.search(f"const\s+PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_SECRETKEYBYTES\s*:\s*u32\s*=\s*([0-9]+)\s*;", out)
-            m_signature = re.search(f"const\s+PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_BYTES\s*:\s*u32\s*=\s*([0-9]+)\s*;", out)
+            m_publickey = re.search(f"const\s+PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES\s*:\s*u32\s*=\s*([0-9]+)\s*;", out)
+            m_secretkey = re.search(f"const\s+PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES\s*:\s*u32\s*=\s*([0-9]+)\s*;", out)
+            m_signature = re.search(f"const\s+PQCLEAN_{ns}_{IMPL}_CRYPTO_BYTES\s*:\s*u32\s*=\s*([0-9]+)\s*;", out)
             assert int(m_publickey.group(1)) == publickey_bytes
             assert int(m_secretkey.group(1)) == secretkey_bytes
             assert int(m_signature.group(1)) == signature_bytes
@@ -10,23 +10,225 @@ def main():
     with open("scripts/dilithium.json", 'r') as json_file:
         spec = json.load(json_file)
 
+    ffi_mods = """\
+#[cfg(test)]
+mod stack_check;
+"""
+
     for param_set in spec["parameter_sets"]:
-        level = param_set["security_level"]
+        name = param_set["name"]
+        ns = param_set["namespace"]
+        fips204 = param_set["fips204"]
+        keygen_random_bytes = param_set["keygen_random_bytes"]
         publickey_bytes = param_set["publickey_bytes"]
         secretkey_bytes = param_set["secretkey_bytes"]
         signature_bytes = param_set["signature_bytes"]
+        prepared_publickey_bytes = param_set["prepared_publickey_bytes"]
+        expanded_secretkey_bytes = param_set["expanded_secretkey_bytes"]
+        level = param_set["security_level"]
+        # number of hint counters and length of the challenge seed, which are laid
+        # out at the end and start of a signature
+        k = {2: 4, 3: 6, 5: 8}[level]
+        # dimension of s1, bytes per packed polynomial of s1 and s2, and length of
+        # the public key hash `tr`, for locating secrets in the secret key
+        l = {2: 4, 3: 5, 5: 7}[level]
+        polyeta_packed_bytes = {2: 96, 3: 128, 5: 96}[level]
+        tr_bytes = 64 if fips204 else 32
+        ctilde_bytes = {2: 32, 3: 48, 5: 64}[level] if fips204 else 32
+
+        ffi_mods += f"""\
+#[cfg(feature = "{name}")]
+pub mod {name};
+"""
 
         rust = f"""\
 pub const PUBLICKEYBYTES: usize = {publickey_bytes};
 pub const SECRETKEYBYTES: usize = {secretkey_bytes};
 pub const SIGNATUREBYTES: usize = {signature_bytes};
-use cty::{{c_int, size_t}};
+pub const KEYGENRANDOMBYTES: usize = {keygen_random_bytes};
+pub const RNDBYTES: usize = 32;
+pub const SEEDBYTES: usize = 32;
+pub const PREPAREDPUBLICKEYBYTES: usize = {prepared_publickey_bytes};
+pub const EXPANDEDSECRETKEYBYTES: usize = {expanded_secretkey_bytes};
+"""
+        if fips204:
+            rust += """\
+pub const CONTEXTMAXBYTES: usize = 255;
+"""
+        rust += """\
+use cty::{c_int, size_t};
+
+/// Public key expanded by `crypto_sign_prepare_pk`, aligned as required by the
+/// C implementations. Its contents depend on the implementation that prepared it.
+#[derive(Clone)]
+#[repr(C, align(32))]
+pub struct PreparedPublicKeyBuf(pub [u8; PREPAREDPUBLICKEYBYTES]);
+
+/// Secret key expanded by `crypto_sign_expand_sk`, aligned as required by the
+/// C implementations. Its contents depend on the implementation that expanded
+/// it, and it must be cleared by the owner.
+#[repr(C, align(32))]
+pub struct ExpandedSecretKeyBuf(pub [u8; EXPANDEDSECRETKEYBYTES]);
+"""
+        if fips204:
+            keygen_domain = f"""
+    hasher.update([{k}, {l}]);"""
+            message_prefix = """
+    hasher.update([0, 0]);"""
+            rnd = """
+    hasher.update([0; RNDBYTES]);"""
+        else:
+            keygen_domain = ""
+            message_prefix = ""
+            rnd = ""
+        eta, eta_bits = {2: (2, 3), 3: (4, 4), 5: (2, 3)}[level]
+        s1_offset = 2 * 32 + tr_bytes
+        s2_offset = s1_offset + l * polyeta_packed_bytes
+        t0_offset = s2_offset + k * polyeta_packed_bytes + (k - 1) * 416
+        rust += f"""
+/// Secrets which the implementations must not leave behind on the stack, for the
+/// keypair from a seed and the deterministic signature of a message. They are
+/// derived here rather than by the C code under test.
+#[cfg(test)]
+struct StackNeedles {{
+    key: [u8; 32],
+    keygen_rhoprime: [u8; 32],
+    signing_rhoprime: [u8; 32],
+    packed_s1: [u8; 32],
+    packed_s2: [u8; 32],
+    s1: [u8; 1024],
+    s2: [u8; 1024],
+    t0: [u8; 32],
+}}
+
+#[cfg(test)]
+impl StackNeedles {{
+    fn new(seed: &[u8; SEEDBYTES], sk: &[u8; SECRETKEYBYTES], msg: &[u8]) -> Self {{
+        use crate::prehash::Shake256;
+
+        let part = |offset: usize| -> [u8; 32] {{ sk[offset..offset + 32].try_into().unwrap() }};
+        // `rho`, then the signing seed `key` and the public key hash `tr`
+        let key = part(32);
+        let tr = &sk[64..{s1_offset}];
+
+        // `rhoprime` follows `rho` in the expanded seed
+        let mut hasher = Shake256::new();
+        hasher.update(seed);{keygen_domain}
+        let keygen_rhoprime = hasher.finalize()[32..64].try_into().unwrap();
+
+        let mut hasher = Shake256::new();
+        hasher.update(tr);{message_prefix}
+        hasher.update(msg);
+        let mu = hasher.finalize();
+        let mut hasher = Shake256::new();
+        hasher.update(key);{rnd}
+        hasher.update(mu);
+        let signing_rhoprime = hasher.finalize()[..32].try_into().unwrap();
+
+        // the whole first polynomials of `s1` and `s2`, as their coefficients are
+        // too small to tell apart from other data in shorter runs
+        let unpack_eta = |offset: usize| {{
+            let mut coefficients = [0; 1024];
+            for (i, coefficient) in coefficients.chunks_exact_mut(4).enumerate() {{
+                let bit = offset * 8 + {eta_bits} * i;
+                let bits = u16::from_le_bytes([sk[bit / 8], sk[bit / 8 + 1]]) >> (bit % 8);
+                let value = {eta} - (bits & {(1 << eta_bits) - 1}) as i32;
+                coefficient.copy_from_slice(&value.to_ne_bytes());
+            }}
+            coefficients
+        }};
+
+        // the first coefficients of the last polynomial of `t0`, which outlives the
+        // others in the implementations that compute `t0` row by row
+        let mut packed = [0; 16];
+        packed[..13].copy_from_slice(&sk[{t0_offset}..{t0_offset + 13}]);
+        let packed = u128::from_le_bytes(packed);
+        let mut t0 = [0; 32];
+        for (i, coefficient) in t0.chunks_exact_mut(4).enumerate() {{
+            let value = (1 << 12) - ((packed >> (13 * i)) & 0x1fff) as i32;
+            coefficient.copy_from_slice(&value.to_ne_bytes());
+        }}
+
+        Self {{
+            key,
+            keygen_rhoprime,
+            signing_rhoprime,
+            packed_s1: part({s1_offset}),
+            packed_s2: part({s2_offset}),
+            s1: unpack_eta({s1_offset}),
+            s2: unpack_eta({s2_offset}),
+            t0,
+        }}
+    }}
+
+    fn named(&self) -> [(&'static str, &[u8]); 8] {{
+        [
+            ("key", &self.key),
+            ("rhoprime of keygen", &self.keygen_rhoprime),
+            ("rhoprime of signing", &self.signing_rhoprime),
+            ("packed s1", &self.packed_s1),
+            ("packed s2", &self.packed_s2),
+            ("s1", &self.s1),
+            ("s2", &self.s2),
+            ("t0", &self.t0),
+        ]
+    }}
+}}
+"""
+
+        if fips204:
+            keypair_random_ffi_param = "seed: *const [u8; KEYGENRANDOMBYTES]"
+            keypair_random_arg = "random as *const _"
+            ctx_ffi_params = """
+            ctx: *const u8,
+            ctxlen: size_t,"""
+            ctx_args = """
+                [].as_ptr(),
+                0,"""
+            sign_verify_doc = """\
+    /// Uses the empty context string.
+    ///
+"""
+            verify_prepared_doc = """\
+    /// Verify with a prepared public key, using the empty context string. Returns
+    /// the same values as [`crypto_sign_verify`].
+    ///
+"""
+            sign_expanded_doc = """\
+    /// Sign with an expanded secret key, using the empty context string.
+    ///
+"""
+            verify_doc = """\
+    /// Uses the empty context string. Returns 0 if the signature is valid, -1 if
+    /// its hints are malformed, -2 if `z` is out of range and -3 if the challenge
+    /// does not match.
+    ///
+"""
+        else:
+            keypair_random_ffi_param = "random: *mut [u8; KEYGENRANDOMBYTES]"
+            keypair_random_arg = "random as *mut _"
+            ctx_ffi_params = ""
+            ctx_args = ""
+            sign_verify_doc = ""
+            verify_prepared_doc = """\
+    /// Verify with a prepared public key. Returns the same values as
+    /// [`crypto_sign_verify`].
+    ///
+"""
+            sign_expanded_doc = """\
+    /// Sign with an expanded secret key.
+    ///
+"""
+            verify_doc = """\
+    /// Returns 0 if the signature is valid, -1 if its hints are malformed, -2 if
+    /// `z` is out of range and -3 if the challenge does not match.
+    ///
 """
 
         for impl in param_set["implementations"]:
             assert impl in ["clean", "avx2", "aarch64"]
             IMPL = impl.upper()
-            cfgs = [f"feature = \"dilithium{level}\""]
+            cfgs = [f"feature = \"{name}\""]
             if impl == "avx2":
                 cfgs.append("enable_avx2")
             elif impl == "aarch64":
@@ -34,6 +236,308 @@ use cty::{{c_int, size_t}};
             test_cfgs = ["test"]
             if impl == "avx2":
                 test_cfgs.append("target_feature = \"avx2\"")
+            if impl == "avx2":
+                safety = """\
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+"""
+            else:
+                safety = """\
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+"""
+
+            if fips204:
+                # the seed is the keygen randomness
+                seed_keypair_fn = "crypto_sign_keypair"
+            else:
+                seed_keypair_fn = "crypto_sign_seed_keypair"
+
+            if fips204:
+                randomized_call = f"""PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_internal(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                [0, 0].as_ptr(),
+                2,
+                rnd as *const _,
+                sk as *const _,
+            )"""
+            else:
+                randomized_call = f"""PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                sk as *const _,
+            )"""
+
+            if fips204:
+                randomized_expanded_call = f"""PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_internal(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                [0, 0].as_ptr(),
+                2,
+                rnd as *const _,
+                esk as *const _,
+            )"""
+            else:
+                randomized_expanded_call = f"""PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                esk as *const _,
+            )"""
+
+            if fips204:
+                internal_ffi_fns = f"""
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_internal(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            pre: *const u8,
+            prelen: size_t,
+            rnd: *const [u8; RNDBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_internal(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            pre: *const u8,
+            prelen: size_t,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared_internal(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            pre: *const u8,
+            prelen: size_t,
+            ppk: *const PreparedPublicKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_internal(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            pre: *const u8,
+            prelen: size_t,
+            rnd: *const [u8; RNDBYTES],
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;"""
+                ctx_fns = f"""\
+    /// Sign with context string `ctx`. Returns a nonzero value if `ctx` is
+    /// longer than [`CONTEXTMAXBYTES`] bytes.
+    ///
+{safety}    pub unsafe fn crypto_sign_signature_ctx(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        ctx: &[u8],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_signature(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                ctx.as_ptr(),
+                ctx.len(),
+                sk as *const _,
+            )
+        }}
+    }}
+
+    /// Verify with context string `ctx`. Returns -4 if `ctx` is longer than
+    /// [`CONTEXTMAXBYTES`] bytes, and otherwise the same values as
+    /// [`crypto_sign_verify`].
+    ///
+{safety}    pub unsafe fn crypto_sign_verify_ctx(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        ctx: &[u8],
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_verify(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                ctx.as_ptr(),
+                ctx.len(),
+                pk as *const _,
+            )
+        }}
+    }}
+
+    /// Sign the formatted message `M' = pre || message`, without checking the
+    /// prefix `pre`. Signing is deterministic if `rnd` is all zero.
+    ///
+{safety}    pub unsafe fn crypto_sign_signature_internal(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        pre: &[u8],
+        rnd: &[u8; RNDBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_internal(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                pre.as_ptr(),
+                pre.len(),
+                rnd as *const _,
+                sk as *const _,
+            )
+        }}
+    }}
+
+    /// Verify the formatted message `M' = pre || message`, without checking
+    /// the prefix `pre`. Returns the same values as [`crypto_sign_verify`].
+    ///
+{safety}    pub unsafe fn crypto_sign_verify_internal(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        pre: &[u8],
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_internal(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                pre.as_ptr(),
+                pre.len(),
+                pk as *const _,
+            )
+        }}
+    }}
+
+    /// Verify with context string `ctx` and a prepared public key. Returns the
+    /// same values as [`crypto_sign_verify_ctx`].
+    ///
+{safety}    /// `ppk` must have been prepared by this implementation.
+    pub unsafe fn crypto_sign_verify_prepared_ctx(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        ctx: &[u8],
+        ppk: &PreparedPublicKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                ctx.as_ptr(),
+                ctx.len(),
+                ppk as *const _,
+            )
+        }}
+    }}
+
+    /// Verify the formatted message `M' = pre || message` with a prepared public
+    /// key, without checking the prefix `pre`. Returns the same values as
+    /// [`crypto_sign_verify`].
+    ///
+{safety}    /// `ppk` must have been prepared by this implementation.
+    pub unsafe fn crypto_sign_verify_prepared_internal(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        pre: &[u8],
+        ppk: &PreparedPublicKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared_internal(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                pre.as_ptr(),
+                pre.len(),
+                ppk as *const _,
+            )
+        }}
+    }}
+
+    /// Sign with context string `ctx` and an expanded secret key. Returns a
+    /// nonzero value if `ctx` is longer than [`CONTEXTMAXBYTES`] bytes.
+    ///
+{safety}    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded_ctx(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        ctx: &[u8],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                ctx.as_ptr(),
+                ctx.len(),
+                esk as *const _,
+            )
+        }}
+    }}
+
+    /// Sign the formatted message `M' = pre || message` with an expanded secret
+    /// key, without checking the prefix `pre`. Signing is deterministic if `rnd`
+    /// is all zero.
+    ///
+{safety}    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded_internal(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        pre: &[u8],
+        rnd: &[u8; RNDBYTES],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_internal(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                pre.as_ptr(),
+                pre.len(),
+                rnd as *const _,
+                esk as *const _,
+            )
+        }}
+    }}
+
+"""
+            else:
+                internal_ffi_fns = f"""
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_seed_keypair(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *mut [u8; SECRETKEYBYTES],
+            seed: *const [u8; SEEDBYTES],
+        ) -> c_int;"""
+                ctx_fns = ""
 
             rust += f"""\
 
@@ -41,74 +545,236 @@ use cty::{{c_int, size_t}};
 pub mod {impl} {{
     use super::*;
 
-    #[link(name = "dilithium{level}_{impl}")]
+    #[link(name = "{name}_{impl}")]
     extern "C" {{
-        /// Generate a new keypair, writing the public key to `pk` and the secret
-        /// key to `sk`. Requires a buffer `random` to be filled with
-        /// cryptographically secure random bytes, living at least until the
-        /// function returns.
-        fn PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_keypair(
+        /// Generate a new keypair, writing the public key to `pk` and the
+        /// secret key to `sk`. Requires a buffer `random` to be filled
+        /// with cryptographically secure random bytes, living at least
+        /// until the function returns.
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_keypair(
             pk: *mut [u8; PUBLICKEYBYTES],
             sk: *mut [u8; SECRETKEYBYTES],
-            random: *mut [u8; 128],
+            {keypair_random_ffi_param},
         ) -> c_int;
 
-        fn PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_signature(
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_signature(
             sig: *mut [u8; SIGNATUREBYTES],
             m: *const u8,
-            mlen: size_t,
+            mlen: size_t,{ctx_ffi_params}
             sk: *const [u8; SECRETKEYBYTES],
         ) -> c_int;
 
-        fn PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_verify(
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_verify(
             sig: *const [u8; SIGNATUREBYTES],
             m: *const u8,
-            mlen: size_t,
+            mlen: size_t,{ctx_ffi_params}
             pk: *const [u8; PUBLICKEYBYTES],
         ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_pk_from_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sig(sig: *const [u8; SIGNATUREBYTES]) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_prepare_pk(
+            ppk: *mut PreparedPublicKeyBuf,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,{ctx_ffi_params}
+            ppk: *const PreparedPublicKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_expand_sk(
+            esk: *mut ExpandedSecretKeyBuf,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,{ctx_ffi_params}
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;{internal_ffi_fns}
+    }}
+
+{safety}    pub unsafe fn crypto_sign_keypair(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &mut [u8; SECRETKEYBYTES],
+        random: &mut [u8; KEYGENRANDOMBYTES],
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_keypair(pk as *mut _, sk as *mut _, {keypair_random_arg})
+        }}
     }}
 
-    pub unsafe fn crypto_sign_keypair(
+    /// Generate a new keypair deterministically from the 32 byte seed `seed`.
+    ///
+{safety}    pub unsafe fn crypto_sign_seed_keypair(
         pk: &mut [u8; PUBLICKEYBYTES],
         sk: &mut [u8; SECRETKEYBYTES],
-        random: &mut [u8; 128],
+        seed: &[u8; SEEDBYTES],
     ) -> c_int {{
         unsafe {{
-            PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_keypair(pk as *mut _, sk as *mut _, random as *mut _)
+            PQCLEAN_{ns}_{IMPL}_{seed_keypair_fn}(pk as *mut _, sk as *mut _, seed as *const _)
         }}
     }}
 
-    pub unsafe fn crypto_sign_signature(
+{sign_verify_doc}{safety}    pub unsafe fn crypto_sign_signature(
         sig: &mut [u8; SIGNATUREBYTES],
         message: &[u8],
         sk: &[u8; SECRETKEYBYTES],
     ) -> c_int {{
         unsafe {{
-            PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_signature(
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_signature(
                 sig as *mut _,
                 message.as_ptr(),
-                message.len(),
+                message.len(),{ctx_args}
                 sk as *const _,
             )
         }}
     }}
 
-    pub unsafe fn crypto_sign_verify(
+    /// Sign with randomness `rnd` mixed into the derivation of the
+    /// per-signature seed.
+    ///
+{safety}    pub unsafe fn crypto_sign_signature_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {{
+        unsafe {{
+            {randomized_call}
+        }}
+    }}
+
+{verify_doc}{safety}    pub unsafe fn crypto_sign_verify(
         sig: &[u8; SIGNATUREBYTES],
         message: &[u8],
         pk: &[u8; PUBLICKEYBYTES],
     ) -> c_int {{
         unsafe {{
-            PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_verify(
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_verify(
                 sig as *const _,
                 message.as_ptr(),
-                message.len(),
+                message.len(),{ctx_args}
                 pk as *const _,
             )
         }}
     }}
 
-    #[{cfg_all(test_cfgs)}]
+    /// Recompute the public key from the secret key `sk`, writing it to `pk`.
+    /// Returns a nonzero value if the hash of `pk` does not match the `tr`
+    /// stored in `sk`; `pk` is written nonetheless.
+    ///
+{safety}    pub unsafe fn crypto_sign_pk_from_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {{
+        unsafe {{ PQCLEAN_{ns}_{IMPL}_crypto_sign_pk_from_sk(pk as *mut _, sk as *const _) }}
+    }}
+
+    /// Check that the secret key `sk` is consistent and recompute its public key,
+    /// writing it to `pk`. Returns 0 if the secret key is consistent, -1 if a
+    /// coefficient of `s1` or `s2` is out of range, -2 if `t0` does not match the
+    /// recomputed one and -3 if the hash of `pk` does not match the `tr` stored in
+    /// `sk`; `pk` is written in all cases.
+    ///
+{safety}    pub unsafe fn crypto_sign_check_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {{
+        unsafe {{ PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sk(pk as *mut _, sk as *const _) }}
+    }}
+
+    /// Check that the signature `sig` is canonically encoded. Returns 0 if it is,
+    /// -1 if the hint counters or the unused hint indices are malformed, -2 if
+    /// the hint indices of a polynomial are not strictly increasing, -3 if there
+    /// are too many hints and -4 if a coefficient of `z` is out of range.
+    ///
+{safety}    pub unsafe fn crypto_sign_check_sig(sig: &[u8; SIGNATUREBYTES]) -> c_int {{
+        unsafe {{ PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sig(sig as *const _) }}
+    }}
+
+    /// Expand the public key `pk` into `ppk` for verifying many signatures with
+    /// [`crypto_sign_verify_prepared`].
+    ///
+{safety}    pub unsafe fn crypto_sign_prepare_pk(
+        ppk: &mut PreparedPublicKeyBuf,
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {{
+        unsafe {{ PQCLEAN_{ns}_{IMPL}_crypto_sign_prepare_pk(ppk as *mut _, pk as *const _) }}
+    }}
+
+{verify_prepared_doc}{safety}    /// `ppk` must have been prepared by this implementation.
+    pub unsafe fn crypto_sign_verify_prepared(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        ppk: &PreparedPublicKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),{ctx_args}
+                ppk as *const _,
+            )
+        }}
+    }}
+
+    /// Expand the secret key `sk` into `esk` for computing many signatures with
+    /// [`crypto_sign_signature_expanded`].
+    ///
+{safety}    pub unsafe fn crypto_sign_expand_sk(
+        esk: &mut ExpandedSecretKeyBuf,
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {{
+        unsafe {{ PQCLEAN_{ns}_{IMPL}_crypto_sign_expand_sk(esk as *mut _, sk as *const _) }}
+    }}
+
+{sign_expanded_doc}{safety}    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),{ctx_args}
+                esk as *const _,
+            )
+        }}
+    }}
+
+    /// Sign with an expanded secret key and randomness `rnd` mixed into the
+    /// derivation of the per-signature seed.
+    ///
+{safety}    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {{
+        unsafe {{
+            {randomized_expanded_call}
+        }}
+    }}
+
+{ctx_fns}    #[{cfg_all(test_cfgs)}]
     mod tests {{
         use super::*;
 
@@ -119,7 +785,7 @@ pub mod {impl} {{
             let mut seckey = [0u8; SECRETKEYBYTES];
             let mut pubkey = [0u8; PUBLICKEYBYTES];
             // not secure random, but deterministic and good enough for the test
-            let mut random = [37u8; 128];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
             let res = unsafe {{
                 crypto_sign_keypair(
                     &mut pubkey,
@@ -148,12 +814,231 @@ pub mod {impl} {{
             }};
             assert_eq!(res, 0, "Invalid signature crated!");
         }}
+
+        #[test]
+        fn test_pk_from_sk() {{
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe {{ crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) }};
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe {{ crypto_sign_pk_from_sk(&mut derived, &seckey) }};
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // corrupt tr, which follows `rho` and `key` in the secret key
+            seckey[64] ^= 1;
+            let res = unsafe {{ crypto_sign_pk_from_sk(&mut derived, &seckey) }};
+            assert_ne!(res, 0);
+            assert_eq!(derived, pubkey);
+        }}
+
+        #[test]
+        fn test_check_sig() {{
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe {{ crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) }};
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe {{ crypto_sign_signature(&mut sig, &msg[..], &seckey) }};
+            assert_eq!(res, 0);
+            let res = unsafe {{ crypto_sign_check_sig(&sig) }};
+            assert_eq!(res, 0);
+
+            // the signature ends with the unused hint indices and {k} hint counters
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - {k} - 1] = 1;
+            let res = unsafe {{ crypto_sign_check_sig(&malformed) }};
+            assert_eq!(res, -1);
+
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe {{ crypto_sign_check_sig(&malformed) }};
+            assert_eq!(res, -3);
+
+            // `z` follows the {ctilde_bytes} byte challenge seed, a first coefficient
+            // with all bits set decodes to 1 - GAMMA1
+            let mut malformed = sig;
+            malformed[{ctilde_bytes}] = 0xff;
+            malformed[{ctilde_bytes} + 1] = 0xff;
+            malformed[{ctilde_bytes} + 2] |= 0x0f;
+            let res = unsafe {{ crypto_sign_check_sig(&malformed) }};
+            assert_eq!(res, -4);
+        }}
+
+        #[test]
+        fn test_verify_prepared() {{
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe {{ crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) }};
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe {{ crypto_sign_signature(&mut sig, &msg[..], &seckey) }};
+            assert_eq!(res, 0);
+
+            let mut ppk = PreparedPublicKeyBuf([0; PREPAREDPUBLICKEYBYTES]);
+            let res = unsafe {{ crypto_sign_prepare_pk(&mut ppk, &pubkey) }};
+            assert_eq!(res, 0);
+            let res = unsafe {{ crypto_sign_verify_prepared(&sig, &msg[..], &ppk) }};
+            assert_eq!(res, 0);
+            let res = unsafe {{ crypto_sign_verify_prepared(&sig, &b"hello warld"[..], &ppk) }};
+            assert_eq!(res, -3);
+
+            // same failures as the unprepared verification
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe {{ crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) }};
+            assert_eq!(res, -1);
+            let mut malformed = sig;
+            malformed[64..128].fill(0xff);
+            let res = unsafe {{ crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) }};
+            assert_eq!(res, -2);
+            let res = unsafe {{ crypto_sign_verify(&malformed, &msg[..], &pubkey) }};
+            assert_eq!(res, -2);
+        }}
+
+        #[test]
+        fn test_sign_expanded() {{
+            let msg = b"hello world";
+            let rnd = [42u8; RNDBYTES];
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe {{ crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) }};
+            assert_eq!(res, 0);
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let res = unsafe {{ crypto_sign_expand_sk(&mut esk, &seckey) }};
+            assert_eq!(res, 0);
+
+            // the same signatures as with the packed secret key
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let mut expected = [0u8; SIGNATUREBYTES];
+            let res = unsafe {{ crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) }};
+            assert_eq!(res, 0);
+            let res = unsafe {{ crypto_sign_signature(&mut expected, &msg[..], &seckey) }};
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+
+            let res = unsafe {{
+                crypto_sign_signature_expanded_randomized(&mut sig, &msg[..], &rnd, &esk)
+            }};
+            assert_eq!(res, 0);
+            let res =
+                unsafe {{ crypto_sign_signature_randomized(&mut expected, &msg[..], &rnd, &seckey) }};
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+            let res = unsafe {{ crypto_sign_verify(&sig, &msg[..], &pubkey) }};
+            assert_eq!(res, 0);
+        }}
+
+        #[test]
+        fn test_check_sk() {{
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe {{ crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) }};
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe {{ crypto_sign_check_sk(&mut derived, &seckey) }};
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // a byte of `s1` with all bits set holds a coefficient below -ETA
+            let mut corrupt = seckey;
+            corrupt[128] = 0xff;
+            let res = unsafe {{ crypto_sign_check_sk(&mut derived, &corrupt) }};
+            assert_eq!(res, -1);
+
+            // `t0` is stored last
+            let mut corrupt = seckey;
+            corrupt[SECRETKEYBYTES - 1] ^= 1;
+            let res = unsafe {{ crypto_sign_check_sk(&mut derived, &corrupt) }};
+            assert_eq!(res, -2);
+            assert_eq!(derived, pubkey);
+
+            let mut corrupt = seckey;
+            corrupt[64] ^= 1;
+            let res = unsafe {{ crypto_sign_check_sk(&mut derived, &corrupt) }};
+            assert_eq!(res, -3);
+            assert_eq!(derived, pubkey);
+        }}
+
+        #[test]
+        fn test_stack_scrubbed() {{
+            use crate::ffi::stack_check::assert_scrubbed;
+
+            let msg = b"hello world";
+            let seed = [37u8; SEEDBYTES];
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut sig = [0u8; SIGNATUREBYTES];
+
+            let res = unsafe {{ crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) }};
+            assert_eq!(res, 0);
+            let needles = super::super::StackNeedles::new(&seed, &seckey, msg);
+            let needles = needles.named();
+
+            let keygen = || {{
+                let res = unsafe {{ crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) }};
+                assert_eq!(res, 0);
+            }};
+            assert_scrubbed(keygen, &needles, "keygen");
+
+            let signing = || {{
+                let res = unsafe {{ crypto_sign_signature(&mut sig, &msg[..], &seckey) }};
+                assert_eq!(res, 0);
+            }};
+            assert_scrubbed(signing, &needles, "signing");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let deriving = || {{
+                let res = unsafe {{ crypto_sign_pk_from_sk(&mut derived, &seckey) }};
+                assert_eq!(res, 0);
+            }};
+            assert_scrubbed(deriving, &needles, "public key derivation");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let checking = || {{
+                let res = unsafe {{ crypto_sign_check_sk(&mut derived, &seckey) }};
+                assert_eq!(res, 0);
+            }};
+            assert_scrubbed(checking, &needles, "secret key check");
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let expanding = || {{
+                let res = unsafe {{ crypto_sign_expand_sk(&mut esk, &seckey) }};
+                assert_eq!(res, 0);
+            }};
+            assert_scrubbed(expanding, &needles, "secret key expansion");
+
+            let signing_expanded = || {{
+                let res = unsafe {{ crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) }};
+                assert_eq!(res, 0);
+            }};
+            assert_scrubbed(signing_expanded, &needles, "signing with an expanded key");
+        }}
     }}
 }}
 """
 
-        with open(f"src/ffi/dilithium{level}.rs", 'w') as rust_file:
+        with open(f"src/ffi/{name}.rs", 'w') as rust_file:
             rust_file.write(rust)
 
+    with open("src/ffi.rs", 'w') as rust_file:
+        rust_file.write(ffi_mods)
+
 if __name__ == "__main__":
     main()
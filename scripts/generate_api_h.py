@@ -5,7 +5,13 @@ def main():
         spec = json.load(json_file)
 
     for param_set in spec["parameter_sets"]:
-        level = param_set["security_level"]
+        name = param_set["name"]
+        ns = param_set["namespace"]
+        algname = param_set["algname"]
+        fips204 = param_set["fips204"]
+        prepared_publickey_bytes = param_set["prepared_publickey_bytes"]
+        expanded_secretkey_bytes = param_set["expanded_secretkey_bytes"]
+        keygen_random_bytes = param_set["keygen_random_bytes"]
         publickey_bytes = param_set["publickey_bytes"]
         secretkey_bytes = param_set["secretkey_bytes"]
         signature_bytes = param_set["signature_bytes"]
@@ -13,40 +19,137 @@ def main():
             assert impl in ["clean", "avx2", "aarch64"]
             IMPL = impl.upper()
 
+            if fips204:
+                keypair_random_param = f"const uint8_t seed[{keygen_random_bytes}]"
+                ctx_params = "\n    const uint8_t* ctx, size_t ctxlen,"
+                internal_decls = f"""
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[32],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t ppk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[32],
+    const uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+"""
+            else:
+                keypair_random_param = f"uint8_t random[{keygen_random_bytes}]"
+                ctx_params = ""
+                internal_decls = f"""
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_seed_keypair(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES],
+    const uint8_t seed[32]
+);
+"""
+
             template = f"""\
-#ifndef PQCLEAN_DILITHIUM{level}_{IMPL}_API_H
-#define PQCLEAN_DILITHIUM{level}_{IMPL}_API_H
+#ifndef PQCLEAN_{ns}_{IMPL}_API_H
+#define PQCLEAN_{ns}_{IMPL}_API_H
 
 #include <stddef.h>
 #include <stdint.h>
 
-#define PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_PUBLICKEYBYTES {publickey_bytes}
-#define PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_SECRETKEYBYTES {secretkey_bytes}
-#define PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_BYTES {signature_bytes}
-#define PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_ALGNAME "Dilithium{level}"
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES {publickey_bytes}
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES {secretkey_bytes}
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_BYTES {signature_bytes}
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES {prepared_publickey_bytes}
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES {expanded_secretkey_bytes}
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_ALGNAME "{algname}"
 
-int PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_keypair(
-    uint8_t pk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
-    uint8_t sk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_SECRETKEYBYTES],
-    uint8_t random[128]
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_keypair(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES],
+    {keypair_random_param}
 );
 
-int PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_signature(
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature(
     uint8_t* sig,
-    const uint8_t* m, size_t mlen,
-    const uint8_t sk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
 );
 
-int PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_verify(
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify(
     const uint8_t* sig,
-    const uint8_t* m, size_t mlen,
-    const uint8_t pk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_pk_from_sk(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sk(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
 );
 
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sig(
+    const uint8_t sig[PQCLEAN_{ns}_{IMPL}_CRYPTO_BYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_prepare_pk(
+    uint8_t ppk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES],
+    const uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t ppk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_expand_sk(
+    uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+{internal_decls}
 #endif
 """
 
-            with open(f"extern/dilithium{level}/{impl}/api.h", 'w') as h_file:
+            with open(f"extern/{name}/{impl}/api.h", 'w') as h_file:
                 h_file.write(template)
 
 if __name__ == "__main__":
@@ -0,0 +1,105 @@
+import hashlib
+import json
+import os
+import re
+import subprocess
+import tempfile
+
+# Known answers for the ML-DSA parameter sets, computed with the ML-DSA
+# implementation of OpenSSL (3.5 or later), an implementation independent of
+# PQClean. The files follow the layout of the keyGen, sigGen and sigVer test
+# cases of NIST's ACVP (usnistgov/ACVP-Server, `ML-DSA-*-FIPS204`), for the
+# external, pure interface with deterministic signing.
+
+PARAMETER_SETS = [("ml_dsa_44", "ML-DSA-44"), ("ml_dsa_65", "ML-DSA-65"), ("ml_dsa_87", "ML-DSA-87")]
+
+def shake(label: str, n: int) -> bytes:
+    return hashlib.shake_256(label.encode()).digest(n)
+
+def openssl(*args: str) -> bytes:
+    return subprocess.run(["openssl", *args], check = True, capture_output = True).stdout
+
+def text_field(text: str, field: str) -> bytes:
+    m = re.search(f"^{field}:\n((?:\\s+[0-9a-f:]+\n)+)", text, re.MULTILINE)
+    return bytes.fromhex(re.sub("[\\s:]", "", m.group(1)))
+
+class Key:
+    def __init__(self, alg: str, seed: bytes, tmp: str):
+        self.path = os.path.join(tmp, "key.pem")
+        openssl("genpkey", "-algorithm", alg, "-pkeyopt", f"hexseed:{seed.hex()}", "-out", self.path)
+        text = openssl("pkey", "-in", self.path, "-text", "-noout").decode()
+        self.seed = seed
+        self.sk = text_field(text, "priv")
+        self.pk = text_field(text, "pub")
+        assert text_field(text, "seed") == seed
+
+    def sign(self, message: bytes, context: bytes) -> bytes:
+        # one-shot signing needs the message in a file of known size
+        message_path = os.path.join(os.path.dirname(self.path), "message")
+        with open(message_path, 'wb') as message_file:
+            message_file.write(message)
+        args = ["pkeyutl", "-sign", "-inkey", self.path, "-rawin", "-in", message_path]
+        args += ["-pkeyopt", "deterministic:1"]
+        if context:
+            args += ["-pkeyopt", f"hexcontext-string:{context.hex()}"]
+        return openssl(*args)
+
+def hex_upper(b: bytes) -> str:
+    return b.hex().upper()
+
+def main():
+    version = openssl("version").decode().split()[1]
+    for name, alg in PARAMETER_SETS:
+        kat = {
+            "source": f"OpenSSL {version}, in the layout of the ACVP {alg} test cases",
+            "keyGen": [],
+            "sigGen": [],
+            "sigVer": [],
+        }
+        with tempfile.TemporaryDirectory() as tmp:
+            key = Key(alg, shake(f"{alg} keyGen", 32), tmp)
+            kat["keyGen"].append({
+                "tcId": 1,
+                "seed": hex_upper(key.seed),
+                "pk": hex_upper(key.pk),
+                "sk": hex_upper(key.sk),
+            })
+
+            for i, (msg_len, ctx_len) in enumerate([(1, 0), (33, 255)]):
+                key = Key(alg, shake(f"{alg} sigGen {i}", 32), tmp)
+                message = shake(f"{alg} sigGen {i} message", msg_len)
+                context = shake(f"{alg} sigGen {i} context", ctx_len)
+                kat["sigGen"].append({
+                    "tcId": i + 1,
+                    "sk": hex_upper(key.sk),
+                    "message": hex_upper(message),
+                    "context": hex_upper(context),
+                    "signature": hex_upper(key.sign(message, context)),
+                })
+
+            key = Key(alg, shake(f"{alg} sigVer", 32), tmp)
+            message = shake(f"{alg} sigVer message", 64)
+            context = shake(f"{alg} sigVer context", 16)
+            signature = key.sign(message, context)
+            modified_signature = bytearray(signature)
+            modified_signature[len(signature) // 2] ^= 1
+            cases = [
+                (message, context, signature, True),
+                (message, context, bytes(modified_signature), False),
+            ]
+            for i, (message, context, signature, passed) in enumerate(cases):
+                kat["sigVer"].append({
+                    "tcId": i + 1,
+                    "pk": hex_upper(key.pk),
+                    "message": hex_upper(message),
+                    "context": hex_upper(context),
+                    "signature": hex_upper(signature),
+                    "testPassed": passed,
+                })
+
+        with open(f"src/regression_tests/{name}_kat.json", 'w') as json_file:
+            json.dump(kat, json_file, indent = 2)
+            json_file.write("\n")
+
+if __name__ == "__main__":
+    main()
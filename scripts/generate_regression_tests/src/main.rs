@@ -2,7 +2,7 @@ macro_rules! generate_dilithium_regression_tests {
     ($filename:expr) => {
         #[derive(serde::Serialize, serde::Deserialize)]
         struct RegressionTestExample {
-            seed: dilithium_raw::util::ByteArray<128>,
+            seed: dilithium_raw::util::ByteArray<KEYGENRANDOMBYTES>,
             pubkey: PublicKey,
             seckey: SecretKey,
             message: String,
@@ -14,7 +14,7 @@ macro_rules! generate_dilithium_regression_tests {
             use rand::Rng;
             use std::fs::File;
 
-            let mut random = [0; 128];
+            let mut random = [0; KEYGENRANDOMBYTES];
             let mut rng = rand::rngs::OsRng::default();
             rng.fill(&mut random[..]);
             let seed = ByteArray(random);
@@ -41,22 +41,46 @@ macro_rules! generate_dilithium_regression_tests {
 }
 
 mod dilithium2 {
+    use dilithium_raw::ffi::dilithium2::KEYGENRANDOMBYTES;
     use dilithium_raw::dilithium2::*;
     generate_dilithium_regression_tests!("../../src/regression_tests/dilithium2.ron");
 }
 
 mod dilithium3 {
+    use dilithium_raw::ffi::dilithium3::KEYGENRANDOMBYTES;
     use dilithium_raw::dilithium3::*;
     generate_dilithium_regression_tests!("../../src/regression_tests/dilithium3.ron");
 }
 
 mod dilithium5 {
+    use dilithium_raw::ffi::dilithium5::KEYGENRANDOMBYTES;
     use dilithium_raw::dilithium5::*;
     generate_dilithium_regression_tests!("../../src/regression_tests/dilithium5.ron");
 }
 
+mod ml_dsa_44 {
+    use dilithium_raw::ffi::ml_dsa_44::KEYGENRANDOMBYTES;
+    use dilithium_raw::ml_dsa_44::*;
+    generate_dilithium_regression_tests!("../../src/regression_tests/ml_dsa_44.ron");
+}
+
+mod ml_dsa_65 {
+    use dilithium_raw::ffi::ml_dsa_65::KEYGENRANDOMBYTES;
+    use dilithium_raw::ml_dsa_65::*;
+    generate_dilithium_regression_tests!("../../src/regression_tests/ml_dsa_65.ron");
+}
+
+mod ml_dsa_87 {
+    use dilithium_raw::ffi::ml_dsa_87::KEYGENRANDOMBYTES;
+    use dilithium_raw::ml_dsa_87::*;
+    generate_dilithium_regression_tests!("../../src/regression_tests/ml_dsa_87.ron");
+}
+
 fn main() {
     dilithium2::generate_tests();
     dilithium3::generate_tests();
     dilithium5::generate_tests();
+    ml_dsa_44::generate_tests();
+    ml_dsa_65::generate_tests();
+    ml_dsa_87::generate_tests();
 }
@@ -5,13 +5,186 @@ def main():
         spec = json.load(json_file)
 
     for param_set in spec["parameter_sets"]:
+        name = param_set["name"]
+        ns = param_set["namespace"]
         level = param_set["security_level"]
+        fips204 = param_set["fips204"]
         for impl in param_set["implementations"]:
             assert impl in ["clean", "avx2", "aarch64"]
             IMPL = impl.upper()
+            if fips204:
+                # FIPS 204: 64 byte tr, challenge seed length depends on the
+                # parameter set, keygen from a 32 byte seed xi and message
+                # formatting M' = 0 || len(ctx) || ctx || M. The core functions take
+                # the prefix of M' as argument, so the pure and pre-hash variants
+                # can share them.
+                TR = "TRBYTES"
+                CT = "CTILDEBYTES"
+                SKVEC = "2 * SEEDBYTES + TRBYTES"
+                keypair_random_doc = """\
+*              - const uint8_t seed[SEEDBYTES]:
+*                       pointer to seed xi filled with random bytes;
+*                       needs to live until the function returns"""
+                keypair_random_param = "const uint8_t seed[SEEDBYTES]"
+                sign_fn = "crypto_sign_signature_internal"
+                verify_fn = "crypto_sign_verify_internal"
+                verify_prepared_fn = "crypto_sign_verify_prepared_internal"
+                sign_expanded_fn = "crypto_sign_signature_expanded_internal"
+                ctx_doc = """
+*              - const uint8_t* pre: pointer to prefix of M'
+*              - size_t prelen:  length of prefix"""
+                ctx_params = """
+    const uint8_t* pre, size_t prelen,"""
+                rnd_doc = """
+*              - const uint8_t rnd[RNDBYTES]: randomness mixed into the
+*                       derivation of rhoprime, all zero for deterministic
+*                       signing"""
+                rnd_param = """
+    const uint8_t rnd[RNDBYTES],"""
+                sign_returns = "Returns 0 (success)"
+                string_h = """
+#include <string.h>"""
+                keypair_seedbuf_decl = """\
+    uint8_t seedbuf[2 * SEEDBYTES + CRHBYTES];
+"""
+                keypair_seedbuf_cleanse = """\
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+"""
+                keypair_seed_init = """\
+    /* Expand seed xi to rho, rhoprime and key, domain separated by K and L */
+    memcpy(seedbuf, seed, SEEDBYTES);
+    seedbuf[SEEDBYTES + 0] = K;
+    seedbuf[SEEDBYTES + 1] = L;
+    shake256(seedbuf, 2 * SEEDBYTES + CRHBYTES, seedbuf, SEEDBYTES + 2);
+    rho = seedbuf;
+    rhoprime = rho + SEEDBYTES;
+    key = rhoprime + CRHBYTES;
+"""
+                sign_seedbuf_decl = """\
+    uint8_t seedbuf[2 * SEEDBYTES + TRBYTES + RNDBYTES + 2 * CRHBYTES];
+    uint8_t *rho, *tr, *key, *rndbuf, *mu, *rhoprime;
+"""
+                sign_seedbuf_init = """\
+    rho = seedbuf;
+    tr = rho + SEEDBYTES;
+    key = tr + TRBYTES;
+    rndbuf = key + SEEDBYTES;
+    mu = rndbuf + RNDBYTES;
+    rhoprime = mu + CRHBYTES;
+"""
+                sign_mu_rhoprime = """\
+    /* Compute CRH(tr, pre, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, tr, TRBYTES);
+    shake256_inc_absorb(&state, pre, prelen);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Compute rhoprime = CRH(key, rnd, mu) */
+    memcpy(rndbuf, rnd, RNDBYTES);
+    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + RNDBYTES + CRHBYTES);
+"""
+                esign_seedbuf_decl = """\
+    uint8_t seedbuf[SEEDBYTES + RNDBYTES + 2 * CRHBYTES];
+    uint8_t *key, *rndbuf, *mu, *rhoprime;
+    const uint8_t *tr = e->tr;
+"""
+                esign_seedbuf_init = """\
+    key = seedbuf;
+    rndbuf = key + SEEDBYTES;
+    mu = rndbuf + RNDBYTES;
+    rhoprime = mu + CRHBYTES;
+    memcpy(key, e->key, SEEDBYTES);
+"""
+                verify_pre_decl = ""
+                verify_ctx_check = ""
+                verify_ctx_absorb = """\
+    shake256_inc_absorb(&state, pre, prelen);
+"""
+            else:
+                TR = "SEEDBYTES"
+                CT = "SEEDBYTES"
+                SKVEC = "3 * SEEDBYTES"
+                keypair_random_doc = """\
+*              - uint8_t random[2 * SEEDBYTES + CRHBYTES]:
+*                       pointer to array filled with random bytes;
+*                       needs to live until the function returns"""
+                keypair_random_param = "uint8_t random[2 * SEEDBYTES + CRHBYTES]"
+                ctx_doc = ""
+                ctx_params = ""
+                rnd_doc = """
+*              - const uint8_t* rnd: NULL for deterministic signing, or
+*                       pointer to RNDBYTES bytes of randomness mixed into
+*                       the derivation of rhoprime"""
+                rnd_param = """
+    const uint8_t* rnd,"""
+                sign_returns = "Returns 0 (success)"
+                string_h = """
+#include <string.h>"""
+                keypair_seedbuf_decl = ""
+                keypair_seedbuf_cleanse = ""
+                keypair_seed_init = """\
+    rho = random;
+    rhoprime = rho + SEEDBYTES;
+    key = rhoprime + CRHBYTES;
+"""
+                sign_seedbuf_decl = """\
+    uint8_t seedbuf[3 * SEEDBYTES + 2 * CRHBYTES];
+    uint8_t *rho, *tr, *key, *mu, *rhoprime;
+"""
+                sign_seedbuf_init = """\
+    rho = seedbuf;
+    tr = rho + SEEDBYTES;
+    key = tr + SEEDBYTES;
+    mu = key + SEEDBYTES;
+    rhoprime = mu + CRHBYTES;
+"""
+                sign_mu_rhoprime = """\
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, tr, SEEDBYTES);
+    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    if (rnd == NULL) {
+        /* Deterministic signing */
+        shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    } else {
+        /* Compute rhoprime = CRH(key, rnd, mu) */
+        shake256_inc_init(&state);
+        shake256_inc_absorb(&state, key, SEEDBYTES);
+        shake256_inc_absorb(&state, rnd, RNDBYTES);
+        shake256_inc_absorb(&state, mu, CRHBYTES);
+        shake256_inc_finalize(&state);
+        shake256_inc_squeeze(rhoprime, CRHBYTES, &state);
+        shake256_inc_ctx_release(&state);
+    }
+"""
+                esign_seedbuf_decl = """\
+    uint8_t seedbuf[SEEDBYTES + 2 * CRHBYTES];
+    uint8_t *key, *mu, *rhoprime;
+    const uint8_t *tr = e->tr;
+"""
+                esign_seedbuf_init = """\
+    key = seedbuf;
+    mu = key + SEEDBYTES;
+    rhoprime = mu + CRHBYTES;
+    memcpy(key, e->key, SEEDBYTES);
+"""
+                verify_pre_decl = ""
+                verify_ctx_check = ""
+                verify_ctx_absorb = ""
+                sign_fn = "crypto_sign_signature_randomized"
+                verify_fn = "crypto_sign_verify"
+                verify_prepared_fn = "crypto_sign_verify_prepared"
+                sign_expanded_fn = "crypto_sign_signature_expanded_randomized"
             keypair_decl = f"""\
 /*************************************************
-* Name:        PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_keypair
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_keypair
 *
 * Description: Generates public and private key.
 *
@@ -21,58 +194,456 @@ def main():
 *              - uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
 *                       pointer to output private key (allocated
 *                       array of CRYPTO_SECRETKEYBYTES bytes)
-*              - uint8_t random[2 * SEEDBYTES + CRHBYTES]:
-*                       pointer to array filled with random bytes;
-*                       needs to live until the function returns
+{keypair_random_doc}
 *
 * Returns 0 (success)
 **************************************************/
 int DILITHIUM_NAMESPACE(crypto_sign_keypair)(
     uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
     uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)],
-    uint8_t random[2 * SEEDBYTES + CRHBYTES]
+    {keypair_random_param}
+) {{"""
+
+            pk_from_sk_decl = f"""\
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_pk_from_sk
+*
+* Description: Recomputes the public key from a secret key and checks it
+*              against the hash tr stored in the secret key.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if H(pk) equals tr and -1 otherwise; pk is written in both cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_pk_from_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {{"""
+
+            check_sk_decl = f"""\
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sk
+*
+* Description: Checks that a secret key is consistent and recomputes the
+*              public key from it.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 if the secret key is consistent, -1 if a coefficient of s1 or s2
+* is outside of [-ETA, ETA], -2 if t0 does not match the low bits of the
+* recomputed t and -3 if H(pk) does not equal tr; pk is written in all cases
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sk)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {{"""
+
+            check_sig_decl = f"""\
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sig
+*
+* Description: Checks that a signature is canonically encoded, i.e. that it
+*              would not be rejected by verification before the challenge is
+*              recomputed.
+*
+* Arguments:   - const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]:
+*                       pointer to signature
+*
+* Returns 0 if the signature is well-formed, -1 if a hint counter is
+* decreasing or unused hint indices are not zero, -2 if the hint indices of a
+* polynomial are not strictly increasing, -3 if there are more than OMEGA
+* hints and -4 if a coefficient of z is outside of (-(GAMMA1 - BETA),
+* GAMMA1 - BETA)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_check_sig)(
+    const uint8_t sig[DILITHIUM_NAMESPACE(CRYPTO_BYTES)]
+) {{"""
+
+            prepare_pk_decl = f"""\
+/* Expanded public key: the matrix A, NTT(t1 * 2^D) and tr = H(pk) */
+typedef struct {{
+    polyvecl mat[K];
+    polyveck t1;
+    uint8_t tr[{TR}];
+}} prepared_pk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char prepared_pk_size_check[
+    sizeof(prepared_pk) == DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_prepare_pk
+*
+* Description: Expands a public key once for verifying many signatures with
+*              PQCLEAN_{ns}_{IMPL}_{verify_prepared_fn}.
+*
+* Arguments:   - uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to output prepared public key, aligned to
+*                       32 bytes
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_prepare_pk)(
+    uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)],
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {{"""
+
+            verify_prepared_decl = f"""\
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_{verify_prepared_fn}
+*
+* Description: Verifies signature with a public key prepared by
+*              PQCLEAN_{ns}_{IMPL}_crypto_sign_prepare_pk.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message{ctx_doc}
+*              - const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to prepared public key
+*
+* Returns the same values as PQCLEAN_{ns}_{IMPL}_{verify_fn}
+**************************************************/
+int DILITHIUM_NAMESPACE({verify_prepared_fn})(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]
+) {{"""
+
+            expand_sk_decl = f"""\
+/* Expanded secret key: the matrix A, NTT(s1), NTT(s2), NTT(t0), key and tr */
+typedef struct {{
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2;
+    polyveck t0;
+    uint8_t key[SEEDBYTES];
+    uint8_t tr[{TR}];
+}} expanded_sk;
+
+/* Fails to compile if the size advertised in the headers is wrong */
+typedef char expanded_sk_size_check[
+    sizeof(expanded_sk) == DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES) ? 1 : -1];
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_expand_sk
+*
+* Description: Expands a secret key once for computing many signatures with
+*              PQCLEAN_{ns}_{IMPL}_{sign_expanded_fn}. The
+*              expanded secret key must be cleared by the caller.
+*
+* Arguments:   - uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to output expanded secret key, aligned to
+*                       32 bytes
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_expand_sk)(
+    uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)],
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {{"""
+
+            sign_expanded_decl = f"""\
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_{sign_expanded_fn}
+*
+* Description: Computes signature with a secret key expanded by
+*              PQCLEAN_{ns}_{IMPL}_crypto_sign_expand_sk.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message{ctx_doc}{rnd_doc}
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* {sign_returns}
+**************************************************/
+int DILITHIUM_NAMESPACE({sign_expanded_fn})(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}{rnd_param}
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
 ) {{"""
 
             sign_signature_decl = f"""\
 /*************************************************
-* Name:        PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_signature
+* Name:        PQCLEAN_{ns}_{IMPL}_{sign_fn}
 *
 * Description: Computes signature.
 *
 * Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
 *                       of CRYPTO_BYTES bytes)
 *              - uint8_t* m:     pointer to message to be signed
-*              - size_t mlen:    length of message
+*              - size_t mlen:    length of message{ctx_doc}{rnd_doc}
 *              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
 *                       pointer to bit-packed secret key
 *
-* Returns 0 (success)
+* {sign_returns}
 **************************************************/
-int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+int DILITHIUM_NAMESPACE({sign_fn})(
     uint8_t* sig,
-    const uint8_t* m, size_t mlen,
+    const uint8_t* m, size_t mlen,{ctx_params}{rnd_param}
     const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
 ) {{"""
 
             sign_verify_decl = f"""\
 /*************************************************
-* Name:        PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_verify
+* Name:        PQCLEAN_{ns}_{IMPL}_{verify_fn}
 *
 * Description: Verifies signature.
 *
 * Arguments:   - uint8_t* m:        pointer to input signature
 *              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message{ctx_doc}
+*              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to bit-packed public key
+*
+* Returns 0 if signature could be verified correctly, -1 if the hints are
+* malformed, -2 if the norm of z is too large and -3 if the challenge does not
+* match
+**************************************************/
+int DILITHIUM_NAMESPACE({verify_fn})(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
+) {{"""
+
+            if fips204:
+                pure_wrappers = f"""
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_signature
+*
+* Description: Computes signature, using the message formatting
+*              M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success) or -1 (context string too long)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {{
+    uint8_t pre[2 + 255];
+    uint8_t rnd[RNDBYTES];
+
+    if (ctxlen > 255) {{
+        return -1;
+    }}
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    /* Deterministic signing: rnd is all zero */
+    memset(rnd, 0, RNDBYTES);
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_internal)(sig, m, mlen, pre, 2 + ctxlen, rnd, sk);
+}}
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_verify
+*
+* Description: Verifies signature, using the message formatting
+*              M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
 *              - size_t mlen:       length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
 *              - const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
 *                       pointer to bit-packed public key
 *
-* Returns 0 if signature could be verified correctly and -1 otherwise
+* Returns 0 if signature could be verified correctly, -4 if the context
+* string is too long and otherwise the failure codes of
+* PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_internal
 **************************************************/
 int DILITHIUM_NAMESPACE(crypto_sign_verify)(
     const uint8_t* sig,
     const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
     const uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]
-) {{"""
+) {{
+    uint8_t pre[2 + 255];
+
+    if (ctxlen > 255) {{
+        return -4;
+    }}
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    return DILITHIUM_NAMESPACE(crypto_sign_verify_internal)(sig, m, mlen, pre, 2 + ctxlen, pk);
+}}
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared
+*
+* Description: Verifies signature with a prepared public key, using the
+*              message formatting M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* m:        pointer to input signature
+*              - const uint8_t* m:  pointer to message
+*              - size_t mlen:       length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]:
+*                       pointer to prepared public key
+*
+* Returns the same values as PQCLEAN_{ns}_{IMPL}_crypto_sign_verify
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_verify_prepared)(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t ppk[DILITHIUM_NAMESPACE(CRYPTO_PREPAREDPUBLICKEYBYTES)]
+) {{
+    uint8_t pre[2 + 255];
+
+    if (ctxlen > 255) {{
+        return -4;
+    }}
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    return DILITHIUM_NAMESPACE(crypto_sign_verify_prepared_internal)(sig, m, mlen, pre, 2 + ctxlen, ppk);
+}}
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded
+*
+* Description: Computes signature with an expanded secret key, using the
+*              message formatting M' = 0 || ctxlen || ctx || m.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t* ctx: pointer to context string
+*              - size_t ctxlen:  length of context string, at most 255
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success) or -1 (context string too long)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* ctx, size_t ctxlen,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {{
+    uint8_t pre[2 + 255];
+    uint8_t rnd[RNDBYTES];
+
+    if (ctxlen > 255) {{
+        return -1;
+    }}
+    pre[0] = 0;
+    pre[1] = (uint8_t) ctxlen;
+    memcpy(pre + 2, ctx, ctxlen);
+
+    /* Deterministic signing: rnd is all zero */
+    memset(rnd, 0, RNDBYTES);
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_internal)(sig, m, mlen, pre, 2 + ctxlen, rnd, esk);
+}}
+"""
+            else:
+                pure_wrappers = f"""
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_signature
+*
+* Description: Computes signature deterministically.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to bit-packed secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]
+) {{
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_randomized)(sig, m, mlen, NULL, sk);
+}}
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded
+*
+* Description: Computes signature deterministically with an expanded secret
+*              key.
+*
+* Arguments:   - uint8_t* sig:   pointer to output signature (allocated array
+*                       of CRYPTO_BYTES bytes)
+*              - uint8_t* m:     pointer to message to be signed
+*              - size_t mlen:    length of message
+*              - const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]:
+*                       pointer to expanded secret key
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_signature_expanded)(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t esk[DILITHIUM_NAMESPACE(CRYPTO_EXPANDEDSECRETKEYBYTES)]
+) {{
+    return DILITHIUM_NAMESPACE(crypto_sign_signature_expanded_randomized)(sig, m, mlen, NULL, esk);
+}}
+
+/*************************************************
+* Name:        PQCLEAN_{ns}_{IMPL}_crypto_sign_seed_keypair
+*
+* Description: Generates public and private key from a seed, which is
+*              expanded with SHAKE256 like in the reference implementation.
+*
+* Arguments:   - uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)]:
+*                       pointer to output public key (allocated
+*                       array of CRYPTO_PUBLICKEYBYTES bytes)
+*              - uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)]:
+*                       pointer to output private key (allocated
+*                       array of CRYPTO_SECRETKEYBYTES bytes)
+*              - const uint8_t seed[SEEDBYTES]: pointer to seed
+*
+* Returns 0 (success)
+**************************************************/
+int DILITHIUM_NAMESPACE(crypto_sign_seed_keypair)(
+    uint8_t pk[DILITHIUM_NAMESPACE(CRYPTO_PUBLICKEYBYTES)],
+    uint8_t sk[DILITHIUM_NAMESPACE(CRYPTO_SECRETKEYBYTES)],
+    const uint8_t seed[SEEDBYTES]
+) {{
+    uint8_t seedbuf[2 * SEEDBYTES + CRHBYTES];
+    int ret;
+
+    shake256(seedbuf, 2 * SEEDBYTES + CRHBYTES, seed, SEEDBYTES);
+    ret = DILITHIUM_NAMESPACE(crypto_sign_keypair)(pk, sk, seedbuf);
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    return ret;
+}}
+"""
 
             if impl == "clean":
                 template = f"""\
@@ -81,143 +652,456 @@ int DILITHIUM_NAMESPACE(crypto_sign_verify)(
 #include "params.h"
 #include "poly.h"
 #include "polyvec.h"
+#include "cleanse.h"
 #include "sign.h"
 #include "symmetric.h"
-#include <stdint.h>
+#include <stdint.h>{string_h}
 
 {keypair_decl}
-    uint8_t tr[SEEDBYTES];
-    const uint8_t *rho, *rhoprime, *key;
+    uint8_t tr[{TR}];
+{keypair_seedbuf_decl}    const uint8_t *rho, *rhoprime, *key;
     polyvecl mat[K];
     polyvecl s1, s1hat;
     polyveck s2, t1, t0;
 
-    rho = random;
-    rhoprime = rho + SEEDBYTES;
-    key = rhoprime + CRHBYTES;
+{keypair_seed_init}
+    /* Expand matrix */
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(mat, rho);
+
+    /* Sample short vectors s1 and s2 */
+    PQCLEAN_{ns}_CLEAN_polyvecl_uniform_eta(&s1, rhoprime, 0);
+    PQCLEAN_{ns}_CLEAN_polyveck_uniform_eta(&s2, rhoprime, L);
+
+    /* Matrix-vector multiplication */
+    s1hat = s1;
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&s1hat);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&t1, mat, &s1hat);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    PQCLEAN_{ns}_CLEAN_polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_power2round(&t1, &t0, &t1);
+    PQCLEAN_{ns}_CLEAN_pack_pk(pk, rho, &t1);
+
+    /* Compute H(rho, t1) and write secret key */
+    shake256(tr, {TR}, pk, PQCLEAN_{ns}_CLEAN_CRYPTO_PUBLICKEYBYTES);
+    PQCLEAN_{ns}_CLEAN_pack_sk(sk, rho, tr, key, &t0, &s1, &s2);
+
+    /* Clear secrets from the stack */
+{keypair_seedbuf_cleanse}    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s1hat, sizeof(s1hat));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return 0;
+}}
+
+{pk_from_sk_decl}
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + {TR}];
+    uint8_t tr[{TR}];
+    uint8_t *rho, *sktr, *key;
+    uint8_t diff = 0;
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2, t1, t0;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + {TR};
+    PQCLEAN_{ns}_CLEAN_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Expand matrix */
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(mat, rho);
+
+    /* Matrix-vector multiplication */
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&s1);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&t1, mat, &s1);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    PQCLEAN_{ns}_CLEAN_polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_power2round(&t1, &t0, &t1);
+    PQCLEAN_{ns}_CLEAN_pack_pk(pk, rho, &t1);
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, {TR}, pk, PQCLEAN_{ns}_CLEAN_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < {TR}; ++i) {{
+        diff |= tr[i] ^ sktr[i];
+    }}
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return diff ? -1 : 0;
+}}
+
+{check_sk_decl}
+    unsigned int i, j;
+    int ret = 0;
+    uint8_t seedbuf[2 * SEEDBYTES + {TR}];
+    uint8_t tr[{TR}];
+    uint8_t t0buf[POLYT0_PACKEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    const uint8_t *skt0 = sk + PQCLEAN_{ns}_CLEAN_CRYPTO_SECRETKEYBYTES - K * POLYT0_PACKEDBYTES;
+    uint8_t diff = 0, t0diff = 0;
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2, t1, t0;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + {TR};
+    PQCLEAN_{ns}_CLEAN_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Check that the coefficients of s1 and s2 lie in [-ETA, ETA] */
+    if (PQCLEAN_{ns}_CLEAN_polyvecl_chknorm(&s1, ETA + 1)
+            || PQCLEAN_{ns}_CLEAN_polyveck_chknorm(&s2, ETA + 1)) {{
+        ret = -1;
+    }}
+
+    /* Expand matrix */
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(mat, rho);
+
+    /* Matrix-vector multiplication */
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&s1);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&t1, mat, &s1);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    PQCLEAN_{ns}_CLEAN_polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_power2round(&t1, &t0, &t1);
+    PQCLEAN_{ns}_CLEAN_pack_pk(pk, rho, &t1);
+
+    /* Check the packed t0 of the secret key against the recomputed one */
+    for (i = 0; i < K; ++i) {{
+        PQCLEAN_{ns}_CLEAN_polyt0_pack(t0buf, &t0.vec[i]);
+        for (j = 0; j < POLYT0_PACKEDBYTES; ++j) {{
+            t0diff |= t0buf[j] ^ skt0[i * POLYT0_PACKEDBYTES + j];
+        }}
+    }}
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, {TR}, pk, PQCLEAN_{ns}_CLEAN_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < {TR}; ++i) {{
+        diff |= tr[i] ^ sktr[i];
+    }}
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(t0buf, sizeof(t0buf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    if (ret == 0 && t0diff) {{
+        ret = -2;
+    }}
+    if (ret == 0 && diff) {{
+        ret = -3;
+    }}
+    return ret;
+}}
+
+{check_sig_decl}
+    unsigned int i, j, k;
+    const uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
+    poly z;
+
+    /* Decode h, rejecting it like unpack_sig */
+    k = 0;
+    for (i = 0; i < K; ++i) {{
+        if (hint[OMEGA + i] > OMEGA) {{
+            return -3;
+        }}
+        if (hint[OMEGA + i] < k) {{
+            return -1;
+        }}
+        for (j = k + 1; j < hint[OMEGA + i]; ++j) {{
+            if (hint[j] <= hint[j - 1]) {{
+                return -2;
+            }}
+        }}
+        k = hint[OMEGA + i];
+    }}
+    for (j = k; j < OMEGA; ++j) {{
+        if (hint[j]) {{
+            return -1;
+        }}
+    }}
+
+    /* Check the norm of z */
+    for (i = 0; i < L; ++i) {{
+        PQCLEAN_{ns}_CLEAN_polyz_unpack(&z, sig + {CT} + i * POLYZ_PACKEDBYTES);
+        if (PQCLEAN_{ns}_CLEAN_poly_chknorm(&z, GAMMA1 - BETA)) {{
+            return -4;
+        }}
+    }}
+
+    return 0;
+}}
+
+{prepare_pk_decl}
+    prepared_pk *p = (prepared_pk *) ppk;
+    uint8_t rho[SEEDBYTES];
+
+    PQCLEAN_{ns}_CLEAN_unpack_pk(rho, &p->t1, pk);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(p->mat, rho);
+    PQCLEAN_{ns}_CLEAN_polyveck_shiftl(&p->t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_ntt(&p->t1);
+    shake256(p->tr, {TR}, pk, PQCLEAN_{ns}_CLEAN_CRYPTO_PUBLICKEYBYTES);
+    return 0;
+}}
+
+{verify_prepared_decl}
+    unsigned int i;
+    uint8_t buf[K * POLYW1_PACKEDBYTES];
+    uint8_t mu[CRHBYTES];
+    uint8_t c[{CT}];
+    uint8_t c2[{CT}];
+    const prepared_pk *p = (const prepared_pk *) ppk;
+    poly cp;
+    polyvecl z;
+    polyveck t1, w1, h;
+    shake256incctx state;
+
+{verify_ctx_check}    if (PQCLEAN_{ns}_CLEAN_unpack_sig(c, &z, &h, sig)) {{
+        return -1;
+    }}
+    if (PQCLEAN_{ns}_CLEAN_polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
+        return -2;
+    }}
+
+    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, p->tr, {TR});
+{verify_ctx_absorb}    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Matrix-vector multiplication; compute Az - c2^dt1 */
+    PQCLEAN_{ns}_CLEAN_poly_challenge(&cp, c);
+
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&z);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&w1, p->mat, &z);
+
+    PQCLEAN_{ns}_CLEAN_poly_ntt(&cp);
+    PQCLEAN_{ns}_CLEAN_polyveck_pointwise_poly_montgomery(&t1, &cp, &p->t1);
+
+    PQCLEAN_{ns}_CLEAN_polyveck_sub(&w1, &w1, &t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&w1);
+
+    /* Reconstruct w1 */
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_use_hint(&w1, &w1, &h);
+    PQCLEAN_{ns}_CLEAN_polyveck_pack_w1(buf, &w1);
+
+    /* Call random oracle and verify challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(c2, {CT}, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < {CT}; ++i) {{
+        if (c[i] != c2[i]) {{
+            return -3;
+        }}
+    }}
+
+    return 0;
+}}
+
+{expand_sk_decl}
+    expanded_sk *e = (expanded_sk *) esk;
+    uint8_t rho[SEEDBYTES];
 
-    /* Expand matrix */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvec_matrix_expand(mat, rho);
+    PQCLEAN_{ns}_CLEAN_unpack_sk(rho, e->tr, e->key, &e->t0, &e->s1, &e->s2, sk);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(e->mat, rho);
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&e->s1);
+    PQCLEAN_{ns}_CLEAN_polyveck_ntt(&e->s2);
+    PQCLEAN_{ns}_CLEAN_polyveck_ntt(&e->t0);
+    return 0;
+}}
 
-    /* Sample short vectors s1 and s2 */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_uniform_eta(&s1, rhoprime, 0);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_uniform_eta(&s2, rhoprime, L);
+{sign_expanded_decl}
+    unsigned int n;
+    const expanded_sk *e = (const expanded_sk *) esk;
+{esign_seedbuf_decl}    uint16_t nonce = 0;
+    polyvecl y, z;
+    polyveck w1, w0, h;
+    poly cp;
+    shake256incctx state;
+
+{esign_seedbuf_init}
+{sign_mu_rhoprime}
+rej:
+    /* Sample intermediate vector y */
+    PQCLEAN_{ns}_CLEAN_polyvecl_uniform_gamma1(&y, rhoprime, nonce++);
 
     /* Matrix-vector multiplication */
-    s1hat = s1;
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_ntt(&s1hat);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvec_matrix_pointwise_montgomery(&t1, mat, &s1hat);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_reduce(&t1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_invntt_tomont(&t1);
+    z = y;
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&z);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&w1, e->mat, &z);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&w1);
 
-    /* Add error vector s2 */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_add(&t1, &t1, &s2);
+    /* Decompose w and call the random oracle */
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_decompose(&w1, &w0, &w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_pack_w1(sig, &w1);
 
-    /* Extract t1 and write public key */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_caddq(&t1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_power2round(&t1, &t0, &t1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_pack_pk(pk, rho, &t1);
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, {CT}, &state);
+    shake256_inc_ctx_release(&state);
+    PQCLEAN_{ns}_CLEAN_poly_challenge(&cp, sig);
+    PQCLEAN_{ns}_CLEAN_poly_ntt(&cp);
 
-    /* Compute H(rho, t1) and write secret key */
-    shake256(tr, SEEDBYTES, pk, PQCLEAN_DILITHIUM{level}_CLEAN_CRYPTO_PUBLICKEYBYTES);
-    PQCLEAN_DILITHIUM{level}_CLEAN_pack_sk(sk, rho, tr, key, &t0, &s1, &s2);
+    /* Compute z, reject if it reveals secret */
+    PQCLEAN_{ns}_CLEAN_polyvecl_pointwise_poly_montgomery(&z, &cp, &e->s1);
+    PQCLEAN_{ns}_CLEAN_polyvecl_invntt_tomont(&z);
+    PQCLEAN_{ns}_CLEAN_polyvecl_add(&z, &z, &y);
+    PQCLEAN_{ns}_CLEAN_polyvecl_reduce(&z);
+    if (PQCLEAN_{ns}_CLEAN_polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
+        goto rej;
+    }}
+
+    /* Check that subtracting cs2 does not change high bits of w and low bits
+     * do not reveal secret information */
+    PQCLEAN_{ns}_CLEAN_polyveck_pointwise_poly_montgomery(&h, &cp, &e->s2);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&h);
+    PQCLEAN_{ns}_CLEAN_polyveck_sub(&w0, &w0, &h);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&w0);
+    if (PQCLEAN_{ns}_CLEAN_polyveck_chknorm(&w0, GAMMA2 - BETA)) {{
+        goto rej;
+    }}
+
+    /* Compute hints for w1 */
+    PQCLEAN_{ns}_CLEAN_polyveck_pointwise_poly_montgomery(&h, &cp, &e->t0);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&h);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&h);
+    if (PQCLEAN_{ns}_CLEAN_polyveck_chknorm(&h, GAMMA2)) {{
+        goto rej;
+    }}
+
+    PQCLEAN_{ns}_CLEAN_polyveck_add(&w0, &w0, &h);
+    n = PQCLEAN_{ns}_CLEAN_polyveck_make_hint(&h, &w0, &w1);
+    if (n > OMEGA) {{
+        goto rej;
+    }}
+
+    /* Write signature */
+    PQCLEAN_{ns}_CLEAN_pack_sig(sig, sig, &z, &h);
 
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&y, sizeof(y));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }}
 
 {sign_signature_decl}
     unsigned int n;
-    uint8_t seedbuf[3 * SEEDBYTES + 2 * CRHBYTES];
-    uint8_t *rho, *tr, *key, *mu, *rhoprime;
-    uint16_t nonce = 0;
+{sign_seedbuf_decl}    uint16_t nonce = 0;
     polyvecl mat[K], s1, y, z;
     polyveck t0, s2, w1, w0, h;
     poly cp;
     shake256incctx state;
 
-    rho = seedbuf;
-    tr = rho + SEEDBYTES;
-    key = tr + SEEDBYTES;
-    mu = key + SEEDBYTES;
-    rhoprime = mu + CRHBYTES;
-    PQCLEAN_DILITHIUM{level}_CLEAN_unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
-
-    /* Compute CRH(tr, msg) */
-    shake256_inc_init(&state);
-    shake256_inc_absorb(&state, tr, SEEDBYTES);
-    shake256_inc_absorb(&state, m, mlen);
-    shake256_inc_finalize(&state);
-    shake256_inc_squeeze(mu, CRHBYTES, &state);
-    shake256_inc_ctx_release(&state);
-
-    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+{sign_seedbuf_init}    PQCLEAN_{ns}_CLEAN_unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
 
+{sign_mu_rhoprime}
     /* Expand matrix and transform vectors */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvec_matrix_expand(mat, rho);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_ntt(&s1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_ntt(&s2);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_ntt(&t0);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(mat, rho);
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&s1);
+    PQCLEAN_{ns}_CLEAN_polyveck_ntt(&s2);
+    PQCLEAN_{ns}_CLEAN_polyveck_ntt(&t0);
 
 rej:
     /* Sample intermediate vector y */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_uniform_gamma1(&y, rhoprime, nonce++);
+    PQCLEAN_{ns}_CLEAN_polyvecl_uniform_gamma1(&y, rhoprime, nonce++);
 
     /* Matrix-vector multiplication */
     z = y;
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_ntt(&z);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvec_matrix_pointwise_montgomery(&w1, mat, &z);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_reduce(&w1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_invntt_tomont(&w1);
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&z);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&w1, mat, &z);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&w1);
 
     /* Decompose w and call the random oracle */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_caddq(&w1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_decompose(&w1, &w0, &w1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_pack_w1(sig, &w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_decompose(&w1, &w0, &w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_pack_w1(sig, &w1);
 
     shake256_inc_init(&state);
     shake256_inc_absorb(&state, mu, CRHBYTES);
     shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
     shake256_inc_finalize(&state);
-    shake256_inc_squeeze(sig, SEEDBYTES, &state);
+    shake256_inc_squeeze(sig, {CT}, &state);
     shake256_inc_ctx_release(&state);
-    PQCLEAN_DILITHIUM{level}_CLEAN_poly_challenge(&cp, sig);
-    PQCLEAN_DILITHIUM{level}_CLEAN_poly_ntt(&cp);
+    PQCLEAN_{ns}_CLEAN_poly_challenge(&cp, sig);
+    PQCLEAN_{ns}_CLEAN_poly_ntt(&cp);
 
     /* Compute z, reject if it reveals secret */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_pointwise_poly_montgomery(&z, &cp, &s1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_invntt_tomont(&z);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_add(&z, &z, &y);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_reduce(&z);
-    if (PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
+    PQCLEAN_{ns}_CLEAN_polyvecl_pointwise_poly_montgomery(&z, &cp, &s1);
+    PQCLEAN_{ns}_CLEAN_polyvecl_invntt_tomont(&z);
+    PQCLEAN_{ns}_CLEAN_polyvecl_add(&z, &z, &y);
+    PQCLEAN_{ns}_CLEAN_polyvecl_reduce(&z);
+    if (PQCLEAN_{ns}_CLEAN_polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
         goto rej;
     }}
 
     /* Check that subtracting cs2 does not change high bits of w and low bits
      * do not reveal secret information */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_pointwise_poly_montgomery(&h, &cp, &s2);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_invntt_tomont(&h);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_sub(&w0, &w0, &h);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_reduce(&w0);
-    if (PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_chknorm(&w0, GAMMA2 - BETA)) {{
+    PQCLEAN_{ns}_CLEAN_polyveck_pointwise_poly_montgomery(&h, &cp, &s2);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&h);
+    PQCLEAN_{ns}_CLEAN_polyveck_sub(&w0, &w0, &h);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&w0);
+    if (PQCLEAN_{ns}_CLEAN_polyveck_chknorm(&w0, GAMMA2 - BETA)) {{
         goto rej;
     }}
 
     /* Compute hints for w1 */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_pointwise_poly_montgomery(&h, &cp, &t0);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_invntt_tomont(&h);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_reduce(&h);
-    if (PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_chknorm(&h, GAMMA2)) {{
+    PQCLEAN_{ns}_CLEAN_polyveck_pointwise_poly_montgomery(&h, &cp, &t0);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&h);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&h);
+    if (PQCLEAN_{ns}_CLEAN_polyveck_chknorm(&h, GAMMA2)) {{
         goto rej;
     }}
 
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_add(&w0, &w0, &h);
-    n = PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_make_hint(&h, &w0, &w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_add(&w0, &w0, &h);
+    n = PQCLEAN_{ns}_CLEAN_polyveck_make_hint(&h, &w0, &w1);
     if (n > OMEGA) {{
         goto rej;
     }}
 
     /* Write signature */
-    PQCLEAN_DILITHIUM{level}_CLEAN_pack_sig(sig, sig, &z, &h);
+    PQCLEAN_{ns}_CLEAN_pack_sig(sig, sig, &z, &h);
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    pqclean_cleanse(&y, sizeof(y));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }}
 
@@ -226,61 +1110,61 @@ rej:
     uint8_t buf[K * POLYW1_PACKEDBYTES];
     uint8_t rho[SEEDBYTES];
     uint8_t mu[CRHBYTES];
-    uint8_t c[SEEDBYTES];
-    uint8_t c2[SEEDBYTES];
+{verify_pre_decl}    uint8_t c[{CT}];
+    uint8_t c2[{CT}];
     poly cp;
     polyvecl mat[K], z;
     polyveck t1, w1, h;
     shake256incctx state;
 
-    PQCLEAN_DILITHIUM{level}_CLEAN_unpack_pk(rho, &t1, pk);
-    if (PQCLEAN_DILITHIUM{level}_CLEAN_unpack_sig(c, &z, &h, sig)) {{
+{verify_ctx_check}    PQCLEAN_{ns}_CLEAN_unpack_pk(rho, &t1, pk);
+    if (PQCLEAN_{ns}_CLEAN_unpack_sig(c, &z, &h, sig)) {{
         return -1;
     }}
-    if (PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
-        return -1;
+    if (PQCLEAN_{ns}_CLEAN_polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
+        return -2;
     }}
 
     /* Compute CRH(H(rho, t1), msg) */
-    shake256(mu, SEEDBYTES, pk, PQCLEAN_DILITHIUM{level}_CLEAN_CRYPTO_PUBLICKEYBYTES);
+    shake256(mu, {TR}, pk, PQCLEAN_{ns}_CLEAN_CRYPTO_PUBLICKEYBYTES);
     shake256_inc_init(&state);
-    shake256_inc_absorb(&state, mu, SEEDBYTES);
-    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_absorb(&state, mu, {TR});
+{verify_ctx_absorb}    shake256_inc_absorb(&state, m, mlen);
     shake256_inc_finalize(&state);
     shake256_inc_squeeze(mu, CRHBYTES, &state);
     shake256_inc_ctx_release(&state);
 
     /* Matrix-vector multiplication; compute Az - c2^dt1 */
-    PQCLEAN_DILITHIUM{level}_CLEAN_poly_challenge(&cp, c);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvec_matrix_expand(mat, rho);
+    PQCLEAN_{ns}_CLEAN_poly_challenge(&cp, c);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_expand(mat, rho);
 
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvecl_ntt(&z);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyvec_matrix_pointwise_montgomery(&w1, mat, &z);
+    PQCLEAN_{ns}_CLEAN_polyvecl_ntt(&z);
+    PQCLEAN_{ns}_CLEAN_polyvec_matrix_pointwise_montgomery(&w1, mat, &z);
 
-    PQCLEAN_DILITHIUM{level}_CLEAN_poly_ntt(&cp);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_shiftl(&t1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_ntt(&t1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_pointwise_poly_montgomery(&t1, &cp, &t1);
+    PQCLEAN_{ns}_CLEAN_poly_ntt(&cp);
+    PQCLEAN_{ns}_CLEAN_polyveck_shiftl(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_ntt(&t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_pointwise_poly_montgomery(&t1, &cp, &t1);
 
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_sub(&w1, &w1, &t1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_reduce(&w1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_invntt_tomont(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_sub(&w1, &w1, &t1);
+    PQCLEAN_{ns}_CLEAN_polyveck_reduce(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_invntt_tomont(&w1);
 
     /* Reconstruct w1 */
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_caddq(&w1);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_use_hint(&w1, &w1, &h);
-    PQCLEAN_DILITHIUM{level}_CLEAN_polyveck_pack_w1(buf, &w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_caddq(&w1);
+    PQCLEAN_{ns}_CLEAN_polyveck_use_hint(&w1, &w1, &h);
+    PQCLEAN_{ns}_CLEAN_polyveck_pack_w1(buf, &w1);
 
-    /* Call random oracle and verify PQCLEAN_DILITHIUM{level}_CLEAN_challenge */
+    /* Call random oracle and verify PQCLEAN_{ns}_CLEAN_challenge */
     shake256_inc_init(&state);
     shake256_inc_absorb(&state, mu, CRHBYTES);
     shake256_inc_absorb(&state, buf, K * POLYW1_PACKEDBYTES);
     shake256_inc_finalize(&state);
-    shake256_inc_squeeze(c2, SEEDBYTES, &state);
+    shake256_inc_squeeze(c2, {CT}, &state);
     shake256_inc_ctx_release(&state);
-    for (i = 0; i < SEEDBYTES; ++i) {{
+    for (i = 0; i < {CT}; ++i) {{
         if (c[i] != c2[i]) {{
-            return -1;
+            return -3;
         }}
     }}
 
@@ -290,21 +1174,21 @@ rej:
             elif impl == "avx2":
                 polyvec_matrix_expand_row_cases_4_5 = f"""
     case 4:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row4(buf, buf + 1, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row4(buf, buf + 1, rho);
         *row = buf;
         break;
     case 5:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row5(buf + 1, buf, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row5(buf + 1, buf, rho);
         *row = buf + 1;
         break;"""
 
                 polyvec_matrix_expand_row_cases_6_7 = f"""
     case 6:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row6(buf, buf + 1, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row6(buf, buf + 1, rho);
         *row = buf;
         break;
     case 7:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row7(buf + 1, buf, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row7(buf + 1, buf, rho);
         *row = buf + 1;
         break;"""
 
@@ -312,29 +1196,30 @@ rej:
                     polyvec_matrix_expand_row_cases_4_5 = ""
                     polyvec_matrix_expand_row_cases_6_7 = ""
                     sample_short_vectors_code = f"""\
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s2.vec[0], &s2.vec[1], &s2.vec[2], &s2.vec[3], rhoprime, 4, 5, 6, 7);"""
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s2.vec[0], &s2.vec[1], &s2.vec[2], &s2.vec[3], rhoprime, 4, 5, 6, 7);"""
                     sample_intermediate_vector_code = f"""\
     nonce += 4;"""
                 elif level == 3:
                     polyvec_matrix_expand_row_cases_6_7 = ""
                     sample_short_vectors_code = f"""\
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s1.vec[4], &s2.vec[0], &s2.vec[1], &s2.vec[2], rhoprime, 4, 5, 6, 7);
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s2.vec[3], &s2.vec[4], &s2.vec[5], &t0, rhoprime, 8, 9, 10, 11);"""
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s1.vec[4], &s2.vec[0], &s2.vec[1], &s2.vec[2], rhoprime, 4, 5, 6, 7);
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s2.vec[3], &s2.vec[4], &s2.vec[5], &t0, rhoprime, 8, 9, 10, 11);"""
                     sample_intermediate_vector_code = f"""\
-    PQCLEAN_DILITHIUM3_AVX2_poly_uniform_gamma1(&z.vec[4], rhoprime, nonce + 4);
+    PQCLEAN_{ns}_AVX2_poly_uniform_gamma1(&z.vec[4], rhoprime, nonce + 4);
     nonce += 5;"""
                 elif level == 5:
                     sample_short_vectors_code = f"""\
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s1.vec[4], &s1.vec[5], &s1.vec[6], &s2.vec[0], rhoprime, 4, 5, 6, 7);
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s2.vec[1], &s2.vec[2], &s2.vec[3], &s2.vec[4], rhoprime, 8, 9, 10, 11);
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s2.vec[5], &s2.vec[6], &s2.vec[7], &t0, rhoprime, 12, 13, 14, 15);"""
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s1.vec[4], &s1.vec[5], &s1.vec[6], &s2.vec[0], rhoprime, 4, 5, 6, 7);
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s2.vec[1], &s2.vec[2], &s2.vec[3], &s2.vec[4], rhoprime, 8, 9, 10, 11);
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s2.vec[5], &s2.vec[6], &s2.vec[7], &t0, rhoprime, 12, 13, 14, 15);"""
                     sample_intermediate_vector_code = f"""\
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_gamma1_4x(&z.vec[4], &z.vec[5], &z.vec[6], &tmp,
+    PQCLEAN_{ns}_AVX2_poly_uniform_gamma1_4x(&z.vec[4], &z.vec[5], &z.vec[6], &tmp,
             rhoprime, nonce + 4, nonce + 5, nonce + 6, 0);
     nonce += 7;"""
 
                 template = f"""\
 #include "align.h"
+#include "cleanse.h"
 #include "fips202.h"
 #include "packing.h"
 #include "params.h"
@@ -348,19 +1233,19 @@ rej:
 static inline void polyvec_matrix_expand_row(polyvecl **row, polyvecl buf[2], const uint8_t rho[SEEDBYTES], unsigned int i) {{
     switch (i) {{
     case 0:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row0(buf, buf + 1, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row0(buf, buf + 1, rho);
         *row = buf;
         break;
     case 1:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row1(buf + 1, buf, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row1(buf + 1, buf, rho);
         *row = buf + 1;
         break;
     case 2:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row2(buf, buf + 1, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row2(buf, buf + 1, rho);
         *row = buf;
         break;
     case 3:
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand_row3(buf + 1, buf, rho);
+        PQCLEAN_{ns}_AVX2_polyvec_matrix_expand_row3(buf + 1, buf, rho);
         *row = buf + 1;
         break;{polyvec_matrix_expand_row_cases_4_5}{polyvec_matrix_expand_row_cases_6_7}
     }}
@@ -368,67 +1253,446 @@ static inline void polyvec_matrix_expand_row(polyvecl **row, polyvecl buf[2], co
 
 {keypair_decl}
     unsigned int i;
-    const uint8_t *rho, *rhoprime, *key;
+{keypair_seedbuf_decl}    const uint8_t *rho, *rhoprime, *key;
     polyvecl rowbuf[2];
     polyvecl s1, *row = rowbuf;
     polyveck s2;
     poly t1, t0;
 
-    rho = random;
-    rhoprime = rho + SEEDBYTES;
-    key = rhoprime + CRHBYTES;
-
+{keypair_seed_init}
     /* Store rho, key */
     memcpy(pk, rho, SEEDBYTES);
     memcpy(sk, rho, SEEDBYTES);
     memcpy(sk + SEEDBYTES, key, SEEDBYTES);
 
     /* Sample short vectors s1 and s2 */
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_eta_4x(&s1.vec[0], &s1.vec[1], &s1.vec[2], &s1.vec[3], rhoprime, 0, 1, 2, 3);
+    PQCLEAN_{ns}_AVX2_poly_uniform_eta_4x(&s1.vec[0], &s1.vec[1], &s1.vec[2], &s1.vec[3], rhoprime, 0, 1, 2, 3);
 {sample_short_vectors_code}
 
     /* Pack secret vectors */
     for (i = 0; i < L; i++) {{
-        PQCLEAN_DILITHIUM{level}_AVX2_polyeta_pack(sk + 3 * SEEDBYTES + i * POLYETA_PACKEDBYTES, &s1.vec[i]);
+        PQCLEAN_{ns}_AVX2_polyeta_pack(sk + {SKVEC} + i * POLYETA_PACKEDBYTES, &s1.vec[i]);
+    }}
+    for (i = 0; i < K; i++) {{
+        PQCLEAN_{ns}_AVX2_polyeta_pack(sk + {SKVEC} + (L + i)*POLYETA_PACKEDBYTES, &s2.vec[i]);
     }}
+
+    /* Transform s1 */
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&s1);
+
+
     for (i = 0; i < K; i++) {{
-        PQCLEAN_DILITHIUM{level}_AVX2_polyeta_pack(sk + 3 * SEEDBYTES + (L + i)*POLYETA_PACKEDBYTES, &s2.vec[i]);
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_{ns}_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_{ns}_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1, t0 */
+        PQCLEAN_{ns}_AVX2_poly_caddq(&t1);
+        PQCLEAN_{ns}_AVX2_poly_power2round(&t1, &t0, &t1);
+        PQCLEAN_{ns}_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+        PQCLEAN_{ns}_AVX2_polyt0_pack(sk + {SKVEC} + (L + K)*POLYETA_PACKEDBYTES + i * POLYT0_PACKEDBYTES, &t0);
     }}
 
+    /* Compute H(rho, t1) and store in secret key */
+    shake256(sk + 2 * SEEDBYTES, {TR}, pk, PQCLEAN_{ns}_AVX2_CRYPTO_PUBLICKEYBYTES);
+
+    /* Clear secrets from the stack */
+{keypair_seedbuf_cleanse}    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return 0;
+}}
+
+{pk_from_sk_decl}
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + {TR}];
+    uint8_t tr[{TR}];
+    uint8_t *rho, *sktr, *key;
+    uint8_t diff = 0;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2, t0;
+    poly t1;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + {TR};
+    PQCLEAN_{ns}_AVX2_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Store rho */
+    memcpy(pk, rho, SEEDBYTES);
+
     /* Transform s1 */
-    PQCLEAN_DILITHIUM{level}_AVX2_polyvecl_ntt(&s1);
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&s1);
+
+
+    for (i = 0; i < K; i++) {{
+        /* Expand matrix row */
+        polyvec_matrix_expand_row(&row, rowbuf, rho, i);
+
+        /* Compute inner-product */
+        PQCLEAN_{ns}_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_{ns}_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1 */
+        PQCLEAN_{ns}_AVX2_poly_caddq(&t1);
+        PQCLEAN_{ns}_AVX2_poly_power2round(&t1, &t0.vec[i], &t1);
+        PQCLEAN_{ns}_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+    }}
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, {TR}, pk, PQCLEAN_{ns}_AVX2_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < {TR}; ++i) {{
+        diff |= tr[i] ^ sktr[i];
+    }}
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return diff ? -1 : 0;
+}}
+
+{check_sk_decl}
+    unsigned int i, j;
+    int ret = 0;
+    uint8_t seedbuf[2 * SEEDBYTES + {TR}];
+    uint8_t tr[{TR}];
+    uint8_t t0buf[POLYT0_PACKEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    const uint8_t *skt0 = sk + PQCLEAN_{ns}_AVX2_CRYPTO_SECRETKEYBYTES - K * POLYT0_PACKEDBYTES;
+    uint8_t diff = 0, t0diff = 0;
+    polyvecl rowbuf[2];
+    polyvecl s1, *row = rowbuf;
+    polyveck s2, t0;
+    poly t1;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + {TR};
+    PQCLEAN_{ns}_AVX2_unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Check that the coefficients of s1 and s2 lie in [-ETA, ETA] */
+    if (PQCLEAN_{ns}_AVX2_polyvecl_chknorm(&s1, ETA + 1)
+            || PQCLEAN_{ns}_AVX2_polyveck_chknorm(&s2, ETA + 1)) {{
+        ret = -1;
+    }}
+
+    /* Store rho */
+    memcpy(pk, rho, SEEDBYTES);
 
+    /* Transform s1 */
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&s1);
 
     for (i = 0; i < K; i++) {{
         /* Expand matrix row */
         polyvec_matrix_expand_row(&row, rowbuf, rho, i);
 
-        /* Compute inner-product */
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_invntt_tomont(&t1);
+        /* Compute inner-product */
+        PQCLEAN_{ns}_AVX2_polyvecl_pointwise_acc_montgomery(&t1, row, &s1);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&t1);
+
+        /* Add error polynomial */
+        PQCLEAN_{ns}_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+
+        /* Round t and pack t1 */
+        PQCLEAN_{ns}_AVX2_poly_caddq(&t1);
+        PQCLEAN_{ns}_AVX2_poly_power2round(&t1, &t0.vec[i], &t1);
+        PQCLEAN_{ns}_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
+
+        /* Check the packed t0 of the secret key against the recomputed one */
+        PQCLEAN_{ns}_AVX2_polyt0_pack(t0buf, &t0.vec[i]);
+        for (j = 0; j < POLYT0_PACKEDBYTES; ++j) {{
+            t0diff |= t0buf[j] ^ skt0[i * POLYT0_PACKEDBYTES + j];
+        }}
+    }}
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, {TR}, pk, PQCLEAN_{ns}_AVX2_CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < {TR}; ++i) {{
+        diff |= tr[i] ^ sktr[i];
+    }}
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(t0buf, sizeof(t0buf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    if (ret == 0 && t0diff) {{
+        ret = -2;
+    }}
+    if (ret == 0 && diff) {{
+        ret = -3;
+    }}
+    return ret;
+}}
+
+{check_sig_decl}
+    unsigned int i, j, k;
+    const uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
+    poly z;
+
+    /* Decode h, rejecting it like unpack_sig */
+    k = 0;
+    for (i = 0; i < K; ++i) {{
+        if (hint[OMEGA + i] > OMEGA) {{
+            return -3;
+        }}
+        if (hint[OMEGA + i] < k) {{
+            return -1;
+        }}
+        for (j = k + 1; j < hint[OMEGA + i]; ++j) {{
+            if (hint[j] <= hint[j - 1]) {{
+                return -2;
+            }}
+        }}
+        k = hint[OMEGA + i];
+    }}
+    for (j = k; j < OMEGA; ++j) {{
+        if (hint[j]) {{
+            return -1;
+        }}
+    }}
+
+    /* Check the norm of z */
+    for (i = 0; i < L; ++i) {{
+        PQCLEAN_{ns}_AVX2_polyz_unpack(&z, sig + {CT} + i * POLYZ_PACKEDBYTES);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&z, GAMMA1 - BETA)) {{
+            return -4;
+        }}
+    }}
+
+    return 0;
+}}
+
+{prepare_pk_decl}
+    prepared_pk *p = (prepared_pk *) ppk;
+    unsigned int i;
+
+    for (i = 0; i < K; i++) {{
+        PQCLEAN_{ns}_AVX2_polyt1_unpack(&p->t1.vec[i], pk + SEEDBYTES + i * POLYT1_PACKEDBYTES);
+        PQCLEAN_{ns}_AVX2_poly_shiftl(&p->t1.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_ntt(&p->t1.vec[i]);
+    }}
+    PQCLEAN_{ns}_AVX2_polyvec_matrix_expand(p->mat, pk);
+    shake256(p->tr, {TR}, pk, PQCLEAN_{ns}_AVX2_CRYPTO_PUBLICKEYBYTES);
+    return 0;
+}}
+
+{verify_prepared_decl}
+    unsigned int i, j, pos = 0;
+    int znorm = 0;
+    /* PQCLEAN_{ns}_AVX2_polyw1_pack writes additional 14 bytes */
+    ALIGNED_UINT8(K * POLYW1_PACKEDBYTES + 14) buf;
+    uint8_t mu[CRHBYTES];
+    const uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
+    const prepared_pk *p = (const prepared_pk *) ppk;
+    polyvecl z;
+    poly c, w1, h;
+    shake256incctx state;
+
+{verify_ctx_check}    /* Compute CRH(tr, msg) */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, p->tr, {TR});
+{verify_ctx_absorb}    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(mu, CRHBYTES, &state);
+    shake256_inc_ctx_release(&state);
+
+    /* Expand challenge */
+    PQCLEAN_{ns}_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_{ns}_AVX2_poly_ntt(&c);
+
+    /* Unpack z and check its norm, which is reported after the hints */
+    for (i = 0; i < L; i++) {{
+        PQCLEAN_{ns}_AVX2_polyz_unpack(&z.vec[i], sig + {CT} + i * POLYZ_PACKEDBYTES);
+        znorm |= PQCLEAN_{ns}_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA);
+        PQCLEAN_{ns}_AVX2_poly_ntt(&z.vec[i]);
+    }}
+
+    for (i = 0; i < K; i++) {{
+        /* Compute i-th row of Az - c2^Dt1 */
+        PQCLEAN_{ns}_AVX2_polyvecl_pointwise_acc_montgomery(&w1, &p->mat[i], &z);
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&h, &c, &p->t1.vec[i]);
+
+        PQCLEAN_{ns}_AVX2_poly_sub(&w1, &w1, &h);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&w1);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&w1);
+
+        /* Get hint polynomial and reconstruct w1 */
+        memset(h.vec, 0, sizeof(poly));
+        if (hint[OMEGA + i] < pos || hint[OMEGA + i] > OMEGA) {{
+            return -1;
+        }}
+
+        for (j = pos; j < hint[OMEGA + i]; ++j) {{
+            /* Coefficients are ordered for strong unforgeability */
+            if (j > pos && hint[j] <= hint[j - 1]) {{
+                return -1;
+            }}
+            h.coeffs[hint[j]] = 1;
+        }}
+        pos = hint[OMEGA + i];
+
+        PQCLEAN_{ns}_AVX2_poly_caddq(&w1);
+        PQCLEAN_{ns}_AVX2_poly_use_hint(&w1, &w1, &h);
+        PQCLEAN_{ns}_AVX2_polyw1_pack(buf.coeffs + i * POLYW1_PACKEDBYTES, &w1);
+    }}
+
+    /* Extra indices are zero for strong unforgeability */
+    for (j = pos; j < OMEGA; ++j) {{
+        if (hint[j]) {{
+            return -1;
+        }}
+    }}
+    if (znorm) {{
+        return -2;
+    }}
+
+    /* Call random oracle and verify challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf.coeffs, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(buf.coeffs, {CT}, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < {CT}; ++i) {{
+        if (buf.coeffs[i] != sig[i]) {{
+            return -3;
+        }}
+    }}
+
+    return 0;
+}}
+
+{expand_sk_decl}
+    expanded_sk *e = (expanded_sk *) esk;
+    uint8_t rho[SEEDBYTES];
+
+    PQCLEAN_{ns}_AVX2_unpack_sk(rho, e->tr, e->key, &e->t0, &e->s1, &e->s2, sk);
+    PQCLEAN_{ns}_AVX2_polyvec_matrix_expand(e->mat, rho);
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&e->s1);
+    PQCLEAN_{ns}_AVX2_polyveck_ntt(&e->s2);
+    PQCLEAN_{ns}_AVX2_polyveck_ntt(&e->t0);
+    return 0;
+}}
+
+{sign_expanded_decl}
+    unsigned int i, n, pos;
+    const expanded_sk *e = (const expanded_sk *) esk;
+{esign_seedbuf_decl}    uint8_t hintbuf[N];
+    uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
+    uint64_t nonce = 0;
+    polyvecl z;
+    polyveck w1;
+    poly c, tmp;
+    union {{
+        polyvecl y;
+        polyveck w0;
+    }} tmpv;
+    shake256incctx state;
+
+{esign_seedbuf_init}
+{sign_mu_rhoprime}
+rej:
+    /* Sample intermediate vector y */
+    PQCLEAN_{ns}_AVX2_poly_uniform_gamma1_4x(&z.vec[0], &z.vec[1], &z.vec[2], &z.vec[3],
+            rhoprime, nonce, nonce + 1, nonce + 2, nonce + 3);
+{sample_intermediate_vector_code}
+
+    /* Matrix-vector product */
+    tmpv.y = z;
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&tmpv.y);
+    PQCLEAN_{ns}_AVX2_polyvec_matrix_pointwise_montgomery(&w1, e->mat, &tmpv.y);
+    PQCLEAN_{ns}_AVX2_polyveck_invntt_tomont(&w1);
+
+    /* Decompose w and call the random oracle */
+    PQCLEAN_{ns}_AVX2_polyveck_caddq(&w1);
+    PQCLEAN_{ns}_AVX2_polyveck_decompose(&w1, &tmpv.w0, &w1);
+    PQCLEAN_{ns}_AVX2_polyveck_pack_w1(sig, &w1);
+
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, {CT}, &state);
+    shake256_inc_ctx_release(&state);
+    PQCLEAN_{ns}_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_{ns}_AVX2_poly_ntt(&c);
+
+    /* Compute z, reject if it reveals secret */
+    for (i = 0; i < L; i++) {{
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->s1.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_{ns}_AVX2_poly_add(&z.vec[i], &z.vec[i], &tmp);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&z.vec[i]);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA)) {{
+            goto rej;
+        }}
+    }}
+
+    /* Zero hint vector in signature */
+    pos = 0;
+    memset(hint, 0, OMEGA);
+
+    for (i = 0; i < K; i++) {{
+        /* Check that subtracting cs2 does not change high bits of w and low bits
+         * do not reveal secret information */
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->s2.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_{ns}_AVX2_poly_sub(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&tmpv.w0.vec[i]);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&tmpv.w0.vec[i], GAMMA2 - BETA)) {{
+            goto rej;
+        }}
+
+        /* Compute hints */
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&tmp, &c, &e->t0.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&tmp);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&tmp, GAMMA2)) {{
+            goto rej;
+        }}
 
-        /* Add error polynomial */
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_add(&t1, &t1, &s2.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_add(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        n = PQCLEAN_{ns}_AVX2_poly_make_hint(hintbuf, &tmpv.w0.vec[i], &w1.vec[i]);
+        if (pos + n > OMEGA) {{
+            goto rej;
+        }}
 
-        /* Round t and pack t1, t0 */
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_caddq(&t1);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_power2round(&t1, &t0, &t1);
-        PQCLEAN_DILITHIUM{level}_AVX2_polyt1_pack(pk + SEEDBYTES + i * POLYT1_PACKEDBYTES, &t1);
-        PQCLEAN_DILITHIUM{level}_AVX2_polyt0_pack(sk + 3 * SEEDBYTES + (L + K)*POLYETA_PACKEDBYTES + i * POLYT0_PACKEDBYTES, &t0);
+        /* Store hints in signature */
+        memcpy(&hint[pos], hintbuf, n);
+        hint[OMEGA + i] = pos = pos + n;
     }}
 
-    /* Compute H(rho, t1) and store in secret key */
-    shake256(sk + 2 * SEEDBYTES, SEEDBYTES, pk, PQCLEAN_DILITHIUM{level}_AVX2_CRYPTO_PUBLICKEYBYTES);
+    /* Pack z into signature */
+    for (i = 0; i < L; i++) {{
+        PQCLEAN_{ns}_AVX2_polyz_pack(sig + {CT} + i * POLYZ_PACKEDBYTES, &z.vec[i]);
+    }}
 
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&tmpv, sizeof(tmpv));
+    pqclean_cleanse(&tmp, sizeof(tmp));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }}
 
 {sign_signature_decl}
     unsigned int i, n, pos;
-    uint8_t seedbuf[3 * SEEDBYTES + 2 * CRHBYTES];
-    uint8_t *rho, *tr, *key, *mu, *rhoprime;
-    uint8_t hintbuf[N];
-    uint8_t *hint = sig + SEEDBYTES + L * POLYZ_PACKEDBYTES;
+{sign_seedbuf_decl}    uint8_t hintbuf[N];
+    uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
     uint64_t nonce = 0;
     polyvecl mat[K], s1, z;
     polyveck t0, s2, w1;
@@ -439,63 +1703,49 @@ static inline void polyvec_matrix_expand_row(polyvecl **row, polyvecl buf[2], co
     }} tmpv;
     shake256incctx state;
 
-    rho = seedbuf;
-    tr = rho + SEEDBYTES;
-    key = tr + SEEDBYTES;
-    mu = key + SEEDBYTES;
-    rhoprime = mu + CRHBYTES;
-    PQCLEAN_DILITHIUM{level}_AVX2_unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
-
-    /* Compute CRH(tr, msg) */
-    shake256_inc_init(&state);
-    shake256_inc_absorb(&state, tr, SEEDBYTES);
-    shake256_inc_absorb(&state, m, mlen);
-    shake256_inc_finalize(&state);
-    shake256_inc_squeeze(mu, CRHBYTES, &state);
-    shake256_inc_ctx_release(&state);
-
-    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+{sign_seedbuf_init}    PQCLEAN_{ns}_AVX2_unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
 
+{sign_mu_rhoprime}
     /* Expand matrix and transform vectors */
-    PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_expand(mat, rho);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyvecl_ntt(&s1);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyveck_ntt(&s2);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyveck_ntt(&t0);
+    PQCLEAN_{ns}_AVX2_polyvec_matrix_expand(mat, rho);
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&s1);
+    PQCLEAN_{ns}_AVX2_polyveck_ntt(&s2);
+    PQCLEAN_{ns}_AVX2_polyveck_ntt(&t0);
 
 
 rej:
     /* Sample intermediate vector y */
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_uniform_gamma1_4x(&z.vec[0], &z.vec[1], &z.vec[2], &z.vec[3],
+    PQCLEAN_{ns}_AVX2_poly_uniform_gamma1_4x(&z.vec[0], &z.vec[1], &z.vec[2], &z.vec[3],
             rhoprime, nonce, nonce + 1, nonce + 2, nonce + 3);
 {sample_intermediate_vector_code}
 
     /* Matrix-vector product */
     tmpv.y = z;
-    PQCLEAN_DILITHIUM{level}_AVX2_polyvecl_ntt(&tmpv.y);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyvec_matrix_pointwise_montgomery(&w1, mat, &tmpv.y);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyveck_invntt_tomont(&w1);
+    PQCLEAN_{ns}_AVX2_polyvecl_ntt(&tmpv.y);
+    PQCLEAN_{ns}_AVX2_polyvec_matrix_pointwise_montgomery(&w1, mat, &tmpv.y);
+    PQCLEAN_{ns}_AVX2_polyveck_invntt_tomont(&w1);
 
     /* Decompose w and call the random oracle */
-    PQCLEAN_DILITHIUM{level}_AVX2_polyveck_caddq(&w1);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyveck_decompose(&w1, &tmpv.w0, &w1);
-    PQCLEAN_DILITHIUM{level}_AVX2_polyveck_pack_w1(sig, &w1);
+    PQCLEAN_{ns}_AVX2_polyveck_caddq(&w1);
+    PQCLEAN_{ns}_AVX2_polyveck_decompose(&w1, &tmpv.w0, &w1);
+    PQCLEAN_{ns}_AVX2_polyveck_pack_w1(sig, &w1);
 
     shake256_inc_init(&state);
     shake256_inc_absorb(&state, mu, CRHBYTES);
     shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
     shake256_inc_finalize(&state);
-    shake256_inc_squeeze(sig, SEEDBYTES, &state);
+    shake256_inc_squeeze(sig, {CT}, &state);
     shake256_inc_ctx_release(&state);
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_challenge(&c, sig);
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_ntt(&c);
+    PQCLEAN_{ns}_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_{ns}_AVX2_poly_ntt(&c);
 
     /* Compute z, reject if it reveals secret */
     for (i = 0; i < L; i++) {{
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_pointwise_montgomery(&tmp, &c, &s1.vec[i]);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_invntt_tomont(&tmp);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_add(&z.vec[i], &z.vec[i], &tmp);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_reduce(&z.vec[i]);
-        if (PQCLEAN_DILITHIUM{level}_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA)) {{
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&tmp, &c, &s1.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_{ns}_AVX2_poly_add(&z.vec[i], &z.vec[i], &tmp);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&z.vec[i]);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA)) {{
             goto rej;
         }}
     }}
@@ -507,24 +1757,24 @@ rej:
     for (i = 0; i < K; i++) {{
         /* Check that subtracting cs2 does not change high bits of w and low bits
          * do not reveal secret information */
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_pointwise_montgomery(&tmp, &c, &s2.vec[i]);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_invntt_tomont(&tmp);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_sub(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_reduce(&tmpv.w0.vec[i]);
-        if (PQCLEAN_DILITHIUM{level}_AVX2_poly_chknorm(&tmpv.w0.vec[i], GAMMA2 - BETA)) {{
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&tmp, &c, &s2.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_{ns}_AVX2_poly_sub(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&tmpv.w0.vec[i]);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&tmpv.w0.vec[i], GAMMA2 - BETA)) {{
             goto rej;
         }}
 
         /* Compute hints */
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_pointwise_montgomery(&tmp, &c, &t0.vec[i]);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_invntt_tomont(&tmp);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_reduce(&tmp);
-        if (PQCLEAN_DILITHIUM{level}_AVX2_poly_chknorm(&tmp, GAMMA2)) {{
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&tmp, &c, &t0.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&tmp);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&tmp);
+        if (PQCLEAN_{ns}_AVX2_poly_chknorm(&tmp, GAMMA2)) {{
             goto rej;
         }}
 
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_add(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
-        n = PQCLEAN_DILITHIUM{level}_AVX2_poly_make_hint(hintbuf, &tmpv.w0.vec[i], &w1.vec[i]);
+        PQCLEAN_{ns}_AVX2_poly_add(&tmpv.w0.vec[i], &tmpv.w0.vec[i], &tmp);
+        n = PQCLEAN_{ns}_AVX2_poly_make_hint(hintbuf, &tmpv.w0.vec[i], &w1.vec[i]);
         if (pos + n > OMEGA) {{
             goto rej;
         }}
@@ -536,41 +1786,51 @@ rej:
 
     /* Pack z into signature */
     for (i = 0; i < L; i++) {{
-        PQCLEAN_DILITHIUM{level}_AVX2_polyz_pack(sig + SEEDBYTES + i * POLYZ_PACKEDBYTES, &z.vec[i]);
+        PQCLEAN_{ns}_AVX2_polyz_pack(sig + {CT} + i * POLYZ_PACKEDBYTES, &z.vec[i]);
     }}
 
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    pqclean_cleanse(&tmpv, sizeof(tmpv));
+    pqclean_cleanse(&tmp, sizeof(tmp));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }}
 
 {sign_verify_decl}
     unsigned int i, j, pos = 0;
-    /* PQCLEAN_DILITHIUM{level}_AVX2_polyw1_pack writes additional 14 bytes */
+    int znorm = 0;
+    /* PQCLEAN_{ns}_AVX2_polyw1_pack writes additional 14 bytes */
     ALIGNED_UINT8(K * POLYW1_PACKEDBYTES + 14) buf;
     uint8_t mu[CRHBYTES];
-    const uint8_t *hint = sig + SEEDBYTES + L * POLYZ_PACKEDBYTES;
+{verify_pre_decl}    const uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
     polyvecl rowbuf[2];
     polyvecl *row = rowbuf;
     polyvecl z;
     poly c, w1, h;
     shake256incctx state;
 
-    /* Compute CRH(H(rho, t1), msg) */
-    shake256(mu, SEEDBYTES, pk, PQCLEAN_DILITHIUM{level}_AVX2_CRYPTO_PUBLICKEYBYTES);
+{verify_ctx_check}    /* Compute CRH(H(rho, t1), msg) */
+    shake256(mu, {TR}, pk, PQCLEAN_{ns}_AVX2_CRYPTO_PUBLICKEYBYTES);
     shake256_inc_init(&state);
-    shake256_inc_absorb(&state, mu, SEEDBYTES);
-    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_absorb(&state, mu, {TR});
+{verify_ctx_absorb}    shake256_inc_absorb(&state, m, mlen);
     shake256_inc_finalize(&state);
     shake256_inc_squeeze(mu, CRHBYTES, &state);
     shake256_inc_ctx_release(&state);
 
-    /* Expand PQCLEAN_DILITHIUM{level}_AVX2_challenge */
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_challenge(&c, sig);
-    PQCLEAN_DILITHIUM{level}_AVX2_poly_ntt(&c);
+    /* Expand PQCLEAN_{ns}_AVX2_challenge */
+    PQCLEAN_{ns}_AVX2_poly_challenge(&c, sig);
+    PQCLEAN_{ns}_AVX2_poly_ntt(&c);
 
-    /* Unpack z; shortness follows from unpacking */
+    /* Unpack z and check its norm, which is reported after the hints */
     for (i = 0; i < L; i++) {{
-        PQCLEAN_DILITHIUM{level}_AVX2_polyz_unpack(&z.vec[i], sig + SEEDBYTES + i * POLYZ_PACKEDBYTES);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_ntt(&z.vec[i]);
+        PQCLEAN_{ns}_AVX2_polyz_unpack(&z.vec[i], sig + {CT} + i * POLYZ_PACKEDBYTES);
+        znorm |= PQCLEAN_{ns}_AVX2_poly_chknorm(&z.vec[i], GAMMA1 - BETA);
+        PQCLEAN_{ns}_AVX2_poly_ntt(&z.vec[i]);
     }}
 
 
@@ -579,16 +1839,16 @@ rej:
         polyvec_matrix_expand_row(&row, rowbuf, pk, i);
 
         /* Compute i-th row of Az - c2^Dt1 */
-        PQCLEAN_DILITHIUM{level}_AVX2_polyvecl_pointwise_acc_montgomery(&w1, row, &z);
+        PQCLEAN_{ns}_AVX2_polyvecl_pointwise_acc_montgomery(&w1, row, &z);
 
-        PQCLEAN_DILITHIUM{level}_AVX2_polyt1_unpack(&h, pk + SEEDBYTES + i * POLYT1_PACKEDBYTES);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_shiftl(&h);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_ntt(&h);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_pointwise_montgomery(&h, &c, &h);
+        PQCLEAN_{ns}_AVX2_polyt1_unpack(&h, pk + SEEDBYTES + i * POLYT1_PACKEDBYTES);
+        PQCLEAN_{ns}_AVX2_poly_shiftl(&h);
+        PQCLEAN_{ns}_AVX2_poly_ntt(&h);
+        PQCLEAN_{ns}_AVX2_poly_pointwise_montgomery(&h, &c, &h);
 
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_sub(&w1, &w1, &h);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_reduce(&w1);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_invntt_tomont(&w1);
+        PQCLEAN_{ns}_AVX2_poly_sub(&w1, &w1, &h);
+        PQCLEAN_{ns}_AVX2_poly_reduce(&w1);
+        PQCLEAN_{ns}_AVX2_poly_invntt_tomont(&w1);
 
         /* Get hint polynomial and reconstruct w1 */
         memset(h.vec, 0, sizeof(poly));
@@ -605,9 +1865,9 @@ rej:
         }}
         pos = hint[OMEGA + i];
 
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_caddq(&w1);
-        PQCLEAN_DILITHIUM{level}_AVX2_poly_use_hint(&w1, &w1, &h);
-        PQCLEAN_DILITHIUM{level}_AVX2_polyw1_pack(buf.coeffs + i * POLYW1_PACKEDBYTES, &w1);
+        PQCLEAN_{ns}_AVX2_poly_caddq(&w1);
+        PQCLEAN_{ns}_AVX2_poly_use_hint(&w1, &w1, &h);
+        PQCLEAN_{ns}_AVX2_polyw1_pack(buf.coeffs + i * POLYW1_PACKEDBYTES, &w1);
     }}
 
     /* Extra indices are zero for strong unforgeability */
@@ -616,17 +1876,20 @@ rej:
             return -1;
         }}
     }}
+    if (znorm) {{
+        return -2;
+    }}
 
-    /* Call random oracle and verify PQCLEAN_DILITHIUM{level}_AVX2_challenge */
+    /* Call random oracle and verify PQCLEAN_{ns}_AVX2_challenge */
     shake256_inc_init(&state);
     shake256_inc_absorb(&state, mu, CRHBYTES);
     shake256_inc_absorb(&state, buf.coeffs, K * POLYW1_PACKEDBYTES);
     shake256_inc_finalize(&state);
-    shake256_inc_squeeze(buf.coeffs, SEEDBYTES, &state);
+    shake256_inc_squeeze(buf.coeffs, {CT}, &state);
     shake256_inc_ctx_release(&state);
-    for (i = 0; i < SEEDBYTES; ++i) {{
+    for (i = 0; i < {CT}; ++i) {{
         if (buf.coeffs[i] != sig[i]) {{
-            return -1;
+            return -3;
         }}
     }}
 
@@ -640,21 +1903,19 @@ rej:
 #include "params.h"
 #include "poly.h"
 #include "polyvec.h"
+#include "cleanse.h"
 #include "sign.h"
 #include "symmetric.h"
-#include <stdint.h>
+#include <stdint.h>{string_h}
 
 {keypair_decl}
-    uint8_t tr[SEEDBYTES];
-    const uint8_t *rho, *rhoprime, *key;
+    uint8_t tr[{TR}];
+{keypair_seedbuf_decl}    const uint8_t *rho, *rhoprime, *key;
     polyvecl mat[K];
     polyvecl s1, s1hat;
     polyveck s2, t1, t0;
 
-    rho = random;
-    rhoprime = rho + SEEDBYTES;
-    key = rhoprime + CRHBYTES;
-
+{keypair_seed_init}
     /* Expand matrix */
     polyvec_matrix_expand(mat, rho);
 
@@ -678,39 +1939,346 @@ rej:
     pack_pk(pk, rho, &t1);
 
     /* Compute H(rho, t1) and write secret key */
-    shake256(tr, SEEDBYTES, pk, CRYPTO_PUBLICKEYBYTES);
+    shake256(tr, {TR}, pk, CRYPTO_PUBLICKEYBYTES);
     pack_sk(sk, rho, tr, key, &t0, &s1, &s2);
 
+    /* Clear secrets from the stack */
+{keypair_seedbuf_cleanse}    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s1hat, sizeof(s1hat));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
     return 0;
 }}
 
-{sign_signature_decl}
-    unsigned int n;
-    uint8_t seedbuf[3 * SEEDBYTES + 2 * CRHBYTES];
-    uint8_t *rho, *tr, *key, *mu, *rhoprime;
-    uint16_t nonce = 0;
-    polyvecl mat[K], s1, y, z;
-    polyveck t0, s2, w1, w0, h;
+{pk_from_sk_decl}
+    unsigned int i;
+    uint8_t seedbuf[2 * SEEDBYTES + {TR}];
+    uint8_t tr[{TR}];
+    uint8_t *rho, *sktr, *key;
+    uint8_t diff = 0;
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2, t1, t0;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + {TR};
+    unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Expand matrix */
+    polyvec_matrix_expand(mat, rho);
+
+    /* Matrix-vector multiplication */
+    polyvecl_ntt(&s1);
+    polyvec_matrix_pointwise_montgomery(&t1, mat, &s1);
+    polyveck_reduce(&t1);
+    polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    polyveck_caddq(&t1);
+    polyveck_power2round(&t1, &t0, &t1);
+    pack_pk(pk, rho, &t1);
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, {TR}, pk, CRYPTO_PUBLICKEYBYTES);
+    for (i = 0; i < {TR}; ++i) {{
+        diff |= tr[i] ^ sktr[i];
+    }}
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    return diff ? -1 : 0;
+}}
+
+{check_sk_decl}
+    unsigned int i, j;
+    int ret = 0;
+    uint8_t seedbuf[2 * SEEDBYTES + {TR}];
+    uint8_t tr[{TR}];
+    uint8_t t0buf[POLYT0_PACKEDBYTES];
+    uint8_t *rho, *sktr, *key;
+    const uint8_t *skt0 = sk + SECRETKEYBYTES - K * POLYT0_PACKEDBYTES;
+    uint8_t diff = 0, t0diff = 0;
+    polyvecl mat[K];
+    polyvecl s1;
+    polyveck s2, t1, t0;
+
+    rho = seedbuf;
+    sktr = rho + SEEDBYTES;
+    key = sktr + {TR};
+    unpack_sk(rho, sktr, key, &t0, &s1, &s2, sk);
+
+    /* Check that the coefficients of s1 and s2 lie in [-ETA, ETA] */
+    if (polyvecl_chknorm(&s1, ETA + 1)
+            || polyveck_chknorm(&s2, ETA + 1)) {{
+        ret = -1;
+    }}
+
+    /* Expand matrix */
+    polyvec_matrix_expand(mat, rho);
+
+    /* Matrix-vector multiplication */
+    polyvecl_ntt(&s1);
+    polyvec_matrix_pointwise_montgomery(&t1, mat, &s1);
+    polyveck_reduce(&t1);
+    polyveck_invntt_tomont(&t1);
+
+    /* Add error vector s2 */
+    polyveck_add(&t1, &t1, &s2);
+
+    /* Extract t1 and write public key */
+    polyveck_caddq(&t1);
+    polyveck_power2round(&t1, &t0, &t1);
+    pack_pk(pk, rho, &t1);
+
+    /* Check the packed t0 of the secret key against the recomputed one */
+    for (i = 0; i < K; ++i) {{
+        polyt0_pack(t0buf, &t0.vec[i]);
+        for (j = 0; j < POLYT0_PACKEDBYTES; ++j) {{
+            t0diff |= t0buf[j] ^ skt0[i * POLYT0_PACKEDBYTES + j];
+        }}
+    }}
+
+    /* Check H(rho, t1) against tr of the secret key */
+    shake256(tr, {TR}, pk, PUBLICKEYBYTES);
+    for (i = 0; i < {TR}; ++i) {{
+        diff |= tr[i] ^ sktr[i];
+    }}
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(t0buf, sizeof(t0buf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    if (ret == 0 && t0diff) {{
+        ret = -2;
+    }}
+    if (ret == 0 && diff) {{
+        ret = -3;
+    }}
+    return ret;
+}}
+
+{check_sig_decl}
+    unsigned int i, j, k;
+    const uint8_t *hint = sig + {CT} + L * POLYZ_PACKEDBYTES;
+    poly z;
+
+    /* Decode h, rejecting it like unpack_sig */
+    k = 0;
+    for (i = 0; i < K; ++i) {{
+        if (hint[OMEGA + i] > OMEGA) {{
+            return -3;
+        }}
+        if (hint[OMEGA + i] < k) {{
+            return -1;
+        }}
+        for (j = k + 1; j < hint[OMEGA + i]; ++j) {{
+            if (hint[j] <= hint[j - 1]) {{
+                return -2;
+            }}
+        }}
+        k = hint[OMEGA + i];
+    }}
+    for (j = k; j < OMEGA; ++j) {{
+        if (hint[j]) {{
+            return -1;
+        }}
+    }}
+
+    /* Check the norm of z */
+    for (i = 0; i < L; ++i) {{
+        polyz_unpack(&z, sig + {CT} + i * POLYZ_PACKEDBYTES);
+        if (poly_chknorm(&z, GAMMA1 - BETA)) {{
+            return -4;
+        }}
+    }}
+
+    return 0;
+}}
+
+{prepare_pk_decl}
+    prepared_pk *p = (prepared_pk *) ppk;
+    uint8_t rho[SEEDBYTES];
+
+    unpack_pk(rho, &p->t1, pk);
+    polyvec_matrix_expand(p->mat, rho);
+    polyveck_shiftl(&p->t1);
+    polyveck_ntt(&p->t1);
+    shake256(p->tr, {TR}, pk, PUBLICKEYBYTES);
+    return 0;
+}}
+
+{verify_prepared_decl}
+    unsigned int i;
+    uint8_t buf[K * POLYW1_PACKEDBYTES];
+    uint8_t mu[CRHBYTES];
+    uint8_t c[{CT}];
+    uint8_t c2[{CT}];
+    const prepared_pk *p = (const prepared_pk *) ppk;
     poly cp;
+    polyvecl z;
+    polyveck t1, w1, h;
     shake256incctx state;
 
-    rho = seedbuf;
-    tr = rho + SEEDBYTES;
-    key = tr + SEEDBYTES;
-    mu = key + SEEDBYTES;
-    rhoprime = mu + CRHBYTES;
-    unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
+{verify_ctx_check}    if (unpack_sig(c, &z, &h, sig)) {{
+        return -1;
+    }}
+    if (polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
+        return -2;
+    }}
 
     /* Compute CRH(tr, msg) */
     shake256_inc_init(&state);
-    shake256_inc_absorb(&state, tr, SEEDBYTES);
-    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_absorb(&state, p->tr, {TR});
+{verify_ctx_absorb}    shake256_inc_absorb(&state, m, mlen);
     shake256_inc_finalize(&state);
     shake256_inc_squeeze(mu, CRHBYTES, &state);
     shake256_inc_ctx_release(&state);
 
-    shake256(rhoprime, CRHBYTES, key, SEEDBYTES + CRHBYTES);
+    /* Matrix-vector multiplication; compute Az - c2^dt1 */
+    poly_challenge(&cp, c);
+
+    polyvecl_ntt(&z);
+    polyvec_matrix_pointwise_montgomery(&w1, p->mat, &z);
+
+    poly_ntt(&cp);
+    polyveck_pointwise_poly_montgomery(&t1, &cp, &p->t1);
+
+    polyveck_sub(&w1, &w1, &t1);
+    polyveck_reduce(&w1);
+    polyveck_invntt_tomont(&w1);
+
+    /* Reconstruct w1 */
+    polyveck_caddq(&w1);
+    polyveck_use_hint(&w1, &w1, &h);
+    polyveck_pack_w1(buf, &w1);
+
+    /* Call random oracle and verify challenge */
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, buf, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(c2, {CT}, &state);
+    shake256_inc_ctx_release(&state);
+    for (i = 0; i < {CT}; ++i) {{
+        if (c[i] != c2[i]) {{
+            return -3;
+        }}
+    }}
+
+    return 0;
+}}
+
+{expand_sk_decl}
+    expanded_sk *e = (expanded_sk *) esk;
+    uint8_t rho[SEEDBYTES];
+
+    unpack_sk(rho, e->tr, e->key, &e->t0, &e->s1, &e->s2, sk);
+    polyvec_matrix_expand(e->mat, rho);
+    polyvecl_ntt(&e->s1);
+    polyveck_ntt(&e->s2);
+    polyveck_ntt(&e->t0);
+    return 0;
+}}
+
+{sign_expanded_decl}
+    unsigned int n;
+    const expanded_sk *e = (const expanded_sk *) esk;
+{esign_seedbuf_decl}    uint16_t nonce = 0;
+    polyvecl y, z;
+    polyveck w1, w0, h;
+    poly cp;
+    shake256incctx state;
+
+{esign_seedbuf_init}
+{sign_mu_rhoprime}
+rej:
+    /* Sample intermediate vector y */
+    polyvecl_uniform_gamma1(&y, rhoprime, nonce++);
+
+    /* Matrix-vector multiplication */
+    z = y;
+    polyvecl_ntt(&z);
+    polyvec_matrix_pointwise_montgomery(&w1, e->mat, &z);
+    polyveck_reduce(&w1);
+    polyveck_invntt_tomont(&w1);
+
+    /* Decompose w and call the random oracle */
+    polyveck_caddq(&w1);
+    polyveck_decompose(&w1, &w0, &w1);
+    polyveck_pack_w1(sig, &w1);
+
+    shake256_inc_init(&state);
+    shake256_inc_absorb(&state, mu, CRHBYTES);
+    shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
+    shake256_inc_finalize(&state);
+    shake256_inc_squeeze(sig, {CT}, &state);
+    shake256_inc_ctx_release(&state);
+    poly_challenge(&cp, sig);
+    poly_ntt(&cp);
+
+    /* Compute z, reject if it reveals secret */
+    polyvecl_pointwise_poly_montgomery(&z, &cp, &e->s1);
+    polyvecl_invntt_tomont(&z);
+    polyvecl_add(&z, &z, &y);
+    polyvecl_reduce(&z);
+    if (polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
+        goto rej;
+    }}
+
+    /* Check that subtracting cs2 does not change high bits of w and low bits
+     * do not reveal secret information */
+    polyveck_pointwise_poly_montgomery(&h, &cp, &e->s2);
+    polyveck_invntt_tomont(&h);
+    polyveck_sub(&w0, &w0, &h);
+    polyveck_reduce(&w0);
+    if (polyveck_chknorm(&w0, GAMMA2 - BETA)) {{
+        goto rej;
+    }}
+
+    /* Compute hints for w1 */
+    polyveck_pointwise_poly_montgomery(&h, &cp, &e->t0);
+    polyveck_invntt_tomont(&h);
+    polyveck_reduce(&h);
+    if (polyveck_chknorm(&h, GAMMA2)) {{
+        goto rej;
+    }}
+
+    polyveck_add(&w0, &w0, &h);
+    n = polyveck_make_hint(&h, &w0, &w1);
+    if (n > OMEGA) {{
+        goto rej;
+    }}
+
+    /* Write signature */
+    pack_sig(sig, sig, &z, &h);
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&y, sizeof(y));
+    pqclean_cleanse(&state, sizeof(state));
+    return 0;
+}}
+
+{sign_signature_decl}
+    unsigned int n;
+{sign_seedbuf_decl}    uint16_t nonce = 0;
+    polyvecl mat[K], s1, y, z;
+    polyveck t0, s2, w1, w0, h;
+    poly cp;
+    shake256incctx state;
+
+{sign_seedbuf_init}    unpack_sk(rho, tr, key, &t0, &s1, &s2, sk);
 
+{sign_mu_rhoprime}
     /* Expand matrix and transform vectors */
     polyvec_matrix_expand(mat, rho);
     polyvecl_ntt(&s1);
@@ -737,7 +2305,7 @@ rej:
     shake256_inc_absorb(&state, mu, CRHBYTES);
     shake256_inc_absorb(&state, sig, K * POLYW1_PACKEDBYTES);
     shake256_inc_finalize(&state);
-    shake256_inc_squeeze(sig, SEEDBYTES, &state);
+    shake256_inc_squeeze(sig, {CT}, &state);
     shake256_inc_ctx_release(&state);
     poly_challenge(&cp, sig);
     poly_ntt(&cp);
@@ -777,6 +2345,14 @@ rej:
 
     /* Write signature */
     pack_sig(sig, sig, &z, &h);
+
+    /* Clear secrets from the stack */
+    pqclean_cleanse(seedbuf, sizeof(seedbuf));
+    pqclean_cleanse(&s1, sizeof(s1));
+    pqclean_cleanse(&s2, sizeof(s2));
+    pqclean_cleanse(&t0, sizeof(t0));
+    pqclean_cleanse(&y, sizeof(y));
+    pqclean_cleanse(&state, sizeof(state));
     return 0;
 }}
 
@@ -785,26 +2361,26 @@ rej:
     uint8_t buf[K * POLYW1_PACKEDBYTES];
     uint8_t rho[SEEDBYTES];
     uint8_t mu[CRHBYTES];
-    uint8_t c[SEEDBYTES];
-    uint8_t c2[SEEDBYTES];
+{verify_pre_decl}    uint8_t c[{CT}];
+    uint8_t c2[{CT}];
     poly cp;
     polyvecl mat[K], z;
     polyveck t1, w1, h;
     shake256incctx state;
 
-    unpack_pk(rho, &t1, pk);
+{verify_ctx_check}    unpack_pk(rho, &t1, pk);
     if (unpack_sig(c, &z, &h, sig)) {{
         return -1;
     }}
     if (polyvecl_chknorm(&z, GAMMA1 - BETA)) {{
-        return -1;
+        return -2;
     }}
 
     /* Compute CRH(H(rho, t1), msg) */
-    shake256(mu, SEEDBYTES, pk, CRYPTO_PUBLICKEYBYTES);
+    shake256(mu, {TR}, pk, CRYPTO_PUBLICKEYBYTES);
     shake256_inc_init(&state);
-    shake256_inc_absorb(&state, mu, SEEDBYTES);
-    shake256_inc_absorb(&state, m, mlen);
+    shake256_inc_absorb(&state, mu, {TR});
+{verify_ctx_absorb}    shake256_inc_absorb(&state, m, mlen);
     shake256_inc_finalize(&state);
     shake256_inc_squeeze(mu, CRHBYTES, &state);
     shake256_inc_ctx_release(&state);
@@ -835,11 +2411,11 @@ rej:
     shake256_inc_absorb(&state, mu, CRHBYTES);
     shake256_inc_absorb(&state, buf, K * POLYW1_PACKEDBYTES);
     shake256_inc_finalize(&state);
-    shake256_inc_squeeze(c2, SEEDBYTES, &state);
+    shake256_inc_squeeze(c2, {CT}, &state);
     shake256_inc_ctx_release(&state);
-    for (i = 0; i < SEEDBYTES; ++i) {{
+    for (i = 0; i < {CT}; ++i) {{
         if (c[i] != c2[i]) {{
-            return -1;
+            return -3;
         }}
     }}
 
@@ -847,8 +2423,8 @@ rej:
 }}
 """
 
-            with open(f"extern/dilithium{level}/{impl}/sign.c", 'w') as h_file:
-                h_file.write(template)
+            with open(f"extern/{name}/{impl}/sign.c", 'w') as h_file:
+                h_file.write(template + pure_wrappers)
 
 if __name__ == "__main__":
     main()
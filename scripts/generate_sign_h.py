@@ -5,45 +5,147 @@ def main():
         spec = json.load(json_file)
 
     for param_set in spec["parameter_sets"]:
-        level = param_set["security_level"]
-        publickey_bytes = param_set["publickey_bytes"]
-        secretkey_bytes = param_set["secretkey_bytes"]
-        signature_bytes = param_set["signature_bytes"]
+        name = param_set["name"]
+        ns = param_set["namespace"]
+        fips204 = param_set["fips204"]
+        prepared_publickey_bytes = param_set["prepared_publickey_bytes"]
+        expanded_secretkey_bytes = param_set["expanded_secretkey_bytes"]
         for impl in param_set["implementations"]:
             assert impl in ["clean", "avx2", "aarch64"]
             IMPL = impl.upper()
 
+            if fips204:
+                keypair_random_param = "const uint8_t seed[SEEDBYTES]"
+                ctx_params = "\n    const uint8_t* ctx, size_t ctxlen,"
+                internal_decls = f"""
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[RNDBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared_internal(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t ppk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_internal(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* pre, size_t prelen,
+    const uint8_t rnd[RNDBYTES],
+    const uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+"""
+            else:
+                keypair_random_param = "uint8_t random[2 * SEEDBYTES + CRHBYTES]"
+                ctx_params = ""
+                internal_decls = f"""
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded_randomized(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,
+    const uint8_t* rnd,
+    const uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_seed_keypair(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES],
+    const uint8_t seed[SEEDBYTES]
+);
+"""
+
             template = f"""\
-#ifndef PQCLEAN_DILITHIUM{level}_{IMPL}_SIGN_H
-#define PQCLEAN_DILITHIUM{level}_{IMPL}_SIGN_H
+#ifndef PQCLEAN_{ns}_{IMPL}_SIGN_H
+#define PQCLEAN_{ns}_{IMPL}_SIGN_H
 #include "params.h"
 #include "poly.h"
 #include "polyvec.h"
 #include <stddef.h>
 #include <stdint.h>
 
-int PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_keypair(
-    uint8_t pk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
-    uint8_t sk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_SECRETKEYBYTES],
-    uint8_t random[2 * SEEDBYTES + CRHBYTES]
+/* Expanded public key of crypto_sign_prepare_pk, aligned to 32 bytes */
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES {prepared_publickey_bytes}
+
+/* Expanded secret key of crypto_sign_expand_sk, aligned to 32 bytes */
+#define PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES {expanded_secretkey_bytes}
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_keypair(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES],
+    {keypair_random_param}
 );
 
-int PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_signature(
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature(
     uint8_t* sig,
-    const uint8_t* m, size_t mlen,
-    const uint8_t sk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
 );
 
-int PQCLEAN_DILITHIUM{level}_{IMPL}_crypto_sign_verify(
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify(
     const uint8_t* sig,
-    const uint8_t* m, size_t mlen,
-    const uint8_t pk[PQCLEAN_DILITHIUM{level}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_pk_from_sk(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sk(
+    uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
 );
 
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_check_sig(
+    const uint8_t sig[PQCLEAN_{ns}_{IMPL}_CRYPTO_BYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_prepare_pk(
+    uint8_t ppk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES],
+    const uint8_t pk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_verify_prepared(
+    const uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t ppk[PQCLEAN_{ns}_{IMPL}_CRYPTO_PREPAREDPUBLICKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_expand_sk(
+    uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES],
+    const uint8_t sk[PQCLEAN_{ns}_{IMPL}_CRYPTO_SECRETKEYBYTES]
+);
+
+int PQCLEAN_{ns}_{IMPL}_crypto_sign_signature_expanded(
+    uint8_t* sig,
+    const uint8_t* m, size_t mlen,{ctx_params}
+    const uint8_t esk[PQCLEAN_{ns}_{IMPL}_CRYPTO_EXPANDEDSECRETKEYBYTES]
+);
+{internal_decls}
 #endif
 """
 
-            with open(f"extern/dilithium{level}/{impl}/sign.h", 'w') as h_file:
+            with open(f"extern/{name}/{impl}/sign.h", 'w') as h_file:
                 h_file.write(template)
 
 if __name__ == "__main__":
@@ -0,0 +1,395 @@
+//! Algorithm agile keys and signatures.
+//!
+//! [`AnyPublicKey`], [`AnySecretKey`] and [`AnySignature`] wrap the types of the
+//! parameter set modules together with their [`Algorithm`]. [`sign`] and
+//! [`verify`] dispatch to the module of the key. The tagged byte encoding is the
+//! algorithm tag byte ([`Algorithm::tag`]) followed by the byte encoding of the
+//! wrapped key or signature, so a stored blob is self-describing.
+//!
+//! Variants only exist for the parameter sets enabled through cargo features.
+//! The tag bytes are fixed, independent of the enabled features.
+
+use crate::{InvalidLength, VerificationResult};
+
+/// Error decoding or combining algorithm agile keys and signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnyError {
+    /// The tagged encoding is empty, so it lacks the algorithm tag.
+    MissingTag,
+    /// The tag byte does not name an algorithm enabled in this build.
+    UnknownAlgorithm(u8),
+    /// The encoding following the tag byte has the wrong length for the
+    /// algorithm.
+    InvalidLength(InvalidLength),
+    /// Key and signature are for different algorithms.
+    AlgorithmMismatch {
+        /// Algorithm of the key.
+        expected: Algorithm,
+        /// Algorithm of the signature.
+        actual: Algorithm,
+    },
+}
+
+impl From<InvalidLength> for AnyError {
+    fn from(err: InvalidLength) -> Self {
+        Self::InvalidLength(err)
+    }
+}
+
+impl core::fmt::Display for AnyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingTag => f.write_str("missing algorithm tag"),
+            Self::UnknownAlgorithm(tag) => write!(f, "unknown algorithm tag {}", tag),
+            Self::InvalidLength(err) => err.fmt(f),
+            Self::AlgorithmMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "algorithm mismatch: expected {}, got {}",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AnyError {}
+
+fn split_tag(bytes: &[u8]) -> Result<(Algorithm, &[u8]), AnyError> {
+    let (&tag, rest) = bytes.split_first().ok_or(AnyError::MissingTag)?;
+    Ok((Algorithm::try_from(tag)?, rest))
+}
+
+fn write_tagged(tag: u8, bytes: &[u8], out: &mut [u8]) -> Result<usize, InvalidLength> {
+    let len = 1 + bytes.len();
+    if out.len() < len {
+        return Err(InvalidLength {
+            expected: len,
+            actual: out.len(),
+        });
+    }
+    out[0] = tag;
+    out[1..len].copy_from_slice(bytes);
+    Ok(len)
+}
+
+macro_rules! impl_any {
+    ($($feature:literal, $variant:ident, $module:ident, $tag:literal, $name:literal;)*) => {
+        /// Signature algorithm, i.e. parameter set.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        // a zero-variant enum, without any parameter set enabled, can not have a repr
+        #[cfg_attr(any($(feature = $feature),*), repr(u8))]
+        #[non_exhaustive]
+        pub enum Algorithm {
+            $(
+                #[doc = concat!("[", $name, "](crate::", stringify!($module), ").")]
+                #[cfg(feature = $feature)]
+                $variant = $tag,
+            )*
+        }
+
+        impl Algorithm {
+            /// All algorithms enabled in this build.
+            pub const ALL: &'static [Algorithm] = &[$(
+                #[cfg(feature = $feature)]
+                Self::$variant,
+            )*];
+
+            /// Tag byte identifying the algorithm in the tagged byte encoding.
+            pub fn tag(self) -> u8 {
+                self as u8
+            }
+
+            /// Human readable name of the algorithm.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant => $name,
+                    )*
+                }
+            }
+
+            /// Length of a public key in bytes.
+            pub fn public_key_len(self) -> usize {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant => crate::ffi::$module::PUBLICKEYBYTES,
+                    )*
+                }
+            }
+
+            /// Length of a secret key in bytes.
+            pub fn secret_key_len(self) -> usize {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant => crate::ffi::$module::SECRETKEYBYTES,
+                    )*
+                }
+            }
+
+            /// Length of a signature in bytes.
+            pub fn signature_len(self) -> usize {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant => crate::ffi::$module::SIGNATUREBYTES,
+                    )*
+                }
+            }
+        }
+
+        impl TryFrom<u8> for Algorithm {
+            type Error = AnyError;
+
+            fn try_from(tag: u8) -> Result<Self, Self::Error> {
+                match tag {
+                    $(
+                        #[cfg(feature = $feature)]
+                        $tag => Ok(Self::$variant),
+                    )*
+                    _ => Err(AnyError::UnknownAlgorithm(tag)),
+                }
+            }
+        }
+
+        /// Public key of any enabled algorithm.
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        #[non_exhaustive]
+        pub enum AnyPublicKey {
+            $(
+                #[doc = concat!(stringify!($module), " public key.")]
+                #[cfg(feature = $feature)]
+                $variant(crate::$module::PublicKey),
+            )*
+        }
+
+        /// Secret key of any enabled algorithm.
+        #[cfg_attr(test, derive(Debug, PartialEq))]
+        #[non_exhaustive]
+        pub enum AnySecretKey {
+            $(
+                #[doc = concat!(stringify!($module), " secret key.")]
+                #[cfg(feature = $feature)]
+                $variant(crate::$module::SecretKey),
+            )*
+        }
+
+        /// Signature of any enabled algorithm.
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        #[non_exhaustive]
+        pub enum AnySignature {
+            $(
+                #[doc = concat!(stringify!($module), " signature.")]
+                #[cfg(feature = $feature)]
+                $variant(crate::$module::Signature),
+            )*
+        }
+
+        $(
+            #[cfg(feature = $feature)]
+            impl From<crate::$module::PublicKey> for AnyPublicKey {
+                fn from(key: crate::$module::PublicKey) -> Self {
+                    Self::$variant(key)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl From<crate::$module::SecretKey> for AnySecretKey {
+                fn from(key: crate::$module::SecretKey) -> Self {
+                    Self::$variant(key)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl From<crate::$module::Signature> for AnySignature {
+                fn from(sig: crate::$module::Signature) -> Self {
+                    Self::$variant(sig)
+                }
+            }
+        )*
+
+        impl_any!(@encoding AnyPublicKey, PublicKey, public_key_len, $($feature, $variant, $module;)*);
+        impl_any!(@encoding AnySecretKey, SecretKey, secret_key_len, $($feature, $variant, $module;)*);
+        impl_any!(@encoding AnySignature, Signature, signature_len, $($feature, $variant, $module;)*);
+
+        /// Sign message `m` with secret key `sk`, using the algorithm of `sk`.
+        pub fn sign<M: AsRef<[u8]>>(m: M, sk: &AnySecretKey) -> AnySignature {
+            match *sk {
+                $(
+                    #[cfg(feature = $feature)]
+                    AnySecretKey::$variant(ref sk) => AnySignature::$variant(crate::$module::sign(m, sk)),
+                )*
+            }
+        }
+
+        /// Verify signature `sig` for message `m` and public key `pk`.
+        ///
+        /// Fails with [`AnyError::AlgorithmMismatch`] if signature and public key are
+        /// for different algorithms.
+        pub fn verify<M: AsRef<[u8]>>(
+            m: M,
+            sig: &AnySignature,
+            pk: &AnyPublicKey,
+        ) -> Result<VerificationResult, AnyError> {
+            #[allow(unreachable_patterns)]
+            match (sig, pk) {
+                $(
+                    #[cfg(feature = $feature)]
+                    (AnySignature::$variant(sig), AnyPublicKey::$variant(pk)) => {
+                        Ok(crate::$module::verify(m, sig, pk))
+                    }
+                )*
+                (sig, pk) => Err(AnyError::AlgorithmMismatch {
+                    expected: pk.algorithm(),
+                    actual: sig.algorithm(),
+                }),
+            }
+        }
+    };
+    (@encoding $any:ident, $inner:ident, $len:ident, $($feature:literal, $variant:ident, $module:ident;)*) => {
+        impl $any {
+            /// The algorithm.
+            pub fn algorithm(&self) -> Algorithm {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(_) => Algorithm::$variant,
+                    )*
+                }
+            }
+
+            /// Byte encoding without algorithm tag.
+            pub fn as_bytes(&self) -> &[u8] {
+                match *self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(ref inner) => inner.as_ref(),
+                    )*
+                }
+            }
+
+            /// Decode from the byte encoding `bytes` without tag, for algorithm `alg`.
+            pub fn from_bytes(alg: Algorithm, bytes: &[u8]) -> Result<Self, InvalidLength> {
+                match alg {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Algorithm::$variant => {
+                            crate::$module::$inner::from_bytes(bytes).map(Self::$variant)
+                        }
+                    )*
+                }
+            }
+
+            /// Decode from the tagged byte encoding.
+            pub fn from_tagged_bytes(bytes: &[u8]) -> Result<Self, AnyError> {
+                let (alg, bytes) = split_tag(bytes)?;
+                Ok(Self::from_bytes(alg, bytes)?)
+            }
+
+            /// Length of the tagged byte encoding.
+            pub fn tagged_len(&self) -> usize {
+                1 + self.algorithm().$len()
+            }
+
+            /// Write the tagged byte encoding to the start of `out` and return its
+            /// length.
+            ///
+            /// Fails if `out` is shorter than [`tagged_len`](Self::tagged_len).
+            pub fn write_tagged_bytes(&self, out: &mut [u8]) -> Result<usize, InvalidLength> {
+                write_tagged(self.algorithm().tag(), self.as_bytes(), out)
+            }
+
+            /// Tagged byte encoding.
+            #[cfg(feature = "alloc")]
+            pub fn to_tagged_bytes(&self) -> alloc::vec::Vec<u8> {
+                let mut out = alloc::vec![0; self.tagged_len()];
+                let _ = self.write_tagged_bytes(&mut out);
+                out
+            }
+        }
+    };
+}
+
+impl_any! {
+    "dilithium2", Dilithium2, dilithium2, 2, "Dilithium2";
+    "dilithium3", Dilithium3, dilithium3, 3, "Dilithium3";
+    "dilithium5", Dilithium5, dilithium5, 5, "Dilithium5";
+    "ml_dsa_44", MlDsa44, ml_dsa_44, 44, "ML-DSA-44";
+    "ml_dsa_65", MlDsa65, ml_dsa_65, 65, "ML-DSA-65";
+    "ml_dsa_87", MlDsa87, ml_dsa_87, 87, "ML-DSA-87";
+}
+
+impl core::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_tags() {
+        for &alg in Algorithm::ALL {
+            assert_eq!(Algorithm::try_from(alg.tag()), Ok(alg));
+        }
+        assert_eq!(Algorithm::try_from(0), Err(AnyError::UnknownAlgorithm(0)));
+    }
+
+    #[cfg(all(feature = "dilithium2", feature = "ml_dsa_65"))]
+    #[test]
+    fn test_sign_verify_tagged() {
+        let mut random = [37u8; crate::ffi::dilithium2::KEYGENRANDOMBYTES];
+        let (pk2, sk2) = crate::dilithium2::generate_keypair(&mut random);
+        let mut random = [37u8; crate::ffi::ml_dsa_65::KEYGENRANDOMBYTES];
+        let (pk65, sk65) = crate::ml_dsa_65::generate_keypair(&mut random);
+        let (pk2, sk2) = (AnyPublicKey::from(pk2), AnySecretKey::from(sk2));
+        let (pk65, sk65) = (AnyPublicKey::from(pk65), AnySecretKey::from(sk65));
+
+        let msg = b"hello world";
+        let sig2 = sign(msg, &sk2);
+        let sig65 = sign(msg, &sk65);
+        assert_eq!(sig2.algorithm(), Algorithm::Dilithium2);
+        assert_eq!(sig65.algorithm(), Algorithm::MlDsa65);
+        assert!(verify(msg, &sig2, &pk2).unwrap().is_ok());
+        assert!(verify(msg, &sig65, &pk65).unwrap().is_ok());
+        assert!(verify(b"hello moon", &sig65, &pk65).unwrap().is_err());
+        assert_eq!(
+            verify(msg, &sig2, &pk65).err(),
+            Some(AnyError::AlgorithmMismatch {
+                expected: Algorithm::MlDsa65,
+                actual: Algorithm::Dilithium2,
+            })
+        );
+
+        let mut buf = [0u8; 1 + crate::ffi::ml_dsa_65::PUBLICKEYBYTES];
+        assert_eq!(pk65.write_tagged_bytes(&mut buf), Ok(buf.len()));
+        assert_eq!(buf[0], 65);
+        assert_eq!(AnyPublicKey::from_tagged_bytes(&buf), Ok(pk65));
+        assert_eq!(
+            AnyPublicKey::from_tagged_bytes(&buf[..buf.len() - 1]),
+            Err(AnyError::InvalidLength(InvalidLength {
+                expected: crate::ffi::ml_dsa_65::PUBLICKEYBYTES,
+                actual: crate::ffi::ml_dsa_65::PUBLICKEYBYTES - 1,
+            }))
+        );
+        assert_eq!(
+            AnyPublicKey::from_tagged_bytes(&[]),
+            Err(AnyError::MissingTag)
+        );
+
+        #[cfg(feature = "alloc")]
+        {
+            let tagged = sk2.to_tagged_bytes();
+            assert_eq!(tagged.len(), sk2.tagged_len());
+            assert_eq!(AnySecretKey::from_tagged_bytes(&tagged), Ok(sk2));
+            let tagged = sig2.to_tagged_bytes();
+            assert_eq!(AnySignature::from_tagged_bytes(&tagged), Ok(sig2));
+        }
+    }
+}
@@ -0,0 +1,780 @@
+//! COSE keys and COSE_Sign1 messages for the ML-DSA parameter sets.
+//!
+//! Follows the IETF JOSE/COSE ML-DSA draft: a key is a COSE_Key of key type
+//! `AKP` (7) with algorithm `ML-DSA-44` (-48), `ML-DSA-65` (-49) or `ML-DSA-87`
+//! (-50), the public key in the `pub` parameter (-1) and, for a private key, the
+//! 32 byte seed in the `priv` parameter (-2). Keys are written in the
+//! deterministic encoding of RFC 8949; when decoding, unknown parameters are
+//! ignored.
+//!
+//! Since the `priv` parameter holds the seed, a private COSE_Key is written from
+//! a `KeySeed` with `KeySeed::to_cose_key`. `SecretKey::from_cose_key` re-derives
+//! the secret key from the seed and rejects a key whose `pub` parameter does not
+//! match it. `PublicKey::to_cose_key` and `PublicKey::from_cose_key` handle the
+//! public COSE_Key; the latter also accepts a private COSE_Key, ignoring `priv`.
+//!
+//! [`CoseSign1Builder`] creates a COSE_Sign1 message (RFC 9052) with the `alg`
+//! header parameter in the protected header, signing the `Sig_structure` with
+//! the module's `sign`, i.e. with an empty context. [`CoseSign1Verifier`] checks
+//! the `alg` header parameter, rejects critical header parameters (`crit`) as
+//! none are understood, verifies the signature with the module's `verify` and
+//! returns the payload.
+//!
+//! The round 3 dilithium parameter sets have no COSE algorithm, so only the
+//! `ml_dsa_X` modules implement these functions.
+//!
+//! ```
+//! # #[cfg(feature = "ml_dsa_44")] {
+//! use dilithium_raw::cose::{CoseSign1Builder, CoseSign1Verifier};
+//! use dilithium_raw::ml_dsa_44::{generate_keypair_from_seed, KeySeed};
+//!
+//! let (pk, sk) = generate_keypair_from_seed(&KeySeed::new([37; 32]));
+//! let message = CoseSign1Builder::new(b"payload").key_id(b"11").sign(&sk);
+//! let payload = CoseSign1Verifier::new().verify(&message, &pk).unwrap();
+//! assert_eq!(payload, b"payload");
+//! # }
+//! ```
+
+use crate::{Algorithm, InvalidLength};
+use alloc::vec::Vec;
+use ciborium::tag::Required;
+use ciborium::Value;
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+/// Label of the `kty` key parameter.
+const KTY: i64 = 1;
+/// Label of the `alg` key parameter.
+const KEY_ALG: i64 = 3;
+/// Label of the `alg` header parameter.
+const ALG: i64 = 1;
+/// Label of the `crit` header parameter.
+const CRIT: i64 = 2;
+/// Label of the `kid` header parameter.
+const KID: i64 = 4;
+/// Key type `AKP`.
+const KTY_AKP: i64 = 7;
+/// Label of the `pub` parameter of an `AKP` key.
+const AKP_PUB: i64 = -1;
+/// Label of the `priv` parameter of an `AKP` key.
+const AKP_PRIV: i64 = -2;
+/// CBOR tag of `COSE_Sign1_Tagged`.
+const COSE_SIGN1_TAG: u64 = 18;
+
+/// Error decoding a COSE_Key or verifying a COSE_Sign1 message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoseError {
+    /// The input is not a single well-formed CBOR item with the structure of a
+    /// COSE_Key or COSE_Sign1 message, or has duplicate labels.
+    Malformed,
+    /// The key type `kty` is not `AKP`.
+    KeyType,
+    /// The algorithm `alg` is not the one of the parameter set.
+    AlgorithmMismatch {
+        /// Algorithm of the parameter set.
+        expected: Algorithm,
+    },
+    /// The key has no `priv` parameter.
+    MissingPrivateKey,
+    /// A key, seed or signature has the wrong length for the algorithm.
+    InvalidLength(InvalidLength),
+    /// The `pub` parameter does not match the key derived from `priv`.
+    InconsistentKey,
+    /// The message has critical header parameters (`crit`), which are not
+    /// supported.
+    CriticalHeader,
+    /// The payload is detached but none was given to the verifier, or it is
+    /// not detached but one was given anyway.
+    DetachedPayload,
+    /// The signature is invalid.
+    InvalidSignature,
+}
+
+impl From<InvalidLength> for CoseError {
+    fn from(err: InvalidLength) -> Self {
+        Self::InvalidLength(err)
+    }
+}
+
+impl core::fmt::Display for CoseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("malformed CBOR"),
+            Self::KeyType => f.write_str("key type is not AKP"),
+            Self::AlgorithmMismatch { expected } => {
+                write!(f, "algorithm mismatch: expected {}", expected)
+            }
+            Self::MissingPrivateKey => f.write_str("missing private key"),
+            Self::InvalidLength(err) => err.fmt(f),
+            Self::InconsistentKey => f.write_str("public key does not match private key"),
+            Self::CriticalHeader => f.write_str("unsupported critical header parameters"),
+            Self::DetachedPayload => f.write_str("payload must be given iff it is detached"),
+            Self::InvalidSignature => f.write_str("invalid signature"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CoseError {}
+
+mod sealed {
+    use super::CoseError;
+
+    pub trait Signer {
+        type Signature: AsRef<[u8]>;
+
+        fn sign_tbs(&self, tbs: &[u8]) -> Self::Signature;
+    }
+
+    pub trait Verifier {
+        fn verify_tbs(&self, tbs: &[u8], signature: &[u8]) -> Result<(), CoseError>;
+    }
+}
+
+/// A secret key which can sign COSE_Sign1 messages, see
+/// [`CoseSign1Builder::sign`].
+///
+/// This trait is sealed: it is only implemented by the `SecretKey` types of the
+/// `ml_dsa_X` modules.
+pub trait CoseSigner: sealed::Signer {
+    /// The algorithm.
+    const ALGORITHM: Algorithm;
+    /// The COSE algorithm identifier.
+    const COSE_ALGORITHM: i64;
+}
+
+/// A public key which can verify COSE_Sign1 messages, see
+/// [`CoseSign1Verifier::verify`].
+///
+/// This trait is sealed: it is only implemented by the `PublicKey` types of the
+/// `ml_dsa_X` modules.
+pub trait CoseVerifier: sealed::Verifier {
+    /// The algorithm.
+    const ALGORITHM: Algorithm;
+    /// The COSE algorithm identifier.
+    const COSE_ALGORITHM: i64;
+}
+
+/// CBOR byte string.
+struct Bstr<'a>(&'a [u8]);
+
+impl Serialize for Bstr<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// `AKP` COSE_Key, with the parameters in deterministic encoding order.
+struct CoseKeyRef<'a> {
+    algorithm: i64,
+    public: &'a [u8],
+    private: Option<&'a [u8]>,
+}
+
+impl Serialize for CoseKeyRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3 + usize::from(self.private.is_some())))?;
+        map.serialize_entry(&KTY, &KTY_AKP)?;
+        map.serialize_entry(&KEY_ALG, &self.algorithm)?;
+        map.serialize_entry(&AKP_PUB, &Bstr(self.public))?;
+        if let Some(private) = self.private {
+            map.serialize_entry(&AKP_PRIV, &Bstr(private))?;
+        }
+        map.end()
+    }
+}
+
+/// Header map with the `alg` and `kid` parameters, if present.
+struct HeaderRef<'a> {
+    algorithm: Option<i64>,
+    key_id: Option<&'a [u8]>,
+}
+
+impl Serialize for HeaderRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = usize::from(self.algorithm.is_some()) + usize::from(self.key_id.is_some());
+        let mut map = serializer.serialize_map(Some(len))?;
+        if let Some(algorithm) = self.algorithm {
+            map.serialize_entry(&ALG, &algorithm)?;
+        }
+        if let Some(key_id) = self.key_id {
+            map.serialize_entry(&KID, &Bstr(key_id))?;
+        }
+        map.end()
+    }
+}
+
+/// Encode `value` as CBOR into `out`.
+fn encode_into(value: &impl Serialize, out: &mut Vec<u8>) {
+    ciborium::into_writer(value, out).expect("encoding to a Vec never fails");
+}
+
+fn encode(value: &impl Serialize) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(value, &mut out);
+    out
+}
+
+/// Decode `bytes` as a single CBOR item.
+fn decode(bytes: &[u8]) -> Result<Value, CoseError> {
+    let mut reader = bytes;
+    let value = ciborium::from_reader(&mut reader).map_err(|_| CoseError::Malformed)?;
+    if !reader.is_empty() {
+        return Err(CoseError::Malformed);
+    }
+    Ok(value)
+}
+
+/// Decode the CBOR map `value`, rejecting duplicate labels.
+fn decode_map(value: Value) -> Result<Vec<(Value, Value)>, CoseError> {
+    let map = value.into_map().map_err(|_| CoseError::Malformed)?;
+    for (i, (label, _)) in map.iter().enumerate() {
+        if map[..i].iter().any(|(other, _)| other == label) {
+            return Err(CoseError::Malformed);
+        }
+    }
+    Ok(map)
+}
+
+fn decode_bytes(value: Value) -> Result<Vec<u8>, CoseError> {
+    value.into_bytes().map_err(|_| CoseError::Malformed)
+}
+
+fn integer(value: &Value) -> Option<i64> {
+    value.as_integer().and_then(|i| i64::try_from(i).ok())
+}
+
+/// Look up the parameter with integer label `label` in `map`.
+fn get(map: &[(Value, Value)], label: i64) -> Option<&Value> {
+    map.iter()
+        .find(|(other, _)| integer(other) == Some(label))
+        .map(|(_, value)| value)
+}
+
+fn check_algorithm(
+    alg: Option<&Value>,
+    algorithm: Algorithm,
+    cose_algorithm: i64,
+) -> Result<(), CoseError> {
+    if alg.and_then(integer) != Some(cose_algorithm) {
+        return Err(CoseError::AlgorithmMismatch {
+            expected: algorithm,
+        });
+    }
+    Ok(())
+}
+
+/// Decoded `AKP` COSE_Key.
+struct CoseKey {
+    public: Vec<u8>,
+    private: Option<Zeroizing<Vec<u8>>>,
+}
+
+impl CoseKey {
+    fn decode(key: &[u8], algorithm: Algorithm, cose_algorithm: i64) -> Result<Self, CoseError> {
+        let mut map = decode_map(decode(key)?)?;
+        let private = match map
+            .iter()
+            .position(|(label, _)| integer(label) == Some(AKP_PRIV))
+        {
+            Some(i) => Some(Zeroizing::new(decode_bytes(map.swap_remove(i).1)?)),
+            None => None,
+        };
+        if get(&map, KTY).and_then(integer) != Some(KTY_AKP) {
+            return Err(CoseError::KeyType);
+        }
+        check_algorithm(get(&map, KEY_ALG), algorithm, cose_algorithm)?;
+        let public = get(&map, AKP_PUB)
+            .and_then(Value::as_bytes)
+            .ok_or(CoseError::Malformed)?
+            .clone();
+        Ok(Self { public, private })
+    }
+
+    /// The `priv` parameter.
+    fn seed(&self) -> Result<Zeroizing<[u8; 32]>, CoseError> {
+        let private = self.private.as_ref().ok_or(CoseError::MissingPrivateKey)?;
+        let mut seed = Zeroizing::new([0; 32]);
+        if private.len() != seed.len() {
+            return Err(InvalidLength {
+                expected: seed.len(),
+                actual: private.len(),
+            }
+            .into());
+        }
+        seed.copy_from_slice(private);
+        Ok(seed)
+    }
+}
+
+fn public_cose_key(cose_algorithm: i64, public_key: &[u8]) -> Vec<u8> {
+    encode(&CoseKeyRef {
+        algorithm: cose_algorithm,
+        public: public_key,
+        private: None,
+    })
+}
+
+fn private_cose_key(cose_algorithm: i64, public_key: &[u8], seed: &[u8]) -> Zeroizing<Vec<u8>> {
+    // reserve enough that the buffer is never reallocated, leaving copies of the
+    // seed behind
+    let mut out = Zeroizing::new(Vec::with_capacity(public_key.len() + 64));
+    let key = CoseKeyRef {
+        algorithm: cose_algorithm,
+        public: public_key,
+        private: Some(seed),
+    };
+    encode_into(&key, &mut out);
+    out
+}
+
+/// Encode the `Sig_structure` of a COSE_Sign1 message.
+fn sig_structure(protected: &[u8], external_aad: &[u8], payload: &[u8]) -> Vec<u8> {
+    encode(&(
+        "Signature1",
+        Bstr(protected),
+        Bstr(external_aad),
+        Bstr(payload),
+    ))
+}
+
+/// Builder for a COSE_Sign1 message.
+///
+/// The message is a `COSE_Sign1_Tagged` with the `alg` header parameter in the
+/// protected header and the optional `kid` header parameter in the unprotected
+/// header.
+#[derive(Debug, Clone, Copy)]
+pub struct CoseSign1Builder<'a> {
+    payload: &'a [u8],
+    external_aad: &'a [u8],
+    key_id: Option<&'a [u8]>,
+    detached: bool,
+    tagged: bool,
+}
+
+impl<'a> CoseSign1Builder<'a> {
+    /// Start a COSE_Sign1 message for `payload`.
+    pub fn new(payload: &'a [u8]) -> Self {
+        Self {
+            payload,
+            external_aad: &[],
+            key_id: None,
+            detached: false,
+            tagged: true,
+        }
+    }
+
+    /// Set the externally supplied data, which is signed but not included in the
+    /// message. Empty by default.
+    pub fn external_aad(mut self, external_aad: &'a [u8]) -> Self {
+        self.external_aad = external_aad;
+        self
+    }
+
+    /// Set the `kid` header parameter.
+    pub fn key_id(mut self, key_id: &'a [u8]) -> Self {
+        self.key_id = Some(key_id);
+        self
+    }
+
+    /// Leave the payload out of the message, see
+    /// [`CoseSign1Verifier::detached_payload`].
+    pub fn detached(mut self) -> Self {
+        self.detached = true;
+        self
+    }
+
+    /// Write a `COSE_Sign1` message without CBOR tag 18.
+    pub fn untagged(mut self) -> Self {
+        self.tagged = false;
+        self
+    }
+
+    /// Sign the message with `key` and encode it.
+    pub fn sign<K: CoseSigner>(&self, key: &K) -> Vec<u8> {
+        let protected = encode(&HeaderRef {
+            algorithm: Some(K::COSE_ALGORITHM),
+            key_id: None,
+        });
+        let signature = key.sign_tbs(&sig_structure(&protected, self.external_aad, self.payload));
+        let message = (
+            Bstr(&protected),
+            HeaderRef {
+                algorithm: None,
+                key_id: self.key_id,
+            },
+            (!self.detached).then_some(Bstr(self.payload)),
+            Bstr(signature.as_ref()),
+        );
+        if self.tagged {
+            encode(&Required::<_, COSE_SIGN1_TAG>(message))
+        } else {
+            encode(&message)
+        }
+    }
+}
+
+/// Verifier of COSE_Sign1 messages.
+///
+/// Accepts both `COSE_Sign1_Tagged` and untagged `COSE_Sign1` messages.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoseSign1Verifier<'a> {
+    external_aad: &'a [u8],
+    detached_payload: Option<&'a [u8]>,
+}
+
+impl<'a> CoseSign1Verifier<'a> {
+    /// Create a verifier with empty external data and no detached payload.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the externally supplied data, which is signed but not included in the
+    /// message. Empty by default.
+    pub fn external_aad(mut self, external_aad: &'a [u8]) -> Self {
+        self.external_aad = external_aad;
+        self
+    }
+
+    /// Set the payload of a message with detached payload.
+    pub fn detached_payload(mut self, payload: &'a [u8]) -> Self {
+        self.detached_payload = Some(payload);
+        self
+    }
+
+    /// Verify the COSE_Sign1 message `message` with `key` and return its
+    /// payload.
+    pub fn verify<K: CoseVerifier>(&self, message: &[u8], key: &K) -> Result<Vec<u8>, CoseError> {
+        let mut message = decode(message)?;
+        if let Value::Tag(tag, inner) = message {
+            if tag != COSE_SIGN1_TAG {
+                return Err(CoseError::Malformed);
+            }
+            message = *inner;
+        }
+        let message = message.into_array().map_err(|_| CoseError::Malformed)?;
+        let [protected, unprotected, payload, signature] =
+            <[Value; 4]>::try_from(message).map_err(|_| CoseError::Malformed)?;
+
+        let protected = decode_bytes(protected)?;
+        let protected_map = if protected.is_empty() {
+            Vec::new()
+        } else {
+            decode_map(decode(&protected)?)?
+        };
+        let unprotected = decode_map(unprotected)?;
+        if protected_map
+            .iter()
+            .any(|(label, _)| unprotected.iter().any(|(other, _)| other == label))
+        {
+            return Err(CoseError::Malformed);
+        }
+        check_algorithm(get(&protected_map, ALG), K::ALGORITHM, K::COSE_ALGORITHM)?;
+        if get(&protected_map, CRIT).is_some() || get(&unprotected, CRIT).is_some() {
+            return Err(CoseError::CriticalHeader);
+        }
+
+        let payload = match (payload, self.detached_payload) {
+            (Value::Bytes(payload), None) => payload,
+            (Value::Null, Some(payload)) => payload.to_vec(),
+            (Value::Bytes(_) | Value::Null, _) => return Err(CoseError::DetachedPayload),
+            _ => return Err(CoseError::Malformed),
+        };
+        let signature = decode_bytes(signature)?;
+        key.verify_tbs(
+            &sig_structure(&protected, self.external_aad, &payload),
+            &signature,
+        )?;
+        Ok(payload)
+    }
+}
+
+macro_rules! impl_cose {
+    ($($feature:literal, $variant:ident, $module:ident, $cose_algorithm:literal;)*) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl sealed::Signer for crate::$module::SecretKey {
+                type Signature = crate::$module::Signature;
+
+                fn sign_tbs(&self, tbs: &[u8]) -> Self::Signature {
+                    crate::$module::sign(tbs, self)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl CoseSigner for crate::$module::SecretKey {
+                const ALGORITHM: Algorithm = Algorithm::$variant;
+                const COSE_ALGORITHM: i64 = $cose_algorithm;
+            }
+
+            #[cfg(feature = $feature)]
+            impl sealed::Verifier for crate::$module::PublicKey {
+                fn verify_tbs(&self, tbs: &[u8], signature: &[u8]) -> Result<(), CoseError> {
+                    let signature = crate::$module::Signature::from_bytes(signature)?;
+                    crate::$module::verify(tbs, &signature, self)
+                        .map_err(|_| CoseError::InvalidSignature)?;
+                    Ok(())
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl CoseVerifier for crate::$module::PublicKey {
+                const ALGORITHM: Algorithm = Algorithm::$variant;
+                const COSE_ALGORITHM: i64 = $cose_algorithm;
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::PublicKey {
+                /// Encode as COSE_Key with the `pub` parameter.
+                pub fn to_cose_key(&self) -> Vec<u8> {
+                    public_cose_key($cose_algorithm, self.as_ref())
+                }
+
+                /// Decode from the `pub` parameter of a COSE_Key.
+                pub fn from_cose_key(key: &[u8]) -> Result<Self, CoseError> {
+                    let key = CoseKey::decode(key, Algorithm::$variant, $cose_algorithm)?;
+                    Ok(Self::from_bytes(&key.public)?)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::SecretKey {
+                /// Decode from the `priv` parameter of a COSE_Key, checking the `pub`
+                /// parameter against it.
+                pub fn from_cose_key(key: &[u8]) -> Result<Self, CoseError> {
+                    Ok(crate::$module::KeySeed::keypair_from_cose_key(key)?.1)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::KeySeed {
+                /// Encode as COSE_Key with the `pub` and `priv` parameters.
+                pub fn to_cose_key(&self) -> Zeroizing<Vec<u8>> {
+                    let (pk, _) = crate::$module::generate_keypair_from_seed(self);
+                    private_cose_key($cose_algorithm, pk.as_ref(), self.as_ref())
+                }
+
+                /// Decode from the `priv` parameter of a COSE_Key, checking the `pub`
+                /// parameter against it.
+                pub fn from_cose_key(key: &[u8]) -> Result<Self, CoseError> {
+                    Ok(Self::keypair_from_cose_key(key)?.0)
+                }
+
+                fn keypair_from_cose_key(
+                    key: &[u8],
+                ) -> Result<(Self, crate::$module::SecretKey), CoseError> {
+                    let key = CoseKey::decode(key, Algorithm::$variant, $cose_algorithm)?;
+                    let seed = Self::new(*key.seed()?);
+                    let public_key = crate::$module::PublicKey::from_bytes(&key.public)?;
+                    let (pk, sk) = crate::$module::generate_keypair_from_seed(&seed);
+                    if pk != public_key {
+                        return Err(CoseError::InconsistentKey);
+                    }
+                    Ok((seed, sk))
+                }
+            }
+        )*
+    };
+}
+
+impl_cose! {
+    "ml_dsa_44", MlDsa44, ml_dsa_44, -48;
+    "ml_dsa_65", MlDsa65, ml_dsa_65, -49;
+    "ml_dsa_87", MlDsa87, ml_dsa_87, -50;
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(feature = "ml_dsa_44")]
+    #[test]
+    fn test_cose_key() {
+        use crate::ffi::ml_dsa_44::KEYGENRANDOMBYTES;
+        use crate::ml_dsa_44::{generate_keypair, KeySeed, PublicKey, SecretKey};
+
+        let mut random = [37u8; KEYGENRANDOMBYTES];
+        let (pk, sk) = generate_keypair(&mut random);
+        let seed = KeySeed::new(random);
+
+        // {1: 7, 3: -48, -1: h'..'}
+        let key = pk.to_cose_key();
+        assert_eq!(
+            key[..10],
+            [0xa3, 0x01, 0x07, 0x03, 0x38, 0x2f, 0x20, 0x59, 0x05, 0x20]
+        );
+        assert_eq!(key[10..], pk.to_bytes());
+        assert_eq!(PublicKey::from_cose_key(&key), Ok(pk.clone()));
+        assert_eq!(
+            SecretKey::from_cose_key(&key).err(),
+            Some(CoseError::MissingPrivateKey)
+        );
+
+        // {1: 7, 3: -48, -1: h'..', -2: h'..'}
+        let key = seed.to_cose_key();
+        assert_eq!(
+            key[..10],
+            [0xa4, 0x01, 0x07, 0x03, 0x38, 0x2f, 0x20, 0x59, 0x05, 0x20]
+        );
+        assert_eq!(key[1322..1325], [0x21, 0x58, 0x20]);
+        assert_eq!(key[1325..], random);
+        assert_eq!(PublicKey::from_cose_key(&key), Ok(pk));
+        assert_eq!(SecretKey::from_cose_key(&key), Ok(sk));
+        assert_eq!(KeySeed::from_cose_key(&key), Ok(seed));
+
+        let mut other = KeySeed::new([38; 32]).to_cose_key();
+        other[1325..].copy_from_slice(&random);
+        assert_eq!(
+            SecretKey::from_cose_key(&other).err(),
+            Some(CoseError::InconsistentKey)
+        );
+        let mut short = key.clone();
+        short[1324] = 31;
+        short.pop();
+        assert_eq!(
+            SecretKey::from_cose_key(&short).err(),
+            Some(CoseError::InvalidLength(InvalidLength {
+                expected: 32,
+                actual: 31
+            }))
+        );
+        let mut okp = key.clone();
+        okp[2] = 0x01;
+        assert_eq!(PublicKey::from_cose_key(&okp), Err(CoseError::KeyType));
+        let mut trailing = key.to_vec();
+        trailing.push(0);
+        assert_eq!(
+            PublicKey::from_cose_key(&trailing),
+            Err(CoseError::Malformed)
+        );
+        assert_eq!(
+            PublicKey::from_cose_key(&[0xa3, 0x01, 0x07, 0x03, 0x38, 0x2f, 0x20, 0x41, 0x00]),
+            Err(CoseError::InvalidLength(InvalidLength {
+                expected: 1312,
+                actual: 1
+            }))
+        );
+        assert_eq!(
+            PublicKey::from_cose_key(&[0xa3, 0x01, 0x07, 0x01, 0x07, 0x20, 0x41, 0x00]),
+            Err(CoseError::Malformed)
+        );
+    }
+
+    #[cfg(feature = "ml_dsa_44")]
+    #[test]
+    fn test_sign1() {
+        use crate::ffi::ml_dsa_44::KEYGENRANDOMBYTES;
+        use crate::ml_dsa_44::generate_keypair;
+
+        let mut random = [37u8; KEYGENRANDOMBYTES];
+        let (pk, sk) = generate_keypair(&mut random);
+        let mut random = [38u8; KEYGENRANDOMBYTES];
+        let (other_pk, _) = generate_keypair(&mut random);
+
+        // 18([<<{1: -48}>>, {4: h'3131'}, h'..', h'..'])
+        let message = CoseSign1Builder::new(b"payload").key_id(b"11").sign(&sk);
+        assert_eq!(
+            message[..20],
+            [
+                0xd2, 0x84, 0x44, 0xa1, 0x01, 0x38, 0x2f, 0xa1, 0x04, 0x42, 0x31, 0x31, 0x47, b'p',
+                b'a', b'y', b'l', b'o', b'a', b'd'
+            ]
+        );
+        assert_eq!(message[20..23], [0x59, 0x09, 0x74]);
+        assert_eq!(message.len(), 23 + 2420);
+        assert_eq!(
+            message,
+            CoseSign1Builder::new(b"payload").key_id(b"11").sign(&sk)
+        );
+        let verifier = CoseSign1Verifier::new();
+        assert_eq!(verifier.verify(&message, &pk).unwrap(), b"payload");
+        assert_eq!(
+            verifier.verify(&message, &other_pk),
+            Err(CoseError::InvalidSignature)
+        );
+        assert_eq!(
+            verifier.detached_payload(b"payload").verify(&message, &pk),
+            Err(CoseError::DetachedPayload)
+        );
+        assert_eq!(
+            verifier.verify(&message[..message.len() - 1], &pk),
+            Err(CoseError::Malformed)
+        );
+
+        let message = CoseSign1Builder::new(b"payload")
+            .external_aad(b"aad")
+            .detached()
+            .untagged()
+            .sign(&sk);
+        assert_eq!(
+            message[..9],
+            [0x84, 0x44, 0xa1, 0x01, 0x38, 0x2f, 0xa0, 0xf6, 0x59]
+        );
+        let verifier = CoseSign1Verifier::new().detached_payload(b"payload");
+        assert_eq!(
+            verifier.verify(&message, &pk),
+            Err(CoseError::InvalidSignature)
+        );
+        let verifier = verifier.external_aad(b"aad");
+        assert_eq!(verifier.verify(&message, &pk).unwrap(), b"payload");
+        assert_eq!(
+            verifier.detached_payload(b"other").verify(&message, &pk),
+            Err(CoseError::InvalidSignature)
+        );
+        assert_eq!(
+            CoseSign1Verifier::new().verify(&message, &pk),
+            Err(CoseError::DetachedPayload)
+        );
+    }
+
+    #[cfg(all(feature = "ml_dsa_44", feature = "ml_dsa_65"))]
+    #[test]
+    fn test_rejects() {
+        use crate::ffi::ml_dsa_44::KEYGENRANDOMBYTES;
+        use crate::ml_dsa_44::generate_keypair;
+
+        let mut random = [37u8; KEYGENRANDOMBYTES];
+        let (pk, sk) = generate_keypair(&mut random);
+        let seed = crate::ml_dsa_44::KeySeed::new(random);
+        let (other_pk, _) =
+            crate::ml_dsa_65::generate_keypair_from_seed(&crate::ml_dsa_65::KeySeed::new(random));
+
+        assert_eq!(
+            crate::ml_dsa_65::PublicKey::from_cose_key(&seed.to_cose_key()),
+            Err(CoseError::AlgorithmMismatch {
+                expected: Algorithm::MlDsa65
+            })
+        );
+        let message = CoseSign1Builder::new(b"payload").sign(&sk);
+        assert_eq!(
+            CoseSign1Verifier::new().verify(&message, &other_pk),
+            Err(CoseError::AlgorithmMismatch {
+                expected: Algorithm::MlDsa65
+            })
+        );
+
+        let with_unprotected = |label: i64, value: Value| {
+            let (tag, message) = decode(&message).unwrap().into_tag().unwrap();
+            let mut message = message.into_array().unwrap();
+            message[1] = Value::Map(Vec::from([(label.into(), value)]));
+            encode(&Value::Tag(tag, Value::Array(message).into()))
+        };
+        let crit = with_unprotected(CRIT, Value::Array(Vec::from([KID.into()])));
+        assert_eq!(
+            CoseSign1Verifier::new().verify(&crit, &pk),
+            Err(CoseError::CriticalHeader)
+        );
+        let alg = with_unprotected(ALG, (-48).into());
+        assert_eq!(
+            CoseSign1Verifier::new().verify(&alg, &pk),
+            Err(CoseError::Malformed)
+        );
+        let kid = with_unprotected(KID, Value::Bytes(b"11".to_vec()));
+        assert_eq!(
+            CoseSign1Verifier::new().verify(&kid, &pk).unwrap(),
+            b"payload"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Selection of the CPU specific implementations at runtime.
+//!
+//! The avx2 implementation is used if the `avx2` target feature is enabled at
+//! compile time. With the `dynamic_cpu_features` feature it is also compiled
+//! for targets without the `avx2` target feature; it is then selected if
+//! runtime detection (requires the `std` feature) finds avx2 support, or if
+//! enabled with [`override_avx2`].
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+const AUTO: u8 = 0;
+const ENABLED: u8 = 1;
+const DISABLED: u8 = 2;
+
+static AVX2: AtomicU8 = AtomicU8::new(AUTO);
+
+/// Override the detection of the `avx2` instruction set extension.
+///
+/// `Some(true)` selects the avx2 implementation (if compiled in), `Some(false)`
+/// the portable one and `None` restores automatic detection. This is the only
+/// way to select the avx2 implementation in a `no_std` build for a target
+/// without the `avx2` target feature.
+///
+/// The override is global and only consulted when an implementation is
+/// selected. Prepared public keys and expanded secret keys are computed by
+/// the implementation selected when they are created and keep using it, so
+/// changing the override while they exist does not affect them.
+///
+/// # Safety
+/// When passing `Some(true)`, the CPU must support the `avx2` instruction set
+/// extension. This must hold for as long as prepared or expanded keys created
+/// while the override is in effect are used.
+pub unsafe fn override_avx2(supported: Option<bool>) {
+    let state = match supported {
+        None => AUTO,
+        Some(true) => ENABLED,
+        Some(false) => DISABLED,
+    };
+    AVX2.store(state, Ordering::Relaxed);
+}
+
+/// Whether the avx2 implementation should be used.
+#[cfg(enable_avx2)]
+pub(crate) fn has_avx2() -> bool {
+    match AVX2.load(Ordering::Relaxed) {
+        ENABLED => true,
+        DISABLED => false,
+        _ => detect_avx2(),
+    }
+}
+
+/// Implementation selected by [`backend`].
+///
+/// Buffers the C implementations precompute, like prepared public keys, have a
+/// layout specific to one implementation, so they record the one they were
+/// computed by instead of selecting it again on every use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Backend {
+    #[cfg_attr(enable_aarch64, allow(dead_code))]
+    Clean,
+    #[cfg(enable_avx2)]
+    Avx2,
+    #[cfg(enable_aarch64)]
+    Aarch64,
+}
+
+/// The implementation to use for a new precomputed buffer.
+pub(crate) fn backend() -> Backend {
+    #[cfg(enable_avx2)]
+    {
+        if has_avx2() {
+            return Backend::Avx2;
+        }
+    }
+    #[cfg(enable_aarch64)]
+    {
+        Backend::Aarch64
+    }
+    #[cfg(not(enable_aarch64))]
+    {
+        Backend::Clean
+    }
+}
+
+#[cfg(enable_avx2)]
+fn detect_avx2() -> bool {
+    if cfg!(target_feature = "avx2") {
+        return true;
+    }
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        false
+    }
+}
@@ -4,3 +4,11 @@ pub mod dilithium2;
 pub mod dilithium3;
 #[cfg(feature = "dilithium5")]
 pub mod dilithium5;
+#[cfg(feature = "ml_dsa_44")]
+pub mod ml_dsa_44;
+#[cfg(feature = "ml_dsa_65")]
+pub mod ml_dsa_65;
+#[cfg(feature = "ml_dsa_87")]
+pub mod ml_dsa_87;
+#[cfg(test)]
+mod stack_check;
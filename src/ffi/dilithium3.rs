@@ -1,8 +1,114 @@
 pub const PUBLICKEYBYTES: usize = 1952;
 pub const SECRETKEYBYTES: usize = 4000;
 pub const SIGNATUREBYTES: usize = 3293;
+pub const KEYGENRANDOMBYTES: usize = 128;
+pub const RNDBYTES: usize = 32;
+pub const SEEDBYTES: usize = 32;
+pub const PREPAREDPUBLICKEYBYTES: usize = 36896;
+pub const EXPANDEDSECRETKEYBYTES: usize = 48192;
 use cty::{c_int, size_t};
 
+/// Public key expanded by `crypto_sign_prepare_pk`, aligned as required by the
+/// C implementations. Its contents depend on the implementation that prepared it.
+#[derive(Clone)]
+#[repr(C, align(32))]
+pub struct PreparedPublicKeyBuf(pub [u8; PREPAREDPUBLICKEYBYTES]);
+
+/// Secret key expanded by `crypto_sign_expand_sk`, aligned as required by the
+/// C implementations. Its contents depend on the implementation that expanded
+/// it, and it must be cleared by the owner.
+#[repr(C, align(32))]
+pub struct ExpandedSecretKeyBuf(pub [u8; EXPANDEDSECRETKEYBYTES]);
+
+/// Secrets which the implementations must not leave behind on the stack, for the
+/// keypair from a seed and the deterministic signature of a message. They are
+/// derived here rather than by the C code under test.
+#[cfg(test)]
+struct StackNeedles {
+    key: [u8; 32],
+    keygen_rhoprime: [u8; 32],
+    signing_rhoprime: [u8; 32],
+    packed_s1: [u8; 32],
+    packed_s2: [u8; 32],
+    s1: [u8; 1024],
+    s2: [u8; 1024],
+    t0: [u8; 32],
+}
+
+#[cfg(test)]
+impl StackNeedles {
+    fn new(seed: &[u8; SEEDBYTES], sk: &[u8; SECRETKEYBYTES], msg: &[u8]) -> Self {
+        use crate::prehash::Shake256;
+
+        let part = |offset: usize| -> [u8; 32] { sk[offset..offset + 32].try_into().unwrap() };
+        // `rho`, then the signing seed `key` and the public key hash `tr`
+        let key = part(32);
+        let tr = &sk[64..96];
+
+        // `rhoprime` follows `rho` in the expanded seed
+        let mut hasher = Shake256::new();
+        hasher.update(seed);
+        let keygen_rhoprime = hasher.finalize()[32..64].try_into().unwrap();
+
+        let mut hasher = Shake256::new();
+        hasher.update(tr);
+        hasher.update(msg);
+        let mu = hasher.finalize();
+        let mut hasher = Shake256::new();
+        hasher.update(key);
+        hasher.update(mu);
+        let signing_rhoprime = hasher.finalize()[..32].try_into().unwrap();
+
+        // the whole first polynomials of `s1` and `s2`, as their coefficients are
+        // too small to tell apart from other data in shorter runs
+        let unpack_eta = |offset: usize| {
+            let mut coefficients = [0; 1024];
+            for (i, coefficient) in coefficients.chunks_exact_mut(4).enumerate() {
+                let bit = offset * 8 + 4 * i;
+                let bits = u16::from_le_bytes([sk[bit / 8], sk[bit / 8 + 1]]) >> (bit % 8);
+                let value = 4 - (bits & 15) as i32;
+                coefficient.copy_from_slice(&value.to_ne_bytes());
+            }
+            coefficients
+        };
+
+        // the first coefficients of the last polynomial of `t0`, which outlives the
+        // others in the implementations that compute `t0` row by row
+        let mut packed = [0; 16];
+        packed[..13].copy_from_slice(&sk[3584..3597]);
+        let packed = u128::from_le_bytes(packed);
+        let mut t0 = [0; 32];
+        for (i, coefficient) in t0.chunks_exact_mut(4).enumerate() {
+            let value = (1 << 12) - ((packed >> (13 * i)) & 0x1fff) as i32;
+            coefficient.copy_from_slice(&value.to_ne_bytes());
+        }
+
+        Self {
+            key,
+            keygen_rhoprime,
+            signing_rhoprime,
+            packed_s1: part(96),
+            packed_s2: part(736),
+            s1: unpack_eta(96),
+            s2: unpack_eta(736),
+            t0,
+        }
+    }
+
+    fn named(&self) -> [(&'static str, &[u8]); 8] {
+        [
+            ("key", &self.key),
+            ("rhoprime of keygen", &self.keygen_rhoprime),
+            ("rhoprime of signing", &self.signing_rhoprime),
+            ("packed s1", &self.packed_s1),
+            ("packed s2", &self.packed_s2),
+            ("s1", &self.s1),
+            ("s2", &self.s2),
+            ("t0", &self.t0),
+        ]
+    }
+}
+
 #[cfg(feature = "dilithium3")]
 pub mod clean {
     use super::*;
@@ -16,7 +122,7 @@ pub mod clean {
         fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_keypair(
             pk: *mut [u8; PUBLICKEYBYTES],
             sk: *mut [u8; SECRETKEYBYTES],
-            random: *mut [u8; 128],
+            random: *mut [u8; KEYGENRANDOMBYTES],
         ) -> c_int;
 
         fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature(
@@ -32,12 +138,75 @@ pub mod clean {
             mlen: size_t,
             pk: *const [u8; PUBLICKEYBYTES],
         ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_pk_from_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_check_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_check_sig(
+            sig: *const [u8; SIGNATUREBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_prepare_pk(
+            ppk: *mut PreparedPublicKeyBuf,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_verify_prepared(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            ppk: *const PreparedPublicKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_expand_sk(
+            esk: *mut ExpandedSecretKeyBuf,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature_expanded(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature_expanded_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_seed_keypair(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *mut [u8; SECRETKEYBYTES],
+            seed: *const [u8; SEEDBYTES],
+        ) -> c_int;
     }
 
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
     pub unsafe fn crypto_sign_keypair(
         pk: &mut [u8; PUBLICKEYBYTES],
         sk: &mut [u8; SECRETKEYBYTES],
-        random: &mut [u8; 128],
+        random: &mut [u8; KEYGENRANDOMBYTES],
     ) -> c_int {
         unsafe {
             PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_keypair(
@@ -48,6 +217,28 @@ pub mod clean {
         }
     }
 
+    /// Generate a new keypair deterministically from the 32 byte seed `seed`.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_seed_keypair(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &mut [u8; SECRETKEYBYTES],
+        seed: &[u8; SEEDBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_seed_keypair(
+                pk as *mut _,
+                sk as *mut _,
+                seed as *const _,
+            )
+        }
+    }
+
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
     pub unsafe fn crypto_sign_signature(
         sig: &mut [u8; SIGNATUREBYTES],
         message: &[u8],
@@ -63,6 +254,35 @@ pub mod clean {
         }
     }
 
+    /// Sign with randomness `rnd` mixed into the derivation of the
+    /// per-signature seed.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_signature_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                sk as *const _,
+            )
+        }
+    }
+
+    /// Returns 0 if the signature is valid, -1 if its hints are malformed, -2 if
+    /// `z` is out of range and -3 if the challenge does not match.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
     pub unsafe fn crypto_sign_verify(
         sig: &[u8; SIGNATUREBYTES],
         message: &[u8],
@@ -78,107 +298,142 @@ pub mod clean {
         }
     }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-
-        #[test]
-        fn test_sign_verify() {
-            let msg = b"hello world";
-
-            let mut seckey = [0u8; SECRETKEYBYTES];
-            let mut pubkey = [0u8; PUBLICKEYBYTES];
-            // not secure random, but deterministic and good enough for the test
-            let mut random = [37u8; 128];
-            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
-            assert_eq!(res, 0);
-
-            let mut sig = [0u8; SIGNATUREBYTES];
-            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
-            assert_eq!(res, 0);
-
-            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
-            assert_eq!(res, 0, "Invalid signature crated!");
-        }
+    /// Recompute the public key from the secret key `sk`, writing it to `pk`.
+    /// Returns a nonzero value if the hash of `pk` does not match the `tr`
+    /// stored in `sk`; `pk` is written nonetheless.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_pk_from_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_pk_from_sk(pk as *mut _, sk as *const _) }
     }
-}
-
-#[cfg(all(feature = "dilithium3", enable_avx2))]
-pub mod avx2 {
-    use super::*;
 
-    #[link(name = "dilithium3_avx2")]
-    extern "C" {
-        /// Generate a new keypair, writing the public key to `pk` and the
-        /// secret key to `sk`. Requires a buffer `random` to be filled
-        /// with cryptographically secure random bytes, living at least
-        /// until the function returns.
-        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_keypair(
-            pk: *mut [u8; PUBLICKEYBYTES],
-            sk: *mut [u8; SECRETKEYBYTES],
-            random: *mut [u8; 128],
-        ) -> c_int;
+    /// Check that the secret key `sk` is consistent and recompute its public key,
+    /// writing it to `pk`. Returns 0 if the secret key is consistent, -1 if a
+    /// coefficient of `s1` or `s2` is out of range, -2 if `t0` does not match the
+    /// recomputed one and -3 if the hash of `pk` does not match the `tr` stored in
+    /// `sk`; `pk` is written in all cases.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_check_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_check_sk(pk as *mut _, sk as *const _) }
+    }
 
-        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature(
-            sig: *mut [u8; SIGNATUREBYTES],
-            m: *const u8,
-            mlen: size_t,
-            sk: *const [u8; SECRETKEYBYTES],
-        ) -> c_int;
+    /// Check that the signature `sig` is canonically encoded. Returns 0 if it is,
+    /// -1 if the hint counters or the unused hint indices are malformed, -2 if
+    /// the hint indices of a polynomial are not strictly increasing, -3 if there
+    /// are too many hints and -4 if a coefficient of `z` is out of range.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_check_sig(sig: &[u8; SIGNATUREBYTES]) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_check_sig(sig as *const _) }
+    }
 
-        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_verify(
-            sig: *const [u8; SIGNATUREBYTES],
-            m: *const u8,
-            mlen: size_t,
-            pk: *const [u8; PUBLICKEYBYTES],
-        ) -> c_int;
+    /// Expand the public key `pk` into `ppk` for verifying many signatures with
+    /// [`crypto_sign_verify_prepared`].
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_prepare_pk(
+        ppk: &mut PreparedPublicKeyBuf,
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_prepare_pk(ppk as *mut _, pk as *const _) }
     }
 
-    pub unsafe fn crypto_sign_keypair(
-        pk: &mut [u8; PUBLICKEYBYTES],
-        sk: &mut [u8; SECRETKEYBYTES],
-        random: &mut [u8; 128],
+    /// Verify with a prepared public key. Returns the same values as
+    /// [`crypto_sign_verify`].
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    /// `ppk` must have been prepared by this implementation.
+    pub unsafe fn crypto_sign_verify_prepared(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        ppk: &PreparedPublicKeyBuf,
     ) -> c_int {
         unsafe {
-            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_keypair(
-                pk as *mut _,
-                sk as *mut _,
-                random as *mut _,
+            PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_verify_prepared(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                ppk as *const _,
             )
         }
     }
 
-    pub unsafe fn crypto_sign_signature(
+    /// Expand the secret key `sk` into `esk` for computing many signatures with
+    /// [`crypto_sign_signature_expanded`].
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_expand_sk(
+        esk: &mut ExpandedSecretKeyBuf,
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_expand_sk(esk as *mut _, sk as *const _) }
+    }
+
+    /// Sign with an expanded secret key.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded(
         sig: &mut [u8; SIGNATUREBYTES],
         message: &[u8],
-        sk: &[u8; SECRETKEYBYTES],
+        esk: &ExpandedSecretKeyBuf,
     ) -> c_int {
         unsafe {
-            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature(
+            PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature_expanded(
                 sig as *mut _,
                 message.as_ptr(),
                 message.len(),
-                sk as *const _,
+                esk as *const _,
             )
         }
     }
 
-    pub unsafe fn crypto_sign_verify(
-        sig: &[u8; SIGNATUREBYTES],
+    /// Sign with an expanded secret key and randomness `rnd` mixed into the
+    /// derivation of the per-signature seed.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
         message: &[u8],
-        pk: &[u8; PUBLICKEYBYTES],
+        rnd: &[u8; RNDBYTES],
+        esk: &ExpandedSecretKeyBuf,
     ) -> c_int {
         unsafe {
-            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_verify(
-                sig as *const _,
+            PQCLEAN_DILITHIUM3_CLEAN_crypto_sign_signature_expanded_randomized(
+                sig as *mut _,
                 message.as_ptr(),
                 message.len(),
-                pk as *const _,
+                rnd.as_ptr(),
+                esk as *const _,
             )
         }
     }
 
-    #[cfg(all(test, target_feature = "avx2"))]
+    #[cfg(test)]
     mod tests {
         use super::*;
 
@@ -189,7 +444,7 @@ pub mod avx2 {
             let mut seckey = [0u8; SECRETKEYBYTES];
             let mut pubkey = [0u8; PUBLICKEYBYTES];
             // not secure random, but deterministic and good enough for the test
-            let mut random = [37u8; 128];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
             let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
             assert_eq!(res, 0);
 
@@ -200,105 +455,1337 @@ pub mod avx2 {
             let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
             assert_eq!(res, 0, "Invalid signature crated!");
         }
-    }
-}
-
-#[cfg(all(feature = "dilithium3", enable_aarch64))]
-pub mod aarch64 {
-    use super::*;
-
-    #[link(name = "dilithium3_aarch64")]
-    extern "C" {
-        /// Generate a new keypair, writing the public key to `pk` and the
-        /// secret key to `sk`. Requires a buffer `random` to be filled
-        /// with cryptographically secure random bytes, living at least
-        /// until the function returns.
-        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_keypair(
-            pk: *mut [u8; PUBLICKEYBYTES],
-            sk: *mut [u8; SECRETKEYBYTES],
-            random: *mut [u8; 128],
-        ) -> c_int;
-
-        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature(
-            sig: *mut [u8; SIGNATUREBYTES],
-            m: *const u8,
-            mlen: size_t,
-            sk: *const [u8; SECRETKEYBYTES],
-        ) -> c_int;
-
-        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_verify(
-            sig: *const [u8; SIGNATUREBYTES],
-            m: *const u8,
-            mlen: size_t,
-            pk: *const [u8; PUBLICKEYBYTES],
-        ) -> c_int;
-    }
 
-    pub unsafe fn crypto_sign_keypair(
-        pk: &mut [u8; PUBLICKEYBYTES],
-        sk: &mut [u8; SECRETKEYBYTES],
-        random: &mut [u8; 128],
-    ) -> c_int {
-        unsafe {
-            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_keypair(
-                pk as *mut _,
-                sk as *mut _,
-                random as *mut _,
-            )
-        }
-    }
+        #[test]
+        fn test_pk_from_sk() {
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
 
-    pub unsafe fn crypto_sign_signature(
-        sig: &mut [u8; SIGNATUREBYTES],
-        message: &[u8],
-        sk: &[u8; SECRETKEYBYTES],
-    ) -> c_int {
-        unsafe {
-            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature(
-                sig as *mut _,
-                message.as_ptr(),
-                message.len(),
-                sk as *const _,
-            )
-        }
-    }
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
 
-    pub unsafe fn crypto_sign_verify(
-        sig: &[u8; SIGNATUREBYTES],
-        message: &[u8],
-        pk: &[u8; PUBLICKEYBYTES],
-    ) -> c_int {
-        unsafe {
-            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_verify(
-                sig as *const _,
-                message.as_ptr(),
-                message.len(),
-                pk as *const _,
-            )
+            // corrupt tr, which follows `rho` and `key` in the secret key
+            seckey[64] ^= 1;
+            let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+            assert_ne!(res, 0);
+            assert_eq!(derived, pubkey);
         }
-    }
-
-    #[cfg(test)]
-    mod tests {
-        use super::*;
 
         #[test]
-        fn test_sign_verify() {
+        fn test_check_sig() {
             let msg = b"hello world";
 
             let mut seckey = [0u8; SECRETKEYBYTES];
             let mut pubkey = [0u8; PUBLICKEYBYTES];
-            // not secure random, but deterministic and good enough for the test
-            let mut random = [37u8; 128];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
             let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
             assert_eq!(res, 0);
 
             let mut sig = [0u8; SIGNATUREBYTES];
             let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
             assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_check_sig(&sig) };
+            assert_eq!(res, 0);
 
-            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
-            assert_eq!(res, 0, "Invalid signature crated!");
+            // the signature ends with the unused hint indices and 6 hint counters
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 6 - 1] = 1;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -1);
+
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -3);
+
+            // `z` follows the 32 byte challenge seed, a first coefficient
+            // with all bits set decodes to 1 - GAMMA1
+            let mut malformed = sig;
+            malformed[32] = 0xff;
+            malformed[32 + 1] = 0xff;
+            malformed[32 + 2] |= 0x0f;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -4);
+        }
+
+        #[test]
+        fn test_verify_prepared() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+
+            let mut ppk = PreparedPublicKeyBuf([0; PREPAREDPUBLICKEYBYTES]);
+            let res = unsafe { crypto_sign_prepare_pk(&mut ppk, &pubkey) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_verify_prepared(&sig, &msg[..], &ppk) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_verify_prepared(&sig, &b"hello warld"[..], &ppk) };
+            assert_eq!(res, -3);
+
+            // same failures as the unprepared verification
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe { crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) };
+            assert_eq!(res, -1);
+            let mut malformed = sig;
+            malformed[64..128].fill(0xff);
+            let res = unsafe { crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) };
+            assert_eq!(res, -2);
+            let res = unsafe { crypto_sign_verify(&malformed, &msg[..], &pubkey) };
+            assert_eq!(res, -2);
+        }
+
+        #[test]
+        fn test_sign_expanded() {
+            let msg = b"hello world";
+            let rnd = [42u8; RNDBYTES];
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let res = unsafe { crypto_sign_expand_sk(&mut esk, &seckey) };
+            assert_eq!(res, 0);
+
+            // the same signatures as with the packed secret key
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let mut expected = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_signature(&mut expected, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+
+            let res = unsafe {
+                crypto_sign_signature_expanded_randomized(&mut sig, &msg[..], &rnd, &esk)
+            };
+            assert_eq!(res, 0);
+            let res =
+                unsafe { crypto_sign_signature_randomized(&mut expected, &msg[..], &rnd, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
+            assert_eq!(res, 0);
+        }
+
+        #[test]
+        fn test_check_sk() {
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // a byte of `s1` with all bits set holds a coefficient below -ETA
+            let mut corrupt = seckey;
+            corrupt[128] = 0xff;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -1);
+
+            // `t0` is stored last
+            let mut corrupt = seckey;
+            corrupt[SECRETKEYBYTES - 1] ^= 1;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -2);
+            assert_eq!(derived, pubkey);
+
+            let mut corrupt = seckey;
+            corrupt[64] ^= 1;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -3);
+            assert_eq!(derived, pubkey);
+        }
+
+        #[test]
+        fn test_stack_scrubbed() {
+            use crate::ffi::stack_check::assert_scrubbed;
+
+            let msg = b"hello world";
+            let seed = [37u8; SEEDBYTES];
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut sig = [0u8; SIGNATUREBYTES];
+
+            let res = unsafe { crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) };
+            assert_eq!(res, 0);
+            let needles = super::super::StackNeedles::new(&seed, &seckey, msg);
+            let needles = needles.named();
+
+            let keygen = || {
+                let res = unsafe { crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(keygen, &needles, "keygen");
+
+            let signing = || {
+                let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(signing, &needles, "signing");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let deriving = || {
+                let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(deriving, &needles, "public key derivation");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let checking = || {
+                let res = unsafe { crypto_sign_check_sk(&mut derived, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(checking, &needles, "secret key check");
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let expanding = || {
+                let res = unsafe { crypto_sign_expand_sk(&mut esk, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(expanding, &needles, "secret key expansion");
+
+            let signing_expanded = || {
+                let res = unsafe { crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(signing_expanded, &needles, "signing with an expanded key");
+        }
+    }
+}
+
+#[cfg(all(feature = "dilithium3", enable_avx2))]
+pub mod avx2 {
+    use super::*;
+
+    #[link(name = "dilithium3_avx2")]
+    extern "C" {
+        /// Generate a new keypair, writing the public key to `pk` and the
+        /// secret key to `sk`. Requires a buffer `random` to be filled
+        /// with cryptographically secure random bytes, living at least
+        /// until the function returns.
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_keypair(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *mut [u8; SECRETKEYBYTES],
+            random: *mut [u8; KEYGENRANDOMBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_verify(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_pk_from_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_check_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_check_sig(sig: *const [u8; SIGNATUREBYTES])
+            -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_prepare_pk(
+            ppk: *mut PreparedPublicKeyBuf,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_verify_prepared(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            ppk: *const PreparedPublicKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_expand_sk(
+            esk: *mut ExpandedSecretKeyBuf,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature_expanded(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature_expanded_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AVX2_crypto_sign_seed_keypair(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *mut [u8; SECRETKEYBYTES],
+            seed: *const [u8; SEEDBYTES],
+        ) -> c_int;
+    }
+
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_keypair(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &mut [u8; SECRETKEYBYTES],
+        random: &mut [u8; KEYGENRANDOMBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_keypair(
+                pk as *mut _,
+                sk as *mut _,
+                random as *mut _,
+            )
+        }
+    }
+
+    /// Generate a new keypair deterministically from the 32 byte seed `seed`.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_seed_keypair(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &mut [u8; SECRETKEYBYTES],
+        seed: &[u8; SEEDBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_seed_keypair(
+                pk as *mut _,
+                sk as *mut _,
+                seed as *const _,
+            )
+        }
+    }
+
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_signature(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                sk as *const _,
+            )
+        }
+    }
+
+    /// Sign with randomness `rnd` mixed into the derivation of the
+    /// per-signature seed.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_signature_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                sk as *const _,
+            )
+        }
+    }
+
+    /// Returns 0 if the signature is valid, -1 if its hints are malformed, -2 if
+    /// `z` is out of range and -3 if the challenge does not match.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_verify(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_verify(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                pk as *const _,
+            )
+        }
+    }
+
+    /// Recompute the public key from the secret key `sk`, writing it to `pk`.
+    /// Returns a nonzero value if the hash of `pk` does not match the `tr`
+    /// stored in `sk`; `pk` is written nonetheless.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_pk_from_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AVX2_crypto_sign_pk_from_sk(pk as *mut _, sk as *const _) }
+    }
+
+    /// Check that the secret key `sk` is consistent and recompute its public key,
+    /// writing it to `pk`. Returns 0 if the secret key is consistent, -1 if a
+    /// coefficient of `s1` or `s2` is out of range, -2 if `t0` does not match the
+    /// recomputed one and -3 if the hash of `pk` does not match the `tr` stored in
+    /// `sk`; `pk` is written in all cases.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_check_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AVX2_crypto_sign_check_sk(pk as *mut _, sk as *const _) }
+    }
+
+    /// Check that the signature `sig` is canonically encoded. Returns 0 if it is,
+    /// -1 if the hint counters or the unused hint indices are malformed, -2 if
+    /// the hint indices of a polynomial are not strictly increasing, -3 if there
+    /// are too many hints and -4 if a coefficient of `z` is out of range.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_check_sig(sig: &[u8; SIGNATUREBYTES]) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AVX2_crypto_sign_check_sig(sig as *const _) }
+    }
+
+    /// Expand the public key `pk` into `ppk` for verifying many signatures with
+    /// [`crypto_sign_verify_prepared`].
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_prepare_pk(
+        ppk: &mut PreparedPublicKeyBuf,
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AVX2_crypto_sign_prepare_pk(ppk as *mut _, pk as *const _) }
+    }
+
+    /// Verify with a prepared public key. Returns the same values as
+    /// [`crypto_sign_verify`].
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    /// `ppk` must have been prepared by this implementation.
+    pub unsafe fn crypto_sign_verify_prepared(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        ppk: &PreparedPublicKeyBuf,
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_verify_prepared(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                ppk as *const _,
+            )
+        }
+    }
+
+    /// Expand the secret key `sk` into `esk` for computing many signatures with
+    /// [`crypto_sign_signature_expanded`].
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    pub unsafe fn crypto_sign_expand_sk(
+        esk: &mut ExpandedSecretKeyBuf,
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AVX2_crypto_sign_expand_sk(esk as *mut _, sk as *const _) }
+    }
+
+    /// Sign with an expanded secret key.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature_expanded(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                esk as *const _,
+            )
+        }
+    }
+
+    /// Sign with an expanded secret key and randomness `rnd` mixed into the
+    /// derivation of the per-signature seed.
+    ///
+    /// # Safety
+    /// The CPU must support the `avx2` instruction set extension.
+    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AVX2_crypto_sign_signature_expanded_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                esk as *const _,
+            )
+        }
+    }
+
+    #[cfg(all(test, target_feature = "avx2"))]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_sign_verify() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            // not secure random, but deterministic and good enough for the test
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+
+            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
+            assert_eq!(res, 0, "Invalid signature crated!");
+        }
+
+        #[test]
+        fn test_pk_from_sk() {
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // corrupt tr, which follows `rho` and `key` in the secret key
+            seckey[64] ^= 1;
+            let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+            assert_ne!(res, 0);
+            assert_eq!(derived, pubkey);
+        }
+
+        #[test]
+        fn test_check_sig() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_check_sig(&sig) };
+            assert_eq!(res, 0);
+
+            // the signature ends with the unused hint indices and 6 hint counters
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 6 - 1] = 1;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -1);
+
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -3);
+
+            // `z` follows the 32 byte challenge seed, a first coefficient
+            // with all bits set decodes to 1 - GAMMA1
+            let mut malformed = sig;
+            malformed[32] = 0xff;
+            malformed[32 + 1] = 0xff;
+            malformed[32 + 2] |= 0x0f;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -4);
+        }
+
+        #[test]
+        fn test_verify_prepared() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+
+            let mut ppk = PreparedPublicKeyBuf([0; PREPAREDPUBLICKEYBYTES]);
+            let res = unsafe { crypto_sign_prepare_pk(&mut ppk, &pubkey) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_verify_prepared(&sig, &msg[..], &ppk) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_verify_prepared(&sig, &b"hello warld"[..], &ppk) };
+            assert_eq!(res, -3);
+
+            // same failures as the unprepared verification
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe { crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) };
+            assert_eq!(res, -1);
+            let mut malformed = sig;
+            malformed[64..128].fill(0xff);
+            let res = unsafe { crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) };
+            assert_eq!(res, -2);
+            let res = unsafe { crypto_sign_verify(&malformed, &msg[..], &pubkey) };
+            assert_eq!(res, -2);
+        }
+
+        #[test]
+        fn test_sign_expanded() {
+            let msg = b"hello world";
+            let rnd = [42u8; RNDBYTES];
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let res = unsafe { crypto_sign_expand_sk(&mut esk, &seckey) };
+            assert_eq!(res, 0);
+
+            // the same signatures as with the packed secret key
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let mut expected = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_signature(&mut expected, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+
+            let res = unsafe {
+                crypto_sign_signature_expanded_randomized(&mut sig, &msg[..], &rnd, &esk)
+            };
+            assert_eq!(res, 0);
+            let res =
+                unsafe { crypto_sign_signature_randomized(&mut expected, &msg[..], &rnd, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
+            assert_eq!(res, 0);
+        }
+
+        #[test]
+        fn test_check_sk() {
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // a byte of `s1` with all bits set holds a coefficient below -ETA
+            let mut corrupt = seckey;
+            corrupt[128] = 0xff;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -1);
+
+            // `t0` is stored last
+            let mut corrupt = seckey;
+            corrupt[SECRETKEYBYTES - 1] ^= 1;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -2);
+            assert_eq!(derived, pubkey);
+
+            let mut corrupt = seckey;
+            corrupt[64] ^= 1;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -3);
+            assert_eq!(derived, pubkey);
+        }
+
+        #[test]
+        fn test_stack_scrubbed() {
+            use crate::ffi::stack_check::assert_scrubbed;
+
+            let msg = b"hello world";
+            let seed = [37u8; SEEDBYTES];
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut sig = [0u8; SIGNATUREBYTES];
+
+            let res = unsafe { crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) };
+            assert_eq!(res, 0);
+            let needles = super::super::StackNeedles::new(&seed, &seckey, msg);
+            let needles = needles.named();
+
+            let keygen = || {
+                let res = unsafe { crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(keygen, &needles, "keygen");
+
+            let signing = || {
+                let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(signing, &needles, "signing");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let deriving = || {
+                let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(deriving, &needles, "public key derivation");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let checking = || {
+                let res = unsafe { crypto_sign_check_sk(&mut derived, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(checking, &needles, "secret key check");
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let expanding = || {
+                let res = unsafe { crypto_sign_expand_sk(&mut esk, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(expanding, &needles, "secret key expansion");
+
+            let signing_expanded = || {
+                let res = unsafe { crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(signing_expanded, &needles, "signing with an expanded key");
+        }
+    }
+}
+
+#[cfg(all(feature = "dilithium3", enable_aarch64))]
+pub mod aarch64 {
+    use super::*;
+
+    #[link(name = "dilithium3_aarch64")]
+    extern "C" {
+        /// Generate a new keypair, writing the public key to `pk` and the
+        /// secret key to `sk`. Requires a buffer `random` to be filled
+        /// with cryptographically secure random bytes, living at least
+        /// until the function returns.
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_keypair(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *mut [u8; SECRETKEYBYTES],
+            random: *mut [u8; KEYGENRANDOMBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_verify(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_pk_from_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_check_sk(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_check_sig(
+            sig: *const [u8; SIGNATUREBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_prepare_pk(
+            ppk: *mut PreparedPublicKeyBuf,
+            pk: *const [u8; PUBLICKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_verify_prepared(
+            sig: *const [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            ppk: *const PreparedPublicKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_expand_sk(
+            esk: *mut ExpandedSecretKeyBuf,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature_expanded(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            sk: *const [u8; SECRETKEYBYTES],
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature_expanded_randomized(
+            sig: *mut [u8; SIGNATUREBYTES],
+            m: *const u8,
+            mlen: size_t,
+            rnd: *const u8,
+            esk: *const ExpandedSecretKeyBuf,
+        ) -> c_int;
+
+        fn PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_seed_keypair(
+            pk: *mut [u8; PUBLICKEYBYTES],
+            sk: *mut [u8; SECRETKEYBYTES],
+            seed: *const [u8; SEEDBYTES],
+        ) -> c_int;
+    }
+
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_keypair(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &mut [u8; SECRETKEYBYTES],
+        random: &mut [u8; KEYGENRANDOMBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_keypair(
+                pk as *mut _,
+                sk as *mut _,
+                random as *mut _,
+            )
+        }
+    }
+
+    /// Generate a new keypair deterministically from the 32 byte seed `seed`.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_seed_keypair(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &mut [u8; SECRETKEYBYTES],
+        seed: &[u8; SEEDBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_seed_keypair(
+                pk as *mut _,
+                sk as *mut _,
+                seed as *const _,
+            )
+        }
+    }
+
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_signature(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                sk as *const _,
+            )
+        }
+    }
+
+    /// Sign with randomness `rnd` mixed into the derivation of the
+    /// per-signature seed.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_signature_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                sk as *const _,
+            )
+        }
+    }
+
+    /// Returns 0 if the signature is valid, -1 if its hints are malformed, -2 if
+    /// `z` is out of range and -3 if the challenge does not match.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_verify(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_verify(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                pk as *const _,
+            )
+        }
+    }
+
+    /// Recompute the public key from the secret key `sk`, writing it to `pk`.
+    /// Returns a nonzero value if the hash of `pk` does not match the `tr`
+    /// stored in `sk`; `pk` is written nonetheless.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_pk_from_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_pk_from_sk(pk as *mut _, sk as *const _) }
+    }
+
+    /// Check that the secret key `sk` is consistent and recompute its public key,
+    /// writing it to `pk`. Returns 0 if the secret key is consistent, -1 if a
+    /// coefficient of `s1` or `s2` is out of range, -2 if `t0` does not match the
+    /// recomputed one and -3 if the hash of `pk` does not match the `tr` stored in
+    /// `sk`; `pk` is written in all cases.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_check_sk(
+        pk: &mut [u8; PUBLICKEYBYTES],
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_check_sk(pk as *mut _, sk as *const _) }
+    }
+
+    /// Check that the signature `sig` is canonically encoded. Returns 0 if it is,
+    /// -1 if the hint counters or the unused hint indices are malformed, -2 if
+    /// the hint indices of a polynomial are not strictly increasing, -3 if there
+    /// are too many hints and -4 if a coefficient of `z` is out of range.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_check_sig(sig: &[u8; SIGNATUREBYTES]) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_check_sig(sig as *const _) }
+    }
+
+    /// Expand the public key `pk` into `ppk` for verifying many signatures with
+    /// [`crypto_sign_verify_prepared`].
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_prepare_pk(
+        ppk: &mut PreparedPublicKeyBuf,
+        pk: &[u8; PUBLICKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_prepare_pk(ppk as *mut _, pk as *const _) }
+    }
+
+    /// Verify with a prepared public key. Returns the same values as
+    /// [`crypto_sign_verify`].
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    /// `ppk` must have been prepared by this implementation.
+    pub unsafe fn crypto_sign_verify_prepared(
+        sig: &[u8; SIGNATUREBYTES],
+        message: &[u8],
+        ppk: &PreparedPublicKeyBuf,
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_verify_prepared(
+                sig as *const _,
+                message.as_ptr(),
+                message.len(),
+                ppk as *const _,
+            )
+        }
+    }
+
+    /// Expand the secret key `sk` into `esk` for computing many signatures with
+    /// [`crypto_sign_signature_expanded`].
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    pub unsafe fn crypto_sign_expand_sk(
+        esk: &mut ExpandedSecretKeyBuf,
+        sk: &[u8; SECRETKEYBYTES],
+    ) -> c_int {
+        unsafe { PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_expand_sk(esk as *mut _, sk as *const _) }
+    }
+
+    /// Sign with an expanded secret key.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature_expanded(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                esk as *const _,
+            )
+        }
+    }
+
+    /// Sign with an expanded secret key and randomness `rnd` mixed into the
+    /// derivation of the per-signature seed.
+    ///
+    /// # Safety
+    /// Calls into the C implementation; the buffer lengths are enforced by the
+    /// argument types.
+    /// `esk` must have been expanded by this implementation.
+    pub unsafe fn crypto_sign_signature_expanded_randomized(
+        sig: &mut [u8; SIGNATUREBYTES],
+        message: &[u8],
+        rnd: &[u8; RNDBYTES],
+        esk: &ExpandedSecretKeyBuf,
+    ) -> c_int {
+        unsafe {
+            PQCLEAN_DILITHIUM3_AARCH64_crypto_sign_signature_expanded_randomized(
+                sig as *mut _,
+                message.as_ptr(),
+                message.len(),
+                rnd.as_ptr(),
+                esk as *const _,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_sign_verify() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            // not secure random, but deterministic and good enough for the test
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+
+            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
+            assert_eq!(res, 0, "Invalid signature crated!");
+        }
+
+        #[test]
+        fn test_pk_from_sk() {
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // corrupt tr, which follows `rho` and `key` in the secret key
+            seckey[64] ^= 1;
+            let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+            assert_ne!(res, 0);
+            assert_eq!(derived, pubkey);
+        }
+
+        #[test]
+        fn test_check_sig() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_check_sig(&sig) };
+            assert_eq!(res, 0);
+
+            // the signature ends with the unused hint indices and 6 hint counters
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 6 - 1] = 1;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -1);
+
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -3);
+
+            // `z` follows the 32 byte challenge seed, a first coefficient
+            // with all bits set decodes to 1 - GAMMA1
+            let mut malformed = sig;
+            malformed[32] = 0xff;
+            malformed[32 + 1] = 0xff;
+            malformed[32 + 2] |= 0x0f;
+            let res = unsafe { crypto_sign_check_sig(&malformed) };
+            assert_eq!(res, -4);
+        }
+
+        #[test]
+        fn test_verify_prepared() {
+            let msg = b"hello world";
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+
+            let mut ppk = PreparedPublicKeyBuf([0; PREPAREDPUBLICKEYBYTES]);
+            let res = unsafe { crypto_sign_prepare_pk(&mut ppk, &pubkey) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_verify_prepared(&sig, &msg[..], &ppk) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_verify_prepared(&sig, &b"hello warld"[..], &ppk) };
+            assert_eq!(res, -3);
+
+            // same failures as the unprepared verification
+            let mut malformed = sig;
+            malformed[SIGNATUREBYTES - 1] = 0xff;
+            let res = unsafe { crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) };
+            assert_eq!(res, -1);
+            let mut malformed = sig;
+            malformed[64..128].fill(0xff);
+            let res = unsafe { crypto_sign_verify_prepared(&malformed, &msg[..], &ppk) };
+            assert_eq!(res, -2);
+            let res = unsafe { crypto_sign_verify(&malformed, &msg[..], &pubkey) };
+            assert_eq!(res, -2);
+        }
+
+        #[test]
+        fn test_sign_expanded() {
+            let msg = b"hello world";
+            let rnd = [42u8; RNDBYTES];
+
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let res = unsafe { crypto_sign_expand_sk(&mut esk, &seckey) };
+            assert_eq!(res, 0);
+
+            // the same signatures as with the packed secret key
+            let mut sig = [0u8; SIGNATUREBYTES];
+            let mut expected = [0u8; SIGNATUREBYTES];
+            let res = unsafe { crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) };
+            assert_eq!(res, 0);
+            let res = unsafe { crypto_sign_signature(&mut expected, &msg[..], &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+
+            let res = unsafe {
+                crypto_sign_signature_expanded_randomized(&mut sig, &msg[..], &rnd, &esk)
+            };
+            assert_eq!(res, 0);
+            let res =
+                unsafe { crypto_sign_signature_randomized(&mut expected, &msg[..], &rnd, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(sig, expected);
+            let res = unsafe { crypto_sign_verify(&sig, &msg[..], &pubkey) };
+            assert_eq!(res, 0);
+        }
+
+        #[test]
+        fn test_check_sk() {
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut random = [37u8; KEYGENRANDOMBYTES];
+            let res = unsafe { crypto_sign_keypair(&mut pubkey, &mut seckey, &mut random) };
+            assert_eq!(res, 0);
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &seckey) };
+            assert_eq!(res, 0);
+            assert_eq!(derived, pubkey);
+
+            // a byte of `s1` with all bits set holds a coefficient below -ETA
+            let mut corrupt = seckey;
+            corrupt[128] = 0xff;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -1);
+
+            // `t0` is stored last
+            let mut corrupt = seckey;
+            corrupt[SECRETKEYBYTES - 1] ^= 1;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -2);
+            assert_eq!(derived, pubkey);
+
+            let mut corrupt = seckey;
+            corrupt[64] ^= 1;
+            let res = unsafe { crypto_sign_check_sk(&mut derived, &corrupt) };
+            assert_eq!(res, -3);
+            assert_eq!(derived, pubkey);
+        }
+
+        #[test]
+        fn test_stack_scrubbed() {
+            use crate::ffi::stack_check::assert_scrubbed;
+
+            let msg = b"hello world";
+            let seed = [37u8; SEEDBYTES];
+            let mut seckey = [0u8; SECRETKEYBYTES];
+            let mut pubkey = [0u8; PUBLICKEYBYTES];
+            let mut sig = [0u8; SIGNATUREBYTES];
+
+            let res = unsafe { crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) };
+            assert_eq!(res, 0);
+            let needles = super::super::StackNeedles::new(&seed, &seckey, msg);
+            let needles = needles.named();
+
+            let keygen = || {
+                let res = unsafe { crypto_sign_seed_keypair(&mut pubkey, &mut seckey, &seed) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(keygen, &needles, "keygen");
+
+            let signing = || {
+                let res = unsafe { crypto_sign_signature(&mut sig, &msg[..], &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(signing, &needles, "signing");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let deriving = || {
+                let res = unsafe { crypto_sign_pk_from_sk(&mut derived, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(deriving, &needles, "public key derivation");
+
+            let mut derived = [0u8; PUBLICKEYBYTES];
+            let checking = || {
+                let res = unsafe { crypto_sign_check_sk(&mut derived, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(checking, &needles, "secret key check");
+
+            let mut esk = ExpandedSecretKeyBuf([0; EXPANDEDSECRETKEYBYTES]);
+            let expanding = || {
+                let res = unsafe { crypto_sign_expand_sk(&mut esk, &seckey) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(expanding, &needles, "secret key expansion");
+
+            let signing_expanded = || {
+                let res = unsafe { crypto_sign_signature_expanded(&mut sig, &msg[..], &esk) };
+                assert_eq!(res, 0);
+            };
+            assert_scrubbed(signing_expanded, &needles, "signing with an expanded key");
         }
     }
 }
@@ -0,0 +1,65 @@
+//! Test harness checking that the C code does not leave secrets behind on the
+//! stack.
+
+use cty::{c_int, c_void};
+
+#[link(name = "pqclean_common_helpers")]
+extern "C" {
+    fn pqclean_stack_check(
+        f: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+        needle: *const u8,
+        needlelen: usize,
+    ) -> c_int;
+}
+
+extern "C" fn trampoline<F: FnMut()>(arg: *mut c_void) {
+    // SAFETY: `arg` is the `&mut F` passed to `pqclean_stack_check` below
+    let f = unsafe { &mut *arg.cast::<F>() };
+    f();
+}
+
+/// Call `f` and report whether `needle` is found afterwards in the stack region
+/// `f` used.
+///
+/// `f` should only write its results through references to buffers outside of
+/// its own frame.
+pub(crate) fn leaves_on_stack<F: FnMut()>(mut f: F, needle: &[u8]) -> bool {
+    assert!(!needle.is_empty());
+    // SAFETY: `f` and `needle` are valid for the duration of the call
+    unsafe {
+        pqclean_stack_check(
+            trampoline::<F>,
+            (&mut f as *mut F).cast(),
+            needle.as_ptr(),
+            needle.len(),
+        ) != 0
+    }
+}
+
+/// Assert that `f` leaves none of the named `needles` behind on the stack,
+/// calling it once per needle.
+pub(crate) fn assert_scrubbed<F: FnMut()>(mut f: F, needles: &[(&str, &[u8])], what: &str) {
+    for (name, needle) in needles {
+        assert!(
+            !leaves_on_stack(&mut f, needle),
+            "{what} left {name} on the stack"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_leftover() {
+        let needle = *b"left behind on purpose by a test";
+
+        let leaking = || {
+            let copy = core::hint::black_box(needle);
+            core::hint::black_box(&copy);
+        };
+        assert!(leaves_on_stack(leaking, &needle));
+    }
+}
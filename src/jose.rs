@@ -0,0 +1,455 @@
+//! JSON Web Keys and compact JWS for the ML-DSA parameter sets.
+//!
+//! Follows the IETF JOSE/COSE ML-DSA draft (draft-ietf-cose-dilithium): a key
+//! is a JWK of key type `AKP` ("algorithm key pair") with `alg` set to
+//! `ML-DSA-44`, `ML-DSA-65` or `ML-DSA-87`, the base64url encoded public key in
+//! the `pub` member and, for a private key, the base64url encoded 32 byte seed
+//! in the `priv` member.
+//!
+//! Since the `priv` member holds the seed, a private JWK is written from a
+//! `KeySeed` with `KeySeed::to_jwk`. `SecretKey::from_jwk` re-derives the secret
+//! key from the seed and rejects a JWK whose `pub` member does not match it.
+//! `PublicKey::to_jwk` and `PublicKey::from_jwk` handle the public JWK; the
+//! latter also accepts a private JWK, ignoring `priv`.
+//!
+//! `SecretKey::sign_jws` creates a compact JWS with the protected header
+//! `{"alg":"ML-DSA-44"}` (for ML-DSA-44), signing the JWS signing input with the
+//! module's `sign`, i.e. with an empty context. `PublicKey::verify_jws` checks
+//! the `alg` header parameter, rejects critical header parameters (`crit`) as
+//! none are understood, verifies the signature with the module's `verify` and
+//! returns the decoded payload.
+//!
+//! The round 3 dilithium parameter sets have no JOSE algorithm, so only the
+//! `ml_dsa_X` modules implement these functions.
+
+use crate::{Algorithm, InvalidLength};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use serde::de::IgnoredAny;
+use zeroize::Zeroizing;
+
+/// Error decoding a JWK or verifying a compact JWS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoseError {
+    /// The JWK or JWS header is not a JSON object with the expected members.
+    Json,
+    /// A member or JWS part is not valid unpadded base64url.
+    Base64,
+    /// The key type `kty` is not `AKP`.
+    KeyType,
+    /// The algorithm `alg` is not the one of the parameter set.
+    AlgorithmMismatch {
+        /// Algorithm of the parameter set.
+        expected: Algorithm,
+    },
+    /// The JWK has no `priv` member.
+    MissingPrivateKey,
+    /// A key, seed or signature has the wrong length for the algorithm.
+    InvalidLength(InvalidLength),
+    /// The `pub` member does not match the key derived from `priv`.
+    InconsistentKey,
+    /// The JWS is not in the compact serialization.
+    MalformedJws,
+    /// The JWS header has critical parameters (`crit`), which are not supported.
+    CriticalHeader,
+    /// The JWS signature is invalid.
+    InvalidSignature,
+}
+
+impl From<InvalidLength> for JoseError {
+    fn from(err: InvalidLength) -> Self {
+        Self::InvalidLength(err)
+    }
+}
+
+impl core::fmt::Display for JoseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Json => f.write_str("invalid JSON"),
+            Self::Base64 => f.write_str("invalid base64url"),
+            Self::KeyType => f.write_str("key type is not AKP"),
+            Self::AlgorithmMismatch { expected } => {
+                write!(f, "algorithm mismatch: expected {}", expected)
+            }
+            Self::MissingPrivateKey => f.write_str("missing private key"),
+            Self::InvalidLength(err) => err.fmt(f),
+            Self::InconsistentKey => f.write_str("public key does not match private key"),
+            Self::MalformedJws => f.write_str("malformed compact JWS"),
+            Self::CriticalHeader => f.write_str("unsupported critical header parameters"),
+            Self::InvalidSignature => f.write_str("invalid signature"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JoseError {}
+
+/// `AKP` JWK. Unknown members are ignored.
+#[derive(serde::Deserialize)]
+struct Jwk<'a> {
+    #[serde(borrow)]
+    kty: Cow<'a, str>,
+    #[serde(borrow)]
+    alg: Cow<'a, str>,
+    #[serde(rename = "pub")]
+    public: &'a str,
+    #[serde(rename = "priv", default)]
+    private: Option<&'a str>,
+}
+
+impl<'a> Jwk<'a> {
+    fn parse(jwk: &'a str, algorithm: Algorithm) -> Result<Self, JoseError> {
+        let jwk: Self = serde_json::from_str(jwk).map_err(|_| JoseError::Json)?;
+        if jwk.kty != "AKP" {
+            return Err(JoseError::KeyType);
+        }
+        check_algorithm(&jwk.alg, algorithm)?;
+        Ok(jwk)
+    }
+
+    /// Decode the `priv` member.
+    fn seed(&self) -> Result<Zeroizing<[u8; 32]>, JoseError> {
+        let private = self.private.ok_or(JoseError::MissingPrivateKey)?;
+        let mut seed = Zeroizing::new([0; 32]);
+        let actual = decoded_len(private);
+        if actual != seed.len() {
+            return Err(InvalidLength {
+                expected: seed.len(),
+                actual,
+            }
+            .into());
+        }
+        Base64UrlUnpadded::decode(private, &mut seed[..]).map_err(|_| JoseError::Base64)?;
+        Ok(seed)
+    }
+}
+
+/// JWS protected header. Parameters other than `alg` and `crit` are ignored.
+#[derive(serde::Deserialize)]
+struct JwsHeader<'a> {
+    #[serde(borrow)]
+    alg: Cow<'a, str>,
+    crit: Option<IgnoredAny>,
+}
+
+fn check_algorithm(alg: &str, algorithm: Algorithm) -> Result<(), JoseError> {
+    if alg != algorithm.name() {
+        return Err(JoseError::AlgorithmMismatch {
+            expected: algorithm,
+        });
+    }
+    Ok(())
+}
+
+/// Length of the data encoded by the unpadded base64 string `encoded`.
+fn decoded_len(encoded: &str) -> usize {
+    encoded.len() * 3 / 4
+}
+
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, JoseError> {
+    Base64UrlUnpadded::decode_vec(encoded).map_err(|_| JoseError::Base64)
+}
+
+/// Write the public JWK.
+fn public_jwk(algorithm: Algorithm, public_key: &[u8]) -> String {
+    format!(
+        r#"{{"kty":"AKP","alg":"{}","pub":"{}"}}"#,
+        algorithm.name(),
+        Base64UrlUnpadded::encode_string(public_key)
+    )
+}
+
+/// Write the private JWK.
+fn private_jwk(algorithm: Algorithm, public_key: &[u8], seed: &[u8]) -> Zeroizing<String> {
+    let seed = Zeroizing::new(Base64UrlUnpadded::encode_string(seed));
+    let mut jwk = Zeroizing::new(public_jwk(algorithm, public_key));
+    jwk.pop();
+    jwk.reserve(10 + seed.len());
+    jwk.push_str(r#","priv":""#);
+    jwk.push_str(&seed);
+    jwk.push_str(r#""}"#);
+    jwk
+}
+
+/// JWS signing input of `payload` with the protected header for `algorithm`.
+fn jws_signing_input(algorithm: Algorithm, payload: &[u8]) -> String {
+    let header = format!(r#"{{"alg":"{}"}}"#, algorithm.name());
+    format!(
+        "{}.{}",
+        Base64UrlUnpadded::encode_string(header.as_bytes()),
+        Base64UrlUnpadded::encode_string(payload)
+    )
+}
+
+/// Split a compact JWS into the signing input, the payload and the signature,
+/// checking the protected header.
+fn split_jws(jws: &str, algorithm: Algorithm) -> Result<(&str, &str, Vec<u8>), JoseError> {
+    let (signing_input, signature) = jws.rsplit_once('.').ok_or(JoseError::MalformedJws)?;
+    let (header, payload) = signing_input
+        .split_once('.')
+        .ok_or(JoseError::MalformedJws)?;
+    if payload.contains('.') {
+        return Err(JoseError::MalformedJws);
+    }
+    let header = decode_base64(header)?;
+    let header: JwsHeader<'_> = serde_json::from_slice(&header).map_err(|_| JoseError::Json)?;
+    check_algorithm(&header.alg, algorithm)?;
+    if header.crit.is_some() {
+        return Err(JoseError::CriticalHeader);
+    }
+    Ok((signing_input, payload, decode_base64(signature)?))
+}
+
+macro_rules! impl_jose {
+    ($($feature:literal, $variant:ident, $module:ident;)*) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl crate::$module::PublicKey {
+                /// Encode as JWK with the `pub` member.
+                pub fn to_jwk(&self) -> String {
+                    public_jwk(Algorithm::$variant, self.as_ref())
+                }
+
+                /// Decode from the `pub` member of a JWK.
+                pub fn from_jwk(jwk: &str) -> Result<Self, JoseError> {
+                    let jwk = Jwk::parse(jwk, Algorithm::$variant)?;
+                    Ok(Self::from_bytes(&decode_base64(jwk.public)?)?)
+                }
+
+                /// Verify the compact JWS `jws` and return its decoded payload.
+                pub fn verify_jws(&self, jws: &str) -> Result<Vec<u8>, JoseError> {
+                    let (signing_input, payload, signature) = split_jws(jws, Algorithm::$variant)?;
+                    let signature = crate::$module::Signature::from_bytes(&signature)?;
+                    crate::$module::verify(signing_input, &signature, self)
+                        .map_err(|_| JoseError::InvalidSignature)?;
+                    decode_base64(payload)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::SecretKey {
+                /// Decode from the `priv` member of a JWK, checking the `pub` member
+                /// against it.
+                pub fn from_jwk(jwk: &str) -> Result<Self, JoseError> {
+                    Ok(crate::$module::KeySeed::keypair_from_jwk(jwk)?.1)
+                }
+
+                /// Sign `payload` as a compact JWS.
+                pub fn sign_jws(&self, payload: &[u8]) -> String {
+                    let mut jws = jws_signing_input(Algorithm::$variant, payload);
+                    let signature = crate::$module::sign(&jws, self);
+                    jws.push('.');
+                    jws.push_str(&Base64UrlUnpadded::encode_string(signature.as_ref()));
+                    jws
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::KeySeed {
+                /// Encode as JWK with the `pub` and `priv` members.
+                pub fn to_jwk(&self) -> Zeroizing<String> {
+                    let (pk, _) = crate::$module::generate_keypair_from_seed(self);
+                    private_jwk(Algorithm::$variant, pk.as_ref(), self.as_ref())
+                }
+
+                /// Decode from the `priv` member of a JWK, checking the `pub` member
+                /// against it.
+                pub fn from_jwk(jwk: &str) -> Result<Self, JoseError> {
+                    Ok(Self::keypair_from_jwk(jwk)?.0)
+                }
+
+                fn keypair_from_jwk(
+                    jwk: &str,
+                ) -> Result<(Self, crate::$module::SecretKey), JoseError> {
+                    let jwk = Jwk::parse(jwk, Algorithm::$variant)?;
+                    let seed = Self::new(*jwk.seed()?);
+                    let public_key = crate::$module::PublicKey::from_bytes(&decode_base64(jwk.public)?)?;
+                    let (pk, sk) = crate::$module::generate_keypair_from_seed(&seed);
+                    if pk != public_key {
+                        return Err(JoseError::InconsistentKey);
+                    }
+                    Ok((seed, sk))
+                }
+            }
+        )*
+    };
+}
+
+impl_jose! {
+    "ml_dsa_44", MlDsa44, ml_dsa_44;
+    "ml_dsa_65", MlDsa65, ml_dsa_65;
+    "ml_dsa_87", MlDsa87, ml_dsa_87;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keys from the seed `00 01 .. 1f` and JWTs signed with them, created with
+    /// the ML-DSA implementation of pyca/cryptography. These are interoperability
+    /// vectors from a third party, not the examples of draft-ietf-cose-dilithium.
+    const VECTORS: &str = include_str!("regression_tests/jose.json");
+
+    macro_rules! test_vectors {
+        ($test:ident, $module:ident, $name:literal) => {
+            #[cfg(feature = $name)]
+            #[test]
+            fn $test() {
+                use crate::$module::{generate_keypair_from_seed, KeySeed, PublicKey, SecretKey};
+
+                let vectors: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+                let vector = &vectors[Algorithm::$test.name()];
+                let jwk = vector["jwk"].to_string();
+                let jws = vector["jws"].as_str().unwrap();
+
+                let mut bytes = [0; 32];
+                for (b, i) in bytes.iter_mut().zip(0..) {
+                    *b = i;
+                }
+                let seed = KeySeed::new(bytes);
+                let (pk, sk) = generate_keypair_from_seed(&seed);
+                assert_eq!(PublicKey::from_jwk(&jwk), Ok(pk.clone()));
+                assert_eq!(SecretKey::from_jwk(&jwk), Ok(sk));
+                assert_eq!(KeySeed::from_jwk(&jwk), Ok(seed));
+                let seed = KeySeed::from_jwk(&jwk).unwrap();
+                assert_eq!(
+                    serde_json::from_str::<serde_json::Value>(&seed.to_jwk()).unwrap(),
+                    vector["jwk"]
+                );
+                assert_eq!(PublicKey::from_jwk(&pk.to_jwk()), Ok(pk.clone()));
+
+                let payload = pk.verify_jws(jws).unwrap();
+                assert_eq!(
+                    payload,
+                    br#"{"iss":"joe","exp":1300819380,"http://example.com/is_root":true}"#
+                );
+                let sk = SecretKey::from_jwk(&jwk).unwrap();
+                let jws = sk.sign_jws(&payload);
+                assert_eq!(pk.verify_jws(&jws).unwrap(), payload);
+            }
+        };
+    }
+
+    /// Checks the members of the JWK and the parts of the compact JWS against the
+    /// encoding the draft specifies, independently of the implementation.
+    ///
+    /// The JWK and JWS examples from the appendix of draft-ietf-cose-dilithium
+    /// are not included yet, for any parameter set: they could not be obtained
+    /// when this test was written, and vectors from other implementations are
+    /// not substituted for them. Until they are added, the draft is only checked
+    /// through its encoding rules here.
+    #[cfg(feature = "ml_dsa_44")]
+    #[test]
+    fn test_draft_encoding() {
+        use crate::ml_dsa_44::{generate_keypair_from_seed, sign, KeySeed};
+
+        let decode = |value: &str| Base64UrlUnpadded::decode_vec(value).unwrap();
+        let seed = KeySeed::new([37; 32]);
+        let (pk, sk) = generate_keypair_from_seed(&seed);
+
+        let members = |jwk: &serde_json::Value| {
+            let mut members: Vec<String> = jwk.as_object().unwrap().keys().cloned().collect();
+            members.sort();
+            members
+        };
+        let jwk: serde_json::Value = serde_json::from_str(&seed.to_jwk()).unwrap();
+        assert_eq!(members(&jwk), ["alg", "kty", "priv", "pub"]);
+        assert_eq!(jwk["kty"], "AKP");
+        assert_eq!(jwk["alg"], "ML-DSA-44");
+        assert_eq!(decode(jwk["pub"].as_str().unwrap()), pk.to_bytes());
+        assert_eq!(decode(jwk["priv"].as_str().unwrap()), [37; 32]);
+        let jwk: serde_json::Value = serde_json::from_str(&pk.to_jwk()).unwrap();
+        assert_eq!(members(&jwk), ["alg", "kty", "pub"]);
+
+        let jws = sk.sign_jws(b"payload");
+        let parts: Vec<&str> = jws.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(decode(parts[0]), br#"{"alg":"ML-DSA-44"}"#);
+        assert_eq!(decode(parts[1]), b"payload");
+        let signing_input = format!("{}.{}", parts[0], parts[1]);
+        assert_eq!(decode(parts[2]), sign(signing_input, &sk).to_bytes());
+    }
+
+    #[cfg(all(feature = "ml_dsa_44", feature = "ml_dsa_65"))]
+    #[test]
+    fn test_rejects() {
+        use crate::ml_dsa_44::{generate_keypair_from_seed, KeySeed, PublicKey, SecretKey};
+
+        let seed = KeySeed::new([37; 32]);
+        let (pk, sk) = generate_keypair_from_seed(&seed);
+        let jwk = seed.to_jwk();
+        assert_eq!(
+            crate::ml_dsa_65::PublicKey::from_jwk(&jwk),
+            Err(JoseError::AlgorithmMismatch {
+                expected: Algorithm::MlDsa65
+            })
+        );
+        assert_eq!(
+            SecretKey::from_jwk(&pk.to_jwk()).err(),
+            Some(JoseError::MissingPrivateKey)
+        );
+        let other = KeySeed::new([38; 32]).to_jwk();
+        let (_, other_priv) = other.split_once(r#","priv""#).unwrap();
+        let (pub_part, _) = jwk.split_once(r#","priv""#).unwrap();
+        let mixed = format!(r#"{},"priv"{}"#, pub_part, other_priv);
+        assert_eq!(
+            SecretKey::from_jwk(&mixed).err(),
+            Some(JoseError::InconsistentKey)
+        );
+        assert_eq!(
+            PublicKey::from_jwk(&jwk.replace("AKP", "OKP")),
+            Err(JoseError::KeyType)
+        );
+        assert_eq!(
+            PublicKey::from_jwk(r#"{"kty":"AKP","alg":"ML-DSA-44","pub":"AAAA"}"#),
+            Err(JoseError::InvalidLength(InvalidLength {
+                expected: 1312,
+                actual: 3
+            }))
+        );
+
+        let jws = sk.sign_jws(b"payload");
+        assert_eq!(pk.verify_jws(&jws).unwrap(), b"payload");
+        let (signing_input, _) = jws.rsplit_once('.').unwrap();
+        let other_jws = crate::ml_dsa_44::SecretKey::from_jwk(&other)
+            .unwrap()
+            .sign_jws(b"payload");
+        let (_, other_sig) = other_jws.rsplit_once('.').unwrap();
+        assert_eq!(
+            pk.verify_jws(&format!("{}.{}", signing_input, other_sig)),
+            Err(JoseError::InvalidSignature)
+        );
+        assert_eq!(
+            pk.verify_jws(&format!("{}.x.{}", signing_input, other_sig)),
+            Err(JoseError::MalformedJws)
+        );
+        assert_eq!(
+            crate::ml_dsa_65::PublicKey::from_jwk(
+                &crate::ml_dsa_65::KeySeed::new([37; 32]).to_jwk()
+            )
+            .unwrap()
+            .verify_jws(&jws),
+            Err(JoseError::AlgorithmMismatch {
+                expected: Algorithm::MlDsa65
+            })
+        );
+        let header = Base64UrlUnpadded::encode_string(br#"{"alg":"ML-DSA-44","crit":["b64"]}"#);
+        let (_, rest) = jws.split_once('.').unwrap();
+        assert_eq!(
+            pk.verify_jws(&format!("{}.{}", header, rest)),
+            Err(JoseError::CriticalHeader)
+        );
+    }
+
+    #[allow(non_snake_case)]
+    mod vectors {
+        use super::*;
+
+        test_vectors!(MlDsa44, ml_dsa_44, "ml_dsa_44");
+        test_vectors!(MlDsa65, ml_dsa_65, "ml_dsa_65");
+        test_vectors!(MlDsa87, ml_dsa_87, "ml_dsa_87");
+    }
+}
@@ -2,10 +2,30 @@
 //!
 //! Uses a slightly modified version of the C code of [`pqclean`] as the actual
 //! implementation, which is compiled by a build script. The API is modified to
-//! put the user in control of required randomness.
+//! put the user in control of required randomness. The C code does not
+//! allocate: key generation, signing and verification never touch the heap and
+//! never abort the process.
 //!
 //! The library has a minimal set of dependencies: in the default configuration
-//! (without [`serde`] support) only [`cty`].
+//! (without [`serde`] support) only [`cty`]. The optional `zeroize` feature
+//! pulls in [`zeroize`] to clear secret keys on drop, and the optional
+//! `signature` feature implements the traits of the [`signature`] crate. With
+//! the optional `rand_core` feature, `generate_keypair_with_rng` and
+//! `sign_with_rng` take their randomness from a [`rand_core`] RNG. The optional
+//! `pkcs8` feature encodes keys as PKCS#8 and SubjectPublicKeyInfo DER using
+//! the [`pkcs8`] crate, see [`mod@pkcs8`], and the optional `pem` feature
+//! additionally as PEM, see [`pem`]. The optional `jose` feature adds JSON Web
+//! Keys and compact JWS for the ML-DSA parameter sets, see [`jose`], and the
+//! optional `cose` feature COSE keys and COSE_Sign1 messages, see [`cose`].
+//! With the optional `serde` feature, keys and signatures are byte arrays in
+//! binary formats and base64 strings in human-readable formats, see
+//! [`util::serde`].
+//!
+//! The crate is `no_std` when the default `std` feature is disabled. `std` is
+//! only used for runtime CPU feature detection (see [`cpu`]) and the
+//! `std::error::Error` implementations. The `alloc` feature, implied by `std`,
+//! adds the allocating `to_tagged_bytes` to the algorithm agile types of
+//! [`any`].
 //!
 //! # Security
 //! __Warning__: This crate is intended as a lower level crate implementing a
@@ -21,10 +41,15 @@
 //! USE AT YOUR OWN RISK!
 //!
 //! # Usage
-//! The API is located in the `dilithiumX` module, for X in {2, 3, 5}. To
-//! generate a keypair, use `generate_keypair`. Note: it requires a buffer
-//! filled with cryptographically secure random bytes. The random buffer is not
-//! modified, so zeroization is left to the user. Example:
+//! The API is located in the `dilithiumX` module, for X in {2, 3, 5}, and in
+//! the `ml_dsa_X` module, for X in {44, 65, 87}. The `dilithiumX` modules
+//! implement round 3 dilithium, whereas the `ml_dsa_X` modules implement the
+//! final FIPS 204 standard; the two are not interoperable. To generate a
+//! keypair, use `generate_keypair`. Note: it requires a buffer filled with
+//! cryptographically secure random bytes (128 bytes for dilithium, a 32 byte
+//! seed for ML-DSA). The random buffer is not modified, so zeroization is left
+//! to the user; with the `zeroize` feature, `generate_keypair_from_randomness`
+//! takes an owned `KeygenRandomness` which clears itself instead. Example:
 //! ```
 //! use dilithium_raw::dilithium5::generate_keypair;
 //! use rand::rngs::OsRng;
@@ -61,25 +86,238 @@
 //!
 //! [`cty`]: https://crates.io/crates/cty
 //! [`pqclean`]: https://github.com/PQClean/PQClean
+//! [`pkcs8`]: https://crates.io/crates/pkcs8
 //! [`serde`]: https://crates.io/crates/serde
+//! [`rand_core`]: https://crates.io/crates/rand_core
+//! [`signature`]: https://crates.io/crates/signature
+//! [`zeroize`]: https://crates.io/crates/zeroize
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(rust_2018_compatibility, unsafe_op_in_unsafe_fn)]
 #![deny(future_incompatible, rust_2018_idioms)]
 #![warn(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+// without a parameter set most of the crate has nothing to be used by
+#![cfg_attr(
+    not(any(
+        feature = "dilithium2",
+        feature = "dilithium3",
+        feature = "dilithium5",
+        feature = "ml_dsa_44",
+        feature = "ml_dsa_65",
+        feature = "ml_dsa_87"
+    )),
+    allow(unused)
+)]
+
+#[cfg(any(
+    feature = "alloc",
+    feature = "pem",
+    all(
+        any(feature = "cose", feature = "jose"),
+        any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87")
+    )
+))]
+extern crate alloc;
 
+pub mod any;
+#[cfg(all(
+    feature = "cose",
+    any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87")
+))]
+pub mod cose;
+pub mod cpu;
 /// Low level C bindings.
 pub mod ffi;
+#[cfg(all(
+    feature = "jose",
+    any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87")
+))]
+pub mod jose;
+pub mod parameter_set;
+#[cfg(feature = "pem")]
+pub mod pem;
+#[cfg(feature = "pkcs8")]
+pub mod pkcs8;
+pub mod prehash;
 /// Utilities, mostly for use in this crate.
 pub mod util;
 
+pub use any::{Algorithm, AnyPublicKey, AnySecretKey, AnySignature};
+#[cfg(feature = "dilithium2")]
+pub use parameter_set::Dilithium2;
+#[cfg(feature = "dilithium3")]
+pub use parameter_set::Dilithium3;
+#[cfg(feature = "dilithium5")]
+pub use parameter_set::Dilithium5;
+#[cfg(feature = "ml_dsa_44")]
+pub use parameter_set::MlDsa44;
+#[cfg(feature = "ml_dsa_65")]
+pub use parameter_set::MlDsa65;
+#[cfg(feature = "ml_dsa_87")]
+pub use parameter_set::MlDsa87;
+pub use parameter_set::ParameterSet;
+
 /// Message did verify correctly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VerificationOk;
+
 /// Message did not verify against the given signature.
-pub struct VerificationFailure;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationFailure {
+    /// Why the signature was rejected.
+    pub reason: FailureReason,
+}
+
+impl VerificationFailure {
+    /// Map a nonzero return value of the C verification functions.
+    pub(crate) fn from_code(code: cty::c_int) -> Self {
+        let reason = match code {
+            -1 => FailureReason::MalformedHint,
+            -2 => FailureReason::ZNormTooLarge,
+            -3 => FailureReason::ChallengeMismatch,
+            // -4, for a context string that is too long, which is rejected before
+            // calling into C, or a code added to the C code later
+            _ => FailureReason::Other,
+        };
+        Self { reason }
+    }
+}
+
+impl core::fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "signature verification failed: {}", self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationFailure {}
+
+/// Reason why a signature was rejected, see [`VerificationFailure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The hints of the signature are malformed.
+    MalformedHint,
+    /// The norm of the response `z` of the signature is too large.
+    ZNormTooLarge,
+    /// The challenge recomputed from the message, the public key and the
+    /// signature does not match the one in the signature. This is the failure
+    /// for a well-formed signature of another message or key.
+    ChallengeMismatch,
+    /// The C implementation rejected the signature with a result the other
+    /// reasons do not cover. The arguments are checked before verifying, so this
+    /// is not expected to occur.
+    Other,
+}
+
+impl core::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::MalformedHint => "malformed hint",
+            Self::ZNormTooLarge => "norm of z too large",
+            Self::ChallengeMismatch => "challenge mismatch",
+            Self::Other => "rejected by the implementation",
+        })
+    }
+}
 
 /// Type alias for the return type of verification checks.
 pub type VerificationResult = Result<VerificationOk, VerificationFailure>;
 
+/// The context string passed to `sign_with_context` or `verify_with_context` is
+/// longer than 255 bytes, the maximum allowed by FIPS 204.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextTooLong;
+
+impl core::fmt::Display for ContextTooLong {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("context string longer than 255 bytes")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ContextTooLong {}
+
+/// A byte slice could not be converted into a key or signature because it has
+/// the wrong length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidLength {
+    /// Length required by the target type.
+    pub expected: usize,
+    /// Length of the given byte slice.
+    pub actual: usize,
+}
+
+impl core::fmt::Display for InvalidLength {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "invalid length {}, expected {} bytes",
+            self.actual, self.expected
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidLength {}
+
+/// A secret key is inconsistent, as detected by `SecretKey::validate` of the
+/// parameter set modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {
+    /// A coefficient of the secret vectors `s1` or `s2` is outside of `[-η, η]`.
+    SecretOutOfRange,
+    /// The low bits `t0` stored in the secret key do not match the ones recomputed
+    /// from `s1` and `s2`.
+    LowBitsMismatch,
+    /// The public key recomputed from the secret key is not the given public key.
+    PublicKeyMismatch,
+    /// The hash `tr` stored in the secret key is not the hash of the public key.
+    HashMismatch,
+}
+
+impl core::fmt::Display for KeyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::SecretOutOfRange => "secret key coefficient out of range",
+            Self::LowBitsMismatch => "secret key t0 does not match s1 and s2",
+            Self::PublicKeyMismatch => "secret key does not match the public key",
+            Self::HashMismatch => "secret key tr does not match the public key hash",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyError {}
+
+/// Reason why a public key or signature is not canonically encoded, as reported
+/// by `check_encoding` of the parameter set modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MalformedReason {
+    /// A hint counter is smaller than the previous one, or an unused hint index is
+    /// not zero.
+    HintEncoding,
+    /// The hint indices of a polynomial are not strictly increasing.
+    HintOrder,
+    /// There are more than ω hints.
+    ExcessHints,
+    /// A coefficient of the response `z` is outside of `(-(γ1 - β), γ1 - β)`.
+    ZNorm,
+}
+
+impl core::fmt::Display for MalformedReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::HintEncoding => "malformed hint encoding",
+            Self::HintOrder => "hint indices out of order",
+            Self::ExcessHints => "too many hints",
+            Self::ZNorm => "response z out of range",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MalformedReason {}
+
 mod sanity {
     // We need this equality because in the build script we can only get the width
     // of a pointer, not that of a `usize`.
@@ -109,6 +347,30 @@ pub mod dilithium5 {
     crate::macros::impl_dilithium_module!("regression_tests/dilithium5.ron");
 }
 
+/// Module containing a mid-level API to ML-DSA-44 (FIPS 204).
+#[cfg(feature = "ml_dsa_44")]
+pub mod ml_dsa_44 {
+    use crate::ffi::ml_dsa_44::*;
+    crate::macros::impl_dilithium_module!("regression_tests/ml_dsa_44.ron");
+    crate::macros::impl_fips204_module!("regression_tests/ml_dsa_44_kat.json");
+}
+
+/// Module containing a mid-level API to ML-DSA-65 (FIPS 204).
+#[cfg(feature = "ml_dsa_65")]
+pub mod ml_dsa_65 {
+    use crate::ffi::ml_dsa_65::*;
+    crate::macros::impl_dilithium_module!("regression_tests/ml_dsa_65.ron");
+    crate::macros::impl_fips204_module!("regression_tests/ml_dsa_65_kat.json");
+}
+
+/// Module containing a mid-level API to ML-DSA-87 (FIPS 204).
+#[cfg(feature = "ml_dsa_87")]
+pub mod ml_dsa_87 {
+    use crate::ffi::ml_dsa_87::*;
+    crate::macros::impl_dilithium_module!("regression_tests/ml_dsa_87.ron");
+    crate::macros::impl_fips204_module!("regression_tests/ml_dsa_87_kat.json");
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -116,4 +378,22 @@ mod tests {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_failure_codes() {
+        use super::{FailureReason, VerificationFailure};
+
+        assert_eq!(
+            VerificationFailure::from_code(-3).reason,
+            FailureReason::ChallengeMismatch
+        );
+        assert_eq!(
+            VerificationFailure::from_code(-4).reason,
+            FailureReason::Other
+        );
+        assert_eq!(
+            VerificationFailure::from_code(-5).reason,
+            FailureReason::Other
+        );
+    }
 }
@@ -20,7 +20,207 @@ macro_rules! newtype_from {
     };
 }
 
-pub(crate) use {newtype_as_ref, newtype_from};
+macro_rules! newtype_bytes {
+    ($new_ty:ident, $len:expr) => {
+        impl core::convert::TryFrom<&[u8]> for $new_ty {
+            type Error = $crate::InvalidLength;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                $crate::util::ByteArray::try_from(bytes).map(Self)
+            }
+        }
+
+        impl $new_ty {
+            #[doc = concat!("Create a [`", stringify!($new_ty), "`] from its byte encoding.")]
+            ///
+            /// Fails if `bytes` is not exactly
+            #[doc = concat!("[`", stringify!($len), "`] bytes long.")]
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, $crate::InvalidLength> {
+                Self::try_from(bytes)
+            }
+
+            /// Copy out the byte encoding.
+            pub fn to_bytes(&self) -> [u8; $len] {
+                self.0 .0
+            }
+
+            /// Convert into the byte encoding.
+            pub fn into_bytes(self) -> [u8; $len] {
+                self.0 .0
+            }
+        }
+    };
+}
+
+macro_rules! impl_signature_traits {
+    () => {
+        /// Secret key together with its public key, implementing
+        /// [`signature::Keypair`].
+        #[cfg(feature = "signature")]
+        pub struct SigningKey {
+            secret_key: SecretKey,
+            public_key: PublicKey,
+        }
+
+        #[cfg(feature = "signature")]
+        impl SigningKey {
+            /// Combine secret key `secret_key` with its public key `public_key`.
+            ///
+            /// It is not checked that the keys belong together.
+            pub fn new(secret_key: SecretKey, public_key: PublicKey) -> Self {
+                Self {
+                    secret_key,
+                    public_key,
+                }
+            }
+
+            /// Generate a new signing key, see [`generate_keypair`].
+            pub fn generate(random: &mut [u8; KEYGENRANDOMBYTES]) -> Self {
+                let (public_key, secret_key) = generate_keypair(random);
+                Self::new(secret_key, public_key)
+            }
+
+            /// Derive the signing key from `seed`, see [`generate_keypair_from_seed`].
+            pub fn from_seed(seed: &KeySeed) -> Self {
+                let (public_key, secret_key) = generate_keypair_from_seed(seed);
+                Self::new(secret_key, public_key)
+            }
+
+            /// The secret key.
+            pub fn secret_key(&self) -> &SecretKey {
+                &self.secret_key
+            }
+
+            /// The public key.
+            pub fn public_key(&self) -> &PublicKey {
+                &self.public_key
+            }
+
+            /// Split into public and secret key.
+            pub fn into_keypair(self) -> (PublicKey, SecretKey) {
+                (self.public_key, self.secret_key)
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::Keypair for SigningKey {
+            type VerifyingKey = PublicKey;
+
+            fn verifying_key(&self) -> PublicKey {
+                self.public_key.clone()
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::Signer<Signature> for SecretKey {
+            fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+                Ok(sign(msg, self))
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::Signer<Signature> for SigningKey {
+            fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+                Ok(sign(msg, &self.secret_key))
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::RandomizedSigner<Signature> for SecretKey {
+            fn try_sign_with_rng(
+                &self,
+                rng: &mut impl signature::rand_core::CryptoRngCore,
+                msg: &[u8],
+            ) -> Result<Signature, signature::Error> {
+                sign_with_fill(msg, self, |rnd| rng.try_fill_bytes(rnd))
+                    .map_err(|_| signature::Error::new())
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::RandomizedSigner<Signature> for SigningKey {
+            fn try_sign_with_rng(
+                &self,
+                rng: &mut impl signature::rand_core::CryptoRngCore,
+                msg: &[u8],
+            ) -> Result<Signature, signature::Error> {
+                self.secret_key.try_sign_with_rng(rng, msg)
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::Verifier<Signature> for PublicKey {
+            fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), signature::Error> {
+                verify(msg, signature, self)
+                    .map(|_| ())
+                    .map_err(|_| signature::Error::new())
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl From<Signature> for [u8; SIGNATUREBYTES] {
+            fn from(sig: Signature) -> Self {
+                sig.into_bytes()
+            }
+        }
+
+        #[cfg(feature = "signature")]
+        impl signature::SignatureEncoding for Signature {
+            type Repr = [u8; SIGNATUREBYTES];
+        }
+
+        #[cfg(all(test, feature = "signature"))]
+        mod signature_tests {
+            use super::*;
+            use signature::{Keypair, RandomizedSigner, SignatureEncoding, Signer, Verifier};
+
+            fn sign_generic<S: Signer<Signature>>(signer: &S, msg: &[u8]) -> Signature {
+                signer.sign(msg)
+            }
+
+            fn verify_generic<V: Verifier<Signature>>(
+                verifier: &V,
+                msg: &[u8],
+                sig: &Signature,
+            ) -> bool {
+                verifier.verify(msg, sig).is_ok()
+            }
+
+            #[test]
+            fn test_signer_verifier() {
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let signing_key = SigningKey::generate(&mut random);
+                let msg = b"hello world";
+
+                let sig = sign_generic(&signing_key, msg);
+                assert_eq!(sig, sign_generic(signing_key.secret_key(), msg));
+                assert_eq!(sig, sign(msg, signing_key.secret_key()));
+                assert!(verify_generic(&signing_key.verifying_key(), msg, &sig));
+                assert!(!verify_generic(
+                    signing_key.public_key(),
+                    b"hello moon",
+                    &sig
+                ));
+
+                let bytes: [u8; SIGNATUREBYTES] = sig.clone().into();
+                assert_eq!(Signature::try_from(&bytes[..]), Ok(sig.clone()));
+                assert_eq!(sig.encoded_len(), SIGNATUREBYTES);
+            }
+
+            #[test]
+            fn test_randomized_signer() {
+                let signing_key = SigningKey::from_seed(&KeySeed::new([37u8; SEEDBYTES]));
+                let msg = b"hello world";
+
+                let sig = signing_key.sign_with_rng(&mut rand::rngs::OsRng, msg);
+                assert!(signing_key.public_key().verify(msg, &sig).is_ok());
+                assert_ne!(sig, signing_key.sign(msg));
+            }
+        }
+    };
+}
+
+pub(crate) use {impl_signature_traits, newtype_as_ref, newtype_bytes, newtype_from};
 
 macro_rules! impl_dilithium_module {
     ($regression_test_file:expr) => {
@@ -33,11 +233,23 @@ macro_rules! impl_dilithium_module {
 
         /// Secret key.
         ///
-        /// Internally consists of a fixed length byte array.
+        /// Internally consists of a fixed length byte array. With the `zeroize` feature
+        /// the key is zeroized on drop; it is then also [`Clone`], since every copy
+        /// clears itself. It is never `Copy`.
         #[cfg_attr(test, derive(Debug, PartialEq))]
         #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct SecretKey($crate::util::ByteArray<SECRETKEYBYTES>);
 
+        /// Seed from which a keypair is derived deterministically, see
+        /// [`generate_keypair_from_seed`].
+        ///
+        /// Internally consists of a fixed length byte array of [`SEEDBYTES`] bytes.
+        /// Storing the seed instead of the [`SecretKey`] suffices to recreate the
+        /// secret key with [`SecretKey::from_seed`].
+        #[cfg_attr(test, derive(Debug, PartialEq))]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct KeySeed($crate::util::ByteArray<SEEDBYTES>);
+
         /// Signature.
         #[derive(Debug, PartialEq, Eq, Clone)]
         #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -52,9 +264,14 @@ macro_rules! impl_dilithium_module {
         $crate::macros::newtype_as_ref!(PublicKey, $crate::util::ByteArray<PUBLICKEYBYTES>);
         $crate::macros::newtype_as_ref!(SecretKey, $crate::util::ByteArray<SECRETKEYBYTES>);
         $crate::macros::newtype_as_ref!(Signature, $crate::util::ByteArray<SIGNATUREBYTES>);
+        $crate::macros::newtype_as_ref!(KeySeed, $crate::util::ByteArray<SEEDBYTES>);
         $crate::macros::newtype_from!(PublicKey, $crate::util::ByteArray<PUBLICKEYBYTES>);
         $crate::macros::newtype_from!(SecretKey, $crate::util::ByteArray<SECRETKEYBYTES>);
         $crate::macros::newtype_from!(Signature, $crate::util::ByteArray<SIGNATUREBYTES>);
+        $crate::macros::newtype_from!(KeySeed, $crate::util::ByteArray<SEEDBYTES>);
+        $crate::macros::newtype_bytes!(PublicKey, PUBLICKEYBYTES);
+        $crate::macros::newtype_bytes!(SecretKey, SECRETKEYBYTES);
+        $crate::macros::newtype_bytes!(Signature, SIGNATUREBYTES);
 
         // You never want to change a public key, right?
         #[cfg(any(test, feature = "hazmat"))]
@@ -110,6 +327,411 @@ macro_rules! impl_dilithium_module {
             }
         }
 
+        impl core::convert::AsRef<[u8]> for KeySeed {
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_ref()
+            }
+        }
+
+        impl KeySeed {
+            /// Create a seed from `seed`.
+            ///
+            /// # Security
+            /// The seed MUST be filled with cryptographically secure random bytes. Use a
+            /// proper cryptographically secure random number generator for it (e.g.
+            /// `rand::rngs::OsRng`)!
+            pub fn new(seed: [u8; SEEDBYTES]) -> Self {
+                Self($crate::util::ByteArray::new(seed))
+            }
+        }
+
+        impl From<[u8; SEEDBYTES]> for KeySeed {
+            /// Same as [`KeySeed::new`].
+            fn from(seed: [u8; SEEDBYTES]) -> Self {
+                Self::new(seed)
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Clone for SecretKey {
+            fn clone(&self) -> Self {
+                Self(self.0)
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for SecretKey {
+            fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for SecretKey {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for SecretKey {}
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for KeySeed {
+            fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for KeySeed {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for KeySeed {}
+
+        /// Owned buffer of [`KEYGENRANDOMBYTES`] random bytes for
+        /// [`generate_keypair_from_randomness`], which is zeroized on drop.
+        #[cfg(feature = "zeroize")]
+        pub struct KeygenRandomness([u8; KEYGENRANDOMBYTES]);
+
+        #[cfg(feature = "zeroize")]
+        impl KeygenRandomness {
+            /// Take ownership of the random bytes `random`.
+            ///
+            /// # Security
+            /// The buffer `random` MUST be completely filled with cryptographically secure
+            /// random bytes. Prefer filling a zeroed buffer in place through
+            /// [`AsMut`], so no copy of the randomness is left on the stack.
+            pub fn new(random: [u8; KEYGENRANDOMBYTES]) -> Self {
+                Self(random)
+            }
+
+            /// Create a zeroed buffer, to be filled in place through [`AsMut`].
+            pub fn zeroed() -> Self {
+                Self([0; KEYGENRANDOMBYTES])
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl core::convert::AsMut<[u8]> for KeygenRandomness {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0[..]
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for KeygenRandomness {
+            fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl Drop for KeygenRandomness {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(self);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for KeygenRandomness {}
+
+        impl SecretKey {
+            /// Re-derive the secret key from the seed it was generated from with
+            /// [`generate_keypair_from_seed`].
+            pub fn from_seed(seed: &KeySeed) -> Self {
+                generate_keypair_from_seed(seed).1
+            }
+
+            /// Recompute the public key belonging to this secret key.
+            ///
+            /// Fails with [`KeyError::HashMismatch`](crate::KeyError::HashMismatch) if the
+            /// hash of the recomputed public key does not match the one stored in the
+            /// secret key, which only happens for a corrupt secret key, e.g. one created
+            /// with [`SecretKey::from_bytes`] from arbitrary bytes. Use
+            /// [`validate`](Self::validate) to check the secret key in full.
+            pub fn public_key(&self) -> Result<PublicKey, $crate::KeyError> {
+                let mut pk = PublicKey::empty();
+                // SAFETY: `pk` and `self` buffers are valid for reads and writes and live
+                // long enough
+                let res =
+                    unsafe { detect_arch::crypto_sign_pk_from_sk(pk.0.as_mut(), self.0.as_ref()) };
+                if res == 0 {
+                    Ok(pk)
+                } else {
+                    Err($crate::KeyError::HashMismatch)
+                }
+            }
+
+            /// Check that the secret key is consistent, and if `pk` is given, that it
+            /// belongs to the public key `pk`.
+            ///
+            /// This checks that the coefficients of `s1` and `s2` are in range, that
+            /// `t0` matches the value recomputed from them, that the recomputed
+            /// public key equals `pk` and that the hash `tr` stored in the secret key
+            /// is the hash of the public key. A secret key that fails these checks
+            /// creates signatures that do not verify.
+            pub fn validate(&self, pk: Option<&PublicKey>) -> Result<(), $crate::KeyError> {
+                let mut computed = PublicKey::empty();
+                // SAFETY: `computed` and `self` buffers are valid for reads and writes and
+                // live long enough
+                let res = unsafe {
+                    detect_arch::crypto_sign_check_sk(computed.0.as_mut(), self.0.as_ref())
+                };
+                match res {
+                    0 | -3 => {}
+                    -1 => return Err($crate::KeyError::SecretOutOfRange),
+                    // -2, the only other result
+                    _ => return Err($crate::KeyError::LowBitsMismatch),
+                }
+                if let Some(pk) = pk {
+                    if *pk != computed {
+                        return Err($crate::KeyError::PublicKeyMismatch);
+                    }
+                }
+                if res != 0 {
+                    return Err($crate::KeyError::HashMismatch);
+                }
+                Ok(())
+            }
+        }
+
+        impl PublicKey {
+            /// Check that the public key is canonically encoded.
+            ///
+            /// Every byte string of the right length is the encoding of a public key,
+            /// so this never fails. It exists to check public keys and signatures
+            /// alike, see [`Signature::check_encoding`].
+            pub fn check_encoding(&self) -> Result<(), $crate::MalformedReason> {
+                Ok(())
+            }
+        }
+
+        impl Signature {
+            /// Check that the signature is canonically encoded.
+            ///
+            /// [`verify`] rejects a signature that fails this check without telling
+            /// why, so this allows rejecting and logging malformed signatures early. A
+            /// signature passing it may still fail to verify.
+            pub fn check_encoding(&self) -> Result<(), $crate::MalformedReason> {
+                // SAFETY: `self` buffer is valid for reads and lives long enough
+                let res = unsafe { detect_arch::crypto_sign_check_sig(self.0.as_ref()) };
+                match res {
+                    0 => Ok(()),
+                    -1 => Err($crate::MalformedReason::HintEncoding),
+                    -2 => Err($crate::MalformedReason::HintOrder),
+                    -3 => Err($crate::MalformedReason::ExcessHints),
+                    // -4, the only other result
+                    _ => Err($crate::MalformedReason::ZNorm),
+                }
+            }
+        }
+
+        /// Public key prepared for verifying many signatures.
+        ///
+        /// [`verify`] expands the matrix `A` from the public key, transforms `t1` and
+        /// hashes the public key on every call, which dominates its cost. A prepared
+        /// public key does this once, so verifying with [`PreparedPublicKey::verify`]
+        /// is considerably faster. It is large, though: every polynomial of the matrix
+        /// and of `t1` takes 1 KiB, so box it if it is moved around a lot.
+        #[derive(Clone)]
+        pub struct PreparedPublicKey {
+            backend: $crate::cpu::Backend,
+            ppk: PreparedPublicKeyBuf,
+        }
+
+        impl PreparedPublicKey {
+            /// Prepare the public key `pk`.
+            pub fn new(pk: &PublicKey) -> Self {
+                let backend = $crate::cpu::backend();
+                let mut ppk = PreparedPublicKeyBuf([0; PREPAREDPUBLICKEYBYTES]);
+                // SAFETY: `ppk` and `pk` buffers are valid for reads and writes and live
+                // long enough, and `backend` was selected for this CPU
+                unsafe { detect_arch::crypto_sign_prepare_pk(backend, &mut ppk, pk.0.as_ref()) };
+                Self { backend, ppk }
+            }
+
+            /// Verify signature `sig` for message `m`, with the same result as
+            /// [`verify`] with the public key this was prepared from.
+            pub fn verify<M: AsRef<[u8]>>(
+                &self,
+                m: M,
+                sig: &Signature,
+            ) -> crate::VerificationResult {
+                let message: &[u8] = m.as_ref();
+
+                // SAFETY: `self.ppk` was prepared by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and live long enough
+                let res = unsafe {
+                    detect_arch::crypto_sign_verify_prepared(
+                        self.backend,
+                        sig.0.as_ref(),
+                        message,
+                        &self.ppk,
+                    )
+                };
+
+                if res == 0 {
+                    Ok(crate::VerificationOk)
+                } else {
+                    Err(crate::VerificationFailure::from_code(res))
+                }
+            }
+        }
+
+        impl From<&PublicKey> for PreparedPublicKey {
+            fn from(pk: &PublicKey) -> Self {
+                Self::new(pk)
+            }
+        }
+
+        impl core::fmt::Debug for PreparedPublicKey {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct("PreparedPublicKey").finish_non_exhaustive()
+            }
+        }
+
+        /// Secret key expanded for computing many signatures, which is zeroized on
+        /// drop.
+        ///
+        /// [`sign`] unpacks the secret key, expands the matrix `A` and transforms `s1`,
+        /// `s2` and `t0` on every call. An expanded secret key holds the results, so
+        /// signing with [`ExpandedSecretKey::sign`] skips that work and produces the
+        /// same signatures.
+        ///
+        /// The expansion takes [`EXPANDEDSECRETKEYBYTES`] bytes, up to 80 KiB.
+        /// [`ExpandedSecretKey::new`] returns it by value, which leaves copies on the
+        /// stack that are never wiped and may overflow small stacks. Expand the key in place with
+        /// [`ExpandedSecretKey::expand_into`] or, with the `alloc` feature,
+        /// [`ExpandedSecretKey::new_boxed`] instead.
+        pub struct ExpandedSecretKey {
+            backend: $crate::cpu::Backend,
+            esk: ExpandedSecretKeyBuf,
+        }
+
+        impl ExpandedSecretKey {
+            /// Expand the secret key `sk`.
+            ///
+            /// Moving the result leaves copies of the expansion behind, see
+            /// [`ExpandedSecretKey`].
+            pub fn new(sk: &SecretKey) -> Self {
+                let mut esk = core::mem::MaybeUninit::uninit();
+                Self::expand_into(&mut esk, sk);
+                // SAFETY: `expand_into` initialized `esk`
+                unsafe { esk.assume_init() }
+            }
+
+            /// Expand the secret key `sk` in place into `out`, without copies through the
+            /// stack.
+            ///
+            /// [`core::mem::MaybeUninit`] never drops its contents, so drop the key with
+            /// [`core::mem::MaybeUninit::assume_init_drop`] to wipe it when done.
+            pub fn expand_into<'a>(
+                out: &'a mut core::mem::MaybeUninit<Self>,
+                sk: &SecretKey,
+            ) -> &'a mut Self {
+                let ptr = out.as_mut_ptr();
+                // SAFETY: `ptr` is valid for writes, and writing every field in place
+                // initializes the whole key
+                let esk = unsafe {
+                    core::ptr::addr_of_mut!((*ptr).backend).write($crate::cpu::backend());
+                    core::ptr::addr_of_mut!((*ptr).esk).write_bytes(0, 1);
+                    &mut *ptr
+                };
+                // SAFETY: `esk` and `sk` buffers are valid for reads and writes and live
+                // long enough, and `esk.backend` was selected for this CPU
+                unsafe {
+                    detect_arch::crypto_sign_expand_sk(esk.backend, &mut esk.esk, sk.0.as_ref())
+                };
+                esk
+            }
+
+            /// Expand the secret key `sk` directly into a heap allocation, without copies
+            /// through the stack.
+            #[cfg(feature = "alloc")]
+            pub fn new_boxed(sk: &SecretKey) -> alloc::boxed::Box<Self> {
+                let mut esk = alloc::boxed::Box::new_uninit();
+                Self::expand_into(&mut esk, sk);
+                // SAFETY: `expand_into` initialized `esk`
+                unsafe { esk.assume_init() }
+            }
+
+            /// Sign message `m`, with the same result as [`sign`] with the secret key
+            /// this was expanded from.
+            pub fn sign<M: AsRef<[u8]>>(&self, m: M) -> Signature {
+                let mut sig = Signature::empty();
+                let message: &[u8] = m.as_ref();
+
+                // SAFETY: `self.esk` was expanded by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and writes and live long
+                // enough
+                unsafe {
+                    detect_arch::crypto_sign_signature_expanded(
+                        self.backend,
+                        sig.0.as_mut(),
+                        message,
+                        &self.esk,
+                    )
+                };
+
+                sig
+            }
+
+            /// Sign message `m` with the randomness `rnd` mixed into the per-signature
+            /// seed, with the same result as [`sign_randomized`].
+            pub fn sign_randomized<M: AsRef<[u8]>>(&self, m: M, rnd: &[u8; RNDBYTES]) -> Signature {
+                let mut sig = Signature::empty();
+                let message: &[u8] = m.as_ref();
+
+                // SAFETY: `self.esk` was expanded by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and writes and live long
+                // enough
+                unsafe {
+                    detect_arch::crypto_sign_signature_expanded_randomized(
+                        self.backend,
+                        sig.0.as_mut(),
+                        message,
+                        rnd,
+                        &self.esk,
+                    )
+                };
+
+                sig
+            }
+        }
+
+        impl From<&SecretKey> for ExpandedSecretKey {
+            fn from(sk: &SecretKey) -> Self {
+                Self::new(sk)
+            }
+        }
+
+        impl core::fmt::Debug for ExpandedSecretKey {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct("ExpandedSecretKey").finish_non_exhaustive()
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::Zeroize for ExpandedSecretKey {
+            fn zeroize(&mut self) {
+                self.esk.0.zeroize();
+            }
+        }
+
+        impl Drop for ExpandedSecretKey {
+            fn drop(&mut self) {
+                $crate::util::cleanse(&mut self.esk.0);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        impl zeroize::ZeroizeOnDrop for ExpandedSecretKey {}
+
         mod detect_arch {
             use super::*;
             use cty::c_int;
@@ -117,11 +739,11 @@ macro_rules! impl_dilithium_module {
             pub unsafe fn crypto_sign_keypair(
                 pk: &mut [u8; PUBLICKEYBYTES],
                 sk: &mut [u8; SECRETKEYBYTES],
-                random: &mut [u8; 128],
+                random: &mut [u8; KEYGENRANDOMBYTES],
             ) -> c_int {
                 #[cfg(enable_avx2)]
                 {
-                    if std::is_x86_feature_detected!("avx2") {
+                    if $crate::cpu::has_avx2() {
                         return unsafe { avx2::crypto_sign_keypair(pk, sk, random) };
                     }
                 }
@@ -132,6 +754,24 @@ macro_rules! impl_dilithium_module {
                 unsafe { clean::crypto_sign_keypair(pk, sk, random) }
             }
 
+            pub unsafe fn crypto_sign_seed_keypair(
+                pk: &mut [u8; PUBLICKEYBYTES],
+                sk: &mut [u8; SECRETKEYBYTES],
+                seed: &[u8; SEEDBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_seed_keypair(pk, sk, seed) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_seed_keypair(pk, sk, seed) };
+                }
+                unsafe { clean::crypto_sign_seed_keypair(pk, sk, seed) }
+            }
+
             pub unsafe fn crypto_sign_signature(
                 sig: &mut [u8; SIGNATUREBYTES],
                 message: &[u8],
@@ -139,7 +779,7 @@ macro_rules! impl_dilithium_module {
             ) -> c_int {
                 #[cfg(enable_avx2)]
                 {
-                    if std::is_x86_feature_detected!("avx2") {
+                    if $crate::cpu::has_avx2() {
                         return unsafe { avx2::crypto_sign_signature(sig, message, sk) };
                     }
                 }
@@ -150,6 +790,29 @@ macro_rules! impl_dilithium_module {
                 unsafe { clean::crypto_sign_signature(sig, message, sk) }
             }
 
+            pub unsafe fn crypto_sign_signature_randomized(
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                rnd: &[u8; RNDBYTES],
+                sk: &[u8; SECRETKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe {
+                            avx2::crypto_sign_signature_randomized(sig, message, rnd, sk)
+                        };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe {
+                        aarch64::crypto_sign_signature_randomized(sig, message, rnd, sk)
+                    };
+                }
+                unsafe { clean::crypto_sign_signature_randomized(sig, message, rnd, sk) }
+            }
+
             pub unsafe fn crypto_sign_verify(
                 sig: &[u8; SIGNATUREBYTES],
                 message: &[u8],
@@ -157,7 +820,7 @@ macro_rules! impl_dilithium_module {
             ) -> c_int {
                 #[cfg(enable_avx2)]
                 {
-                    if std::is_x86_feature_detected!("avx2") {
+                    if $crate::cpu::has_avx2() {
                         return unsafe { avx2::crypto_sign_verify(sig, message, pk) };
                     }
                 }
@@ -167,35 +830,294 @@ macro_rules! impl_dilithium_module {
                 }
                 unsafe { clean::crypto_sign_verify(sig, message, pk) }
             }
-        }
-
-        /// Generate a new keypair. Requires a buffer `random` to be filled with
-        /// cryptographically secure random bytes.
-        ///
-        /// # Security
-        /// The buffer `random` MUST be completely filled with cryptographically secure
-        /// random bytes. Use a proper cryptographically secure random number generator
-        /// for it (e.g. `rand::rngs::OsRng`)!
-        pub fn generate_keypair(random: &mut [u8; 128]) -> (PublicKey, SecretKey) {
-            let mut pk = PublicKey::empty();
-            let mut sk = SecretKey::empty();
-            // SAFETY: `pk`, `sk` and `random` buffers are valid for writes and live long
-            // enough
-            unsafe { detect_arch::crypto_sign_keypair(pk.0.as_mut(), sk.0.as_mut(), random) };
-            (pk, sk)
-        }
-
-        /// Sign message `m` with secret key `sk`.
-        pub fn sign<M: AsRef<[u8]>>(m: M, sk: &SecretKey) -> Signature {
-            let mut sig = Signature::empty();
-            let message: &[u8] = m.as_ref();
 
-            unsafe { detect_arch::crypto_sign_signature(sig.0.as_mut(), message, sk.0.as_ref()) };
-
-            sig
-        }
+            pub unsafe fn crypto_sign_pk_from_sk(
+                pk: &mut [u8; PUBLICKEYBYTES],
+                sk: &[u8; SECRETKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_pk_from_sk(pk, sk) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_pk_from_sk(pk, sk) };
+                }
+                unsafe { clean::crypto_sign_pk_from_sk(pk, sk) }
+            }
 
-        /// Verify signature `sig` for message `m` and public key `pk`.
+            pub unsafe fn crypto_sign_check_sk(
+                pk: &mut [u8; PUBLICKEYBYTES],
+                sk: &[u8; SECRETKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_check_sk(pk, sk) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_check_sk(pk, sk) };
+                }
+                unsafe { clean::crypto_sign_check_sk(pk, sk) }
+            }
+
+            pub unsafe fn crypto_sign_check_sig(sig: &[u8; SIGNATUREBYTES]) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_check_sig(sig) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_check_sig(sig) };
+                }
+                unsafe { clean::crypto_sign_check_sig(sig) }
+            }
+
+            pub unsafe fn crypto_sign_prepare_pk(
+                backend: $crate::cpu::Backend,
+                ppk: &mut PreparedPublicKeyBuf,
+                pk: &[u8; PUBLICKEYBYTES],
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe { avx2::crypto_sign_prepare_pk(ppk, pk) },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_prepare_pk(ppk, pk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_prepare_pk(ppk, pk)
+                    },
+                }
+            }
+
+            pub unsafe fn crypto_sign_verify_prepared(
+                backend: $crate::cpu::Backend,
+                sig: &[u8; SIGNATUREBYTES],
+                message: &[u8],
+                ppk: &PreparedPublicKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_verify_prepared(sig, message, ppk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_verify_prepared(sig, message, ppk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_verify_prepared(sig, message, ppk)
+                    },
+                }
+            }
+
+            pub unsafe fn crypto_sign_expand_sk(
+                backend: $crate::cpu::Backend,
+                esk: &mut ExpandedSecretKeyBuf,
+                sk: &[u8; SECRETKEYBYTES],
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe { avx2::crypto_sign_expand_sk(esk, sk) },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_expand_sk(esk, sk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe { clean::crypto_sign_expand_sk(esk, sk) },
+                }
+            }
+
+            pub unsafe fn crypto_sign_signature_expanded(
+                backend: $crate::cpu::Backend,
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                esk: &ExpandedSecretKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_signature_expanded(sig, message, esk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_signature_expanded(sig, message, esk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_signature_expanded(sig, message, esk)
+                    },
+                }
+            }
+
+            pub unsafe fn crypto_sign_signature_expanded_randomized(
+                backend: $crate::cpu::Backend,
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                rnd: &[u8; RNDBYTES],
+                esk: &ExpandedSecretKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_signature_expanded_randomized(sig, message, rnd, esk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_signature_expanded_randomized(sig, message, rnd, esk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_signature_expanded_randomized(sig, message, rnd, esk)
+                    },
+                }
+            }
+        }
+
+        /// Generate a new keypair. Requires a buffer `random` to be filled with
+        /// cryptographically secure random bytes.
+        ///
+        /// The buffer has length [`KEYGENRANDOMBYTES`]: 128 bytes for the round 3
+        /// dilithium parameter sets, and the 32 byte seed ξ for ML-DSA.
+        ///
+        /// # Security
+        /// The buffer `random` MUST be completely filled with cryptographically secure
+        /// random bytes. Use a proper cryptographically secure random number generator
+        /// for it (e.g. `rand::rngs::OsRng`)!
+        pub fn generate_keypair(random: &mut [u8; KEYGENRANDOMBYTES]) -> (PublicKey, SecretKey) {
+            let mut pk = PublicKey::empty();
+            let mut sk = SecretKey::empty();
+            // SAFETY: `pk`, `sk` and `random` buffers are valid for writes and live long
+            // enough
+            unsafe { detect_arch::crypto_sign_keypair(pk.0.as_mut(), sk.0.as_mut(), random) };
+            (pk, sk)
+        }
+
+        /// Generate a new keypair from the owned buffer of random bytes `random`, which
+        /// is zeroized afterwards.
+        ///
+        /// # Security
+        /// The buffer `random` MUST be completely filled with cryptographically secure
+        /// random bytes. Use a proper cryptographically secure random number generator
+        /// for it (e.g. `rand::rngs::OsRng`)!
+        #[cfg(feature = "zeroize")]
+        pub fn generate_keypair_from_randomness(
+            mut random: KeygenRandomness,
+        ) -> (PublicKey, SecretKey) {
+            generate_keypair(&mut random.0)
+        }
+
+        /// Generate a new keypair with randomness from the cryptographically secure
+        /// random number generator `rng`.
+        ///
+        /// The internal buffer with the randomness is wiped afterwards.
+        ///
+        /// # Panics
+        /// Panics if `rng` fails to produce random bytes.
+        #[cfg(feature = "rand_core")]
+        pub fn generate_keypair_with_rng(
+            rng: &mut impl rand_core::CryptoRngCore,
+        ) -> (PublicKey, SecretKey) {
+            let mut random = [0; KEYGENRANDOMBYTES];
+            rng.fill_bytes(&mut random);
+            let keypair = generate_keypair(&mut random);
+            $crate::util::cleanse(&mut random);
+            keypair
+        }
+
+        /// Generate a keypair deterministically from the 32 byte seed `seed`.
+        ///
+        /// The seed is expanded with SHAKE256 into the randomness for
+        /// [`generate_keypair`]: for ML-DSA the seed is ξ from FIPS 204, for the round 3
+        /// dilithium parameter sets the expansion matches the reference
+        /// implementation. Use [`SecretKey::from_seed`] to re-derive just the secret
+        /// key.
+        pub fn generate_keypair_from_seed(seed: &KeySeed) -> (PublicKey, SecretKey) {
+            let mut pk = PublicKey::empty();
+            let mut sk = SecretKey::empty();
+            // SAFETY: `pk`, `sk` and `seed` buffers are valid and live long enough
+            unsafe {
+                detect_arch::crypto_sign_seed_keypair(pk.0.as_mut(), sk.0.as_mut(), seed.0.as_ref())
+            };
+            (pk, sk)
+        }
+
+        /// Sign message `m` with secret key `sk`.
+        pub fn sign<M: AsRef<[u8]>>(m: M, sk: &SecretKey) -> Signature {
+            let mut sig = Signature::empty();
+            let message: &[u8] = m.as_ref();
+
+            unsafe { detect_arch::crypto_sign_signature(sig.0.as_mut(), message, sk.0.as_ref()) };
+
+            sig
+        }
+
+        /// Sign message `m` with secret key `sk`, mixing the randomness `rnd` into the
+        /// derivation of the per-signature seed ("hedged" signing).
+        ///
+        /// Unlike [`sign`], the signature depends on `rnd`, which protects against
+        /// fault and side-channel attacks on deterministic signing. The signature
+        /// verifies with [`verify`] like any other.
+        ///
+        /// # Security
+        /// The buffer `rnd` SHOULD be filled with fresh cryptographically secure random
+        /// bytes for every signature. If it is not, security degrades to that of
+        /// deterministic signing, nothing worse.
+        pub fn sign_randomized<M: AsRef<[u8]>>(
+            m: M,
+            sk: &SecretKey,
+            rnd: &[u8; RNDBYTES],
+        ) -> Signature {
+            let mut sig = Signature::empty();
+            let message: &[u8] = m.as_ref();
+
+            unsafe {
+                detect_arch::crypto_sign_signature_randomized(
+                    sig.0.as_mut(),
+                    message,
+                    rnd,
+                    sk.0.as_ref(),
+                )
+            };
+
+            sig
+        }
+
+        /// Sign message `m` with secret key `sk`, with randomness from the
+        /// cryptographically secure random number generator `rng` mixed into the
+        /// per-signature seed, see [`sign_randomized`].
+        ///
+        /// # Panics
+        /// Panics if `rng` fails to produce random bytes.
+        #[cfg(feature = "rand_core")]
+        pub fn sign_with_rng<M: AsRef<[u8]>>(
+            m: M,
+            sk: &SecretKey,
+            rng: &mut impl rand_core::CryptoRngCore,
+        ) -> Signature {
+            sign_with_fill(m, sk, |rnd| rng.try_fill_bytes(rnd))
+                .expect("rng failed to produce random bytes")
+        }
+
+        /// Sign with the randomness that `fill` writes, wiping it whether or not
+        /// `fill` succeeds.
+        #[cfg(any(feature = "rand_core", feature = "signature"))]
+        fn sign_with_fill<M: AsRef<[u8]>, E>(
+            m: M,
+            sk: &SecretKey,
+            fill: impl FnOnce(&mut [u8]) -> Result<(), E>,
+        ) -> Result<Signature, E> {
+            let mut rnd = [0; RNDBYTES];
+            let sig = fill(&mut rnd).map(|()| sign_randomized(m, sk, &rnd));
+            $crate::util::cleanse(&mut rnd);
+            sig
+        }
+
+        /// Verify signature `sig` for message `m` and public key `pk`.
         pub fn verify<M: AsRef<[u8]>>(
             m: M,
             sig: &Signature,
@@ -209,10 +1131,12 @@ macro_rules! impl_dilithium_module {
             if res == 0 {
                 Ok(crate::VerificationOk)
             } else {
-                Err(crate::VerificationFailure)
+                Err(crate::VerificationFailure::from_code(res))
             }
         }
 
+        $crate::macros::impl_signature_traits!();
+
         #[cfg(test)]
         mod tests {
             use super::*;
@@ -220,7 +1144,7 @@ macro_rules! impl_dilithium_module {
             #[test]
             fn test_sign_verify() {
                 let msg = b"hello world";
-                let mut random = [37u8; 128];
+                let mut random = [37u8; KEYGENRANDOMBYTES];
 
                 let (pubkey, seckey) = generate_keypair(&mut random);
                 let sig = sign(&msg.as_ref(), &seckey);
@@ -232,7 +1156,7 @@ macro_rules! impl_dilithium_module {
             #[test]
             fn test_invalid_other_signature() {
                 let msg = b"hello world";
-                let mut random = [37u8; 128];
+                let mut random = [37u8; KEYGENRANDOMBYTES];
 
                 let (pubkey, seckey) = generate_keypair(&mut random);
                 let sig = sign(&msg.as_ref(), &seckey);
@@ -245,7 +1169,7 @@ macro_rules! impl_dilithium_module {
             #[test]
             fn test_invalid_modified_signature() {
                 let msg = b"hello world";
-                let mut random = [37u8; 128];
+                let mut random = [37u8; KEYGENRANDOMBYTES];
 
                 let (pubkey, seckey) = generate_keypair(&mut random);
                 let mut sig = sign(&msg.as_ref(), &seckey);
@@ -256,13 +1180,330 @@ macro_rules! impl_dilithium_module {
                 assert!(res.is_err(), "Invalid signature accepted!");
             }
 
+            #[test]
+            fn test_sign_randomized() {
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let sig1 = sign_randomized(&msg.as_ref(), &seckey, &[1u8; RNDBYTES]);
+                let sig2 = sign_randomized(&msg.as_ref(), &seckey, &[2u8; RNDBYTES]);
+                assert_ne!(sig1, sig2);
+                assert_ne!(sig1, sign(&msg.as_ref(), &seckey));
+                assert_eq!(
+                    sig1,
+                    sign_randomized(&msg.as_ref(), &seckey, &[1u8; RNDBYTES])
+                );
+
+                assert!(
+                    verify(&msg.as_ref(), &sig1, &pubkey).is_ok(),
+                    "Invalid signature crated!"
+                );
+                assert!(
+                    verify(&msg.as_ref(), &sig2, &pubkey).is_ok(),
+                    "Invalid signature crated!"
+                );
+            }
+
+            #[test]
+            fn test_from_bytes() {
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let sig = sign(b"hello world", &seckey);
+
+                assert_eq!(
+                    PublicKey::from_bytes(&pubkey.to_bytes()),
+                    Ok(pubkey.clone())
+                );
+                assert_eq!(SecretKey::try_from(&seckey.to_bytes()[..]), Ok(seckey));
+                assert_eq!(Signature::from_bytes(sig.as_ref()), Ok(sig.clone()));
+                assert_eq!(sig.clone().into_bytes(), sig.to_bytes());
+
+                let long = [0u8; SIGNATUREBYTES + 1];
+                assert_eq!(
+                    PublicKey::from_bytes(&long[..PUBLICKEYBYTES - 1]),
+                    Err($crate::InvalidLength {
+                        expected: PUBLICKEYBYTES,
+                        actual: PUBLICKEYBYTES - 1
+                    })
+                );
+                assert_eq!(
+                    Signature::try_from(&long[..]),
+                    Err($crate::InvalidLength {
+                        expected: SIGNATUREBYTES,
+                        actual: SIGNATUREBYTES + 1
+                    })
+                );
+            }
+
+            #[test]
+            fn test_public_key() {
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                assert_eq!(seckey.public_key(), Ok(pubkey));
+            }
+
+            #[test]
+            fn test_public_key_corrupt() {
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (_, seckey) = generate_keypair(&mut random);
+                let mut bytes = seckey.to_bytes();
+                // flip a bit of `tr`, which follows `rho` and `key`
+                bytes[64] ^= 1;
+                assert_eq!(
+                    SecretKey::from_bytes(&bytes).unwrap().public_key(),
+                    Err($crate::KeyError::HashMismatch)
+                );
+            }
+
+            #[test]
+            fn test_validate() {
+                use $crate::KeyError;
+
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                assert_eq!(seckey.validate(None), Ok(()));
+                assert_eq!(seckey.validate(Some(&pubkey)), Ok(()));
+
+                let mut random = [38u8; KEYGENRANDOMBYTES];
+                let (other_pubkey, _) = generate_keypair(&mut random);
+                assert_eq!(
+                    seckey.validate(Some(&other_pubkey)),
+                    Err(KeyError::PublicKeyMismatch)
+                );
+
+                let corrupt = |pos: usize, f: fn(u8) -> u8| {
+                    let mut bytes = seckey.to_bytes();
+                    bytes[pos] = f(bytes[pos]);
+                    SecretKey::from_bytes(&bytes).unwrap()
+                };
+                // `s1` starts at byte 96 or 128, so this sets one of its coefficients
+                // below -η
+                let seckey1 = corrupt(128, |_| 0xff);
+                assert_eq!(seckey1.validate(None), Err(KeyError::SecretOutOfRange));
+                let seckey2 = corrupt(SECRETKEYBYTES - 1, |b| b ^ 1);
+                assert_eq!(seckey2.validate(None), Err(KeyError::LowBitsMismatch));
+                let seckey3 = corrupt(64, |b| b ^ 1);
+                assert_eq!(seckey3.validate(None), Err(KeyError::HashMismatch));
+                assert_eq!(seckey3.validate(Some(&pubkey)), Err(KeyError::HashMismatch));
+            }
+
+            #[test]
+            fn test_check_encoding() {
+                use $crate::MalformedReason;
+
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                assert_eq!(pubkey.check_encoding(), Ok(()));
+                let sig = sign(&msg.as_ref(), &seckey);
+                assert_eq!(sig.check_encoding(), Ok(()));
+
+                // the last byte is the hint counter of the last polynomial
+                let mut malformed = sig.clone();
+                malformed.as_mut()[SIGNATUREBYTES - 1] = 0xff;
+                assert_eq!(
+                    malformed.check_encoding(),
+                    Err(MalformedReason::ExcessHints)
+                );
+                assert!(verify(&msg.as_ref(), &malformed, &pubkey).is_err());
+            }
+
+            #[test]
+            fn test_failure_reason() {
+                use $crate::FailureReason;
+
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let sig = sign(&msg.as_ref(), &seckey);
+
+                let err = verify(&b"hello warld".as_ref(), &sig, &pubkey).unwrap_err();
+                assert_eq!(err.reason, FailureReason::ChallengeMismatch);
+                assert_eq!(
+                    err.to_string(),
+                    "signature verification failed: challenge mismatch"
+                );
+
+                let mut malformed = sig.clone();
+                malformed.as_mut()[SIGNATUREBYTES - 1] = 0xff;
+                let err = verify(&msg.as_ref(), &malformed, &pubkey).unwrap_err();
+                assert_eq!(err.reason, FailureReason::MalformedHint);
+
+                // `z` follows the challenge seed of at most 64 bytes; a coefficient with
+                // all bits set decodes to 1 - γ1
+                let mut malformed = sig.clone();
+                malformed.as_mut()[64..128].fill(0xff);
+                assert_eq!(
+                    malformed.check_encoding(),
+                    Err($crate::MalformedReason::ZNorm)
+                );
+                let err = verify(&msg.as_ref(), &malformed, &pubkey).unwrap_err();
+                assert_eq!(err.reason, FailureReason::ZNormTooLarge);
+            }
+
+            #[test]
+            fn test_prepared_public_key() {
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let sig = sign(&msg.as_ref(), &seckey);
+
+                let prepared = PreparedPublicKey::new(&pubkey);
+                assert!(
+                    prepared.verify(msg, &sig).is_ok(),
+                    "Invalid signature crated!"
+                );
+                assert_eq!(
+                    prepared.verify(b"hello warld", &sig),
+                    verify(&b"hello warld".as_ref(), &sig, &pubkey)
+                );
+
+                let mut malformed = sig.clone();
+                malformed.as_mut()[SIGNATUREBYTES - 1] = 0xff;
+                assert_eq!(
+                    prepared.verify(msg, &malformed),
+                    verify(&msg.as_ref(), &malformed, &pubkey)
+                );
+                let mut malformed = sig.clone();
+                malformed.as_mut()[64..128].fill(0xff);
+                assert_eq!(
+                    prepared.verify(msg, &malformed),
+                    verify(&msg.as_ref(), &malformed, &pubkey)
+                );
+
+                let mut random = [38u8; KEYGENRANDOMBYTES];
+                let (other, _) = generate_keypair(&mut random);
+                assert!(PreparedPublicKey::from(&other).verify(msg, &sig).is_err());
+            }
+
+            #[cfg(feature = "zeroize")]
+            #[test]
+            fn test_zeroize() {
+                use zeroize::Zeroize;
+
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey1, seckey1) = generate_keypair(&mut random);
+                let mut keygen_random = KeygenRandomness::zeroed();
+                keygen_random.as_mut().copy_from_slice(&random);
+                let (pubkey2, seckey2) = generate_keypair_from_randomness(keygen_random);
+                assert_eq!(pubkey1, pubkey2);
+                assert_eq!(seckey1, seckey2);
+
+                let mut seckey3 = seckey2.clone();
+                assert_eq!(seckey3, seckey1);
+                seckey3.zeroize();
+                assert_eq!(seckey3.to_bytes(), [0; SECRETKEYBYTES]);
+
+                let mut random = KeygenRandomness::new(random);
+                random.zeroize();
+                assert_eq!(random.0, [0; KEYGENRANDOMBYTES]);
+            }
+
+            #[test]
+            fn test_expanded_secret_key() {
+                let msg = b"hello world";
+                let rnd = [42u8; RNDBYTES];
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+
+                let expanded = ExpandedSecretKey::new(&seckey);
+                let sig = expanded.sign(msg);
+                assert_eq!(sig, sign(&msg.as_ref(), &seckey));
+                assert!(
+                    verify(&msg.as_ref(), &sig, &pubkey).is_ok(),
+                    "Invalid signature crated!"
+                );
+                assert_eq!(
+                    expanded.sign_randomized(msg, &rnd),
+                    sign_randomized(msg, &seckey, &rnd)
+                );
+
+                let mut expanded = core::mem::ManuallyDrop::new(expanded);
+                // SAFETY: `expanded` is not used after dropping it, only its bytes are read
+                unsafe { core::mem::ManuallyDrop::drop(&mut expanded) };
+                assert!(expanded.esk.0.iter().all(|&b| b == 0));
+            }
+
+            #[test]
+            fn test_expanded_secret_key_in_place() {
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (_, seckey) = generate_keypair(&mut random);
+                let expanded = ExpandedSecretKey::new(&seckey);
+
+                let mut slot = core::mem::MaybeUninit::uninit();
+                let in_place = ExpandedSecretKey::expand_into(&mut slot, &seckey);
+                assert_eq!(in_place.esk.0[..], expanded.esk.0[..]);
+                assert_eq!(in_place.sign(msg), expanded.sign(msg));
+                // SAFETY: `slot` was initialized by `expand_into` and is dropped once, only
+                // its bytes are read afterwards
+                unsafe { slot.assume_init_drop() };
+                let wiped = unsafe { slot.assume_init_ref() };
+                assert!(wiped.esk.0.iter().all(|&b| b == 0));
+
+                #[cfg(feature = "alloc")]
+                {
+                    let boxed = ExpandedSecretKey::new_boxed(&seckey);
+                    assert_eq!(boxed.esk.0[..], expanded.esk.0[..]);
+                    assert_eq!(boxed.sign(msg), expanded.sign(msg));
+                }
+            }
+
+            #[cfg(feature = "zeroize")]
+            #[test]
+            fn test_zeroize_expanded_secret_key() {
+                use zeroize::Zeroize;
+
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (_, seckey) = generate_keypair(&mut random);
+                let mut expanded = ExpandedSecretKey::new(&seckey);
+                expanded.zeroize();
+                assert!(expanded.esk.0.iter().all(|&b| b == 0));
+            }
+
+            #[cfg(feature = "rand_core")]
+            #[test]
+            fn test_rng() {
+                use rand::{RngCore, SeedableRng};
+
+                let mut random = [0u8; KEYGENRANDOMBYTES];
+                rand::rngs::StdRng::seed_from_u64(37).fill_bytes(&mut random);
+                let mut rng = rand::rngs::StdRng::seed_from_u64(37);
+                let (pubkey, seckey) = generate_keypair_with_rng(&mut rng);
+                assert_eq!((pubkey, seckey), generate_keypair(&mut random));
+
+                let (pubkey, seckey) = generate_keypair_with_rng(&mut rand::rngs::OsRng);
+                let msg = b"hello world";
+                let sig = sign_with_rng(msg, &seckey, &mut rand::rngs::OsRng);
+                assert!(verify(msg, &sig, &pubkey).is_ok());
+                assert_ne!(sig, sign(msg, &seckey));
+            }
+
+            #[test]
+            fn test_keygen_from_seed() {
+                let seed = KeySeed::new([37u8; SEEDBYTES]);
+                let (pubkey1, seckey1) = generate_keypair_from_seed(&seed);
+                let (pubkey2, seckey2) = generate_keypair_from_seed(&seed);
+                assert_eq!(pubkey1, pubkey2);
+                assert_eq!(seckey1, seckey2);
+                assert_eq!(SecretKey::from_seed(&seed), seckey1);
+
+                let other = KeySeed::new([38u8; SEEDBYTES]);
+                assert_ne!(generate_keypair_from_seed(&other).0, pubkey1);
+
+                let msg = b"hello world";
+                let sig = sign(&msg.as_ref(), &SecretKey::from_seed(&seed));
+                assert!(verify(&msg.as_ref(), &sig, &pubkey1).is_ok());
+            }
+
             #[test]
             fn test_deterministic_keygen() {
-                let mut random = [37u8; 128];
+                let mut random = [37u8; KEYGENRANDOMBYTES];
                 let (pubkey1, seckey1) = generate_keypair(&mut random);
                 // the seed is probably never modified in the C code, but it uses mutable
                 // references for some reason
-                assert_eq!(random, [37u8; 128]);
+                assert_eq!(random, [37u8; KEYGENRANDOMBYTES]);
                 let (pubkey2, seckey2) = generate_keypair(&mut random);
                 assert_eq!(pubkey1, pubkey2);
                 assert_eq!(seckey1, seckey2);
@@ -275,7 +1516,7 @@ macro_rules! impl_dilithium_module {
 
             #[derive(serde::Serialize, serde::Deserialize)]
             struct RegressionTestExample {
-                seed: crate::util::ByteArray<128>,
+                seed: crate::util::ByteArray<KEYGENRANDOMBYTES>,
                 pubkey: PublicKey,
                 seckey: SecretKey,
                 message: String,
@@ -308,3 +1549,682 @@ macro_rules! impl_dilithium_module {
 }
 
 pub(crate) use impl_dilithium_module;
+
+/// FIPS 204 only additions to the API generated by `impl_dilithium_module`.
+#[cfg(any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87"))]
+macro_rules! impl_fips204_module {
+    ($kat_file:literal) => {
+        mod detect_arch_fips204 {
+            use super::*;
+            use cty::c_int;
+
+            pub unsafe fn crypto_sign_signature_ctx(
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                ctx: &[u8],
+                sk: &[u8; SECRETKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_signature_ctx(sig, message, ctx, sk) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_signature_ctx(sig, message, ctx, sk) };
+                }
+                unsafe { clean::crypto_sign_signature_ctx(sig, message, ctx, sk) }
+            }
+
+            pub unsafe fn crypto_sign_verify_ctx(
+                sig: &[u8; SIGNATUREBYTES],
+                message: &[u8],
+                ctx: &[u8],
+                pk: &[u8; PUBLICKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_verify_ctx(sig, message, ctx, pk) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_verify_ctx(sig, message, ctx, pk) };
+                }
+                unsafe { clean::crypto_sign_verify_ctx(sig, message, ctx, pk) }
+            }
+
+            pub unsafe fn crypto_sign_signature_internal(
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                pre: &[u8],
+                rnd: &[u8; RNDBYTES],
+                sk: &[u8; SECRETKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe {
+                            avx2::crypto_sign_signature_internal(sig, message, pre, rnd, sk)
+                        };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe {
+                        aarch64::crypto_sign_signature_internal(sig, message, pre, rnd, sk)
+                    };
+                }
+                unsafe { clean::crypto_sign_signature_internal(sig, message, pre, rnd, sk) }
+            }
+
+            pub unsafe fn crypto_sign_verify_internal(
+                sig: &[u8; SIGNATUREBYTES],
+                message: &[u8],
+                pre: &[u8],
+                pk: &[u8; PUBLICKEYBYTES],
+            ) -> c_int {
+                #[cfg(enable_avx2)]
+                {
+                    if $crate::cpu::has_avx2() {
+                        return unsafe { avx2::crypto_sign_verify_internal(sig, message, pre, pk) };
+                    }
+                }
+                #[cfg(enable_aarch64)]
+                {
+                    return unsafe { aarch64::crypto_sign_verify_internal(sig, message, pre, pk) };
+                }
+                unsafe { clean::crypto_sign_verify_internal(sig, message, pre, pk) }
+            }
+
+            pub unsafe fn crypto_sign_verify_prepared_ctx(
+                backend: $crate::cpu::Backend,
+                sig: &[u8; SIGNATUREBYTES],
+                message: &[u8],
+                ctx: &[u8],
+                ppk: &PreparedPublicKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_verify_prepared_ctx(sig, message, ctx, ppk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_verify_prepared_ctx(sig, message, ctx, ppk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_verify_prepared_ctx(sig, message, ctx, ppk)
+                    },
+                }
+            }
+
+            pub unsafe fn crypto_sign_verify_prepared_internal(
+                backend: $crate::cpu::Backend,
+                sig: &[u8; SIGNATUREBYTES],
+                message: &[u8],
+                pre: &[u8],
+                ppk: &PreparedPublicKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_verify_prepared_internal(sig, message, pre, ppk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_verify_prepared_internal(sig, message, pre, ppk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_verify_prepared_internal(sig, message, pre, ppk)
+                    },
+                }
+            }
+
+            pub unsafe fn crypto_sign_signature_expanded_ctx(
+                backend: $crate::cpu::Backend,
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                ctx: &[u8],
+                esk: &ExpandedSecretKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_signature_expanded_ctx(sig, message, ctx, esk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_signature_expanded_ctx(sig, message, ctx, esk)
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_signature_expanded_ctx(sig, message, ctx, esk)
+                    },
+                }
+            }
+
+            pub unsafe fn crypto_sign_signature_expanded_internal(
+                backend: $crate::cpu::Backend,
+                sig: &mut [u8; SIGNATUREBYTES],
+                message: &[u8],
+                pre: &[u8],
+                rnd: &[u8; RNDBYTES],
+                esk: &ExpandedSecretKeyBuf,
+            ) -> c_int {
+                match backend {
+                    #[cfg(enable_avx2)]
+                    $crate::cpu::Backend::Avx2 => unsafe {
+                        avx2::crypto_sign_signature_expanded_internal(sig, message, pre, rnd, esk)
+                    },
+                    #[cfg(enable_aarch64)]
+                    $crate::cpu::Backend::Aarch64 => unsafe {
+                        aarch64::crypto_sign_signature_expanded_internal(
+                            sig, message, pre, rnd, esk,
+                        )
+                    },
+                    $crate::cpu::Backend::Clean => unsafe {
+                        clean::crypto_sign_signature_expanded_internal(sig, message, pre, rnd, esk)
+                    },
+                }
+            }
+        }
+
+        /// Sign message `m` with secret key `sk`, binding the context string `ctx`
+        /// into the signature for domain separation.
+        ///
+        /// `sign(m, sk)` is the same as `sign_with_context(m, b"", sk)`. Returns
+        /// [`ContextTooLong`](crate::ContextTooLong) if `ctx` is longer than
+        /// [`CONTEXTMAXBYTES`] bytes.
+        pub fn sign_with_context<M: AsRef<[u8]>, C: AsRef<[u8]>>(
+            m: M,
+            ctx: C,
+            sk: &SecretKey,
+        ) -> Result<Signature, crate::ContextTooLong> {
+            let mut sig = Signature::empty();
+            let message: &[u8] = m.as_ref();
+            let ctx: &[u8] = ctx.as_ref();
+            if ctx.len() > CONTEXTMAXBYTES {
+                return Err(crate::ContextTooLong);
+            }
+
+            unsafe {
+                detect_arch_fips204::crypto_sign_signature_ctx(
+                    sig.0.as_mut(),
+                    message,
+                    ctx,
+                    sk.0.as_ref(),
+                )
+            };
+
+            Ok(sig)
+        }
+
+        /// Verify signature `sig` for message `m`, context string `ctx` and public key
+        /// `pk`.
+        ///
+        /// Returns [`ContextTooLong`](crate::ContextTooLong) if `ctx` is longer than
+        /// [`CONTEXTMAXBYTES`] bytes, so that passing an invalid context is not
+        /// mistaken for an invalid signature.
+        pub fn verify_with_context<M: AsRef<[u8]>, C: AsRef<[u8]>>(
+            m: M,
+            ctx: C,
+            sig: &Signature,
+            pk: &PublicKey,
+        ) -> Result<crate::VerificationResult, crate::ContextTooLong> {
+            let message: &[u8] = m.as_ref();
+            let ctx: &[u8] = ctx.as_ref();
+            if ctx.len() > CONTEXTMAXBYTES {
+                return Err(crate::ContextTooLong);
+            }
+
+            let res = unsafe {
+                detect_arch_fips204::crypto_sign_verify_ctx(
+                    sig.0.as_ref(),
+                    message,
+                    ctx,
+                    pk.0.as_ref(),
+                )
+            };
+
+            if res == 0 {
+                Ok(Ok(crate::VerificationOk))
+            } else {
+                Ok(Err(crate::VerificationFailure::from_code(res)))
+            }
+        }
+
+        /// Sign the digest `digest` of a message, computed with the hash function
+        /// `hash`, with secret key `sk` and context string `ctx` (HashML-DSA).
+        ///
+        /// The signature is only valid for HashML-DSA verification using
+        /// [`verify_prehashed`], not for [`verify`] of the original message. Returns an
+        /// error if `ctx` is longer than [`CONTEXTMAXBYTES`] bytes or `digest` does not
+        /// have length [`hash.digest_len()`](crate::prehash::HashAlgorithm::digest_len).
+        pub fn sign_prehashed<D: AsRef<[u8]>, C: AsRef<[u8]>>(
+            hash: crate::prehash::HashAlgorithm,
+            digest: D,
+            ctx: C,
+            sk: &SecretKey,
+        ) -> Result<Signature, crate::prehash::PrehashError> {
+            let mut sig = Signature::empty();
+            let digest: &[u8] = digest.as_ref();
+            let pre = crate::prehash::Prefix::new(hash, digest, ctx.as_ref())?;
+
+            unsafe {
+                detect_arch_fips204::crypto_sign_signature_internal(
+                    sig.0.as_mut(),
+                    digest,
+                    pre.as_ref(),
+                    &[0; RNDBYTES],
+                    sk.0.as_ref(),
+                )
+            };
+
+            Ok(sig)
+        }
+
+        /// Verify HashML-DSA signature `sig` for the digest `digest` of a message,
+        /// computed with the hash function `hash`, context string `ctx` and public key
+        /// `pk`.
+        ///
+        /// Returns an error if `ctx` is longer than [`CONTEXTMAXBYTES`] bytes or
+        /// `digest` does not have length
+        /// [`hash.digest_len()`](crate::prehash::HashAlgorithm::digest_len).
+        pub fn verify_prehashed<D: AsRef<[u8]>, C: AsRef<[u8]>>(
+            hash: crate::prehash::HashAlgorithm,
+            digest: D,
+            ctx: C,
+            sig: &Signature,
+            pk: &PublicKey,
+        ) -> Result<crate::VerificationResult, crate::prehash::PrehashError> {
+            let digest: &[u8] = digest.as_ref();
+            let pre = crate::prehash::Prefix::new(hash, digest, ctx.as_ref())?;
+
+            let res = unsafe {
+                detect_arch_fips204::crypto_sign_verify_internal(
+                    sig.0.as_ref(),
+                    digest,
+                    pre.as_ref(),
+                    pk.0.as_ref(),
+                )
+            };
+
+            if res == 0 {
+                Ok(Ok(crate::VerificationOk))
+            } else {
+                Ok(Err(crate::VerificationFailure::from_code(res)))
+            }
+        }
+
+        impl PreparedPublicKey {
+            /// Verify signature `sig` for message `m` and context string `ctx`, with the
+            /// same result as [`verify_with_context`].
+            pub fn verify_with_context<M: AsRef<[u8]>, C: AsRef<[u8]>>(
+                &self,
+                m: M,
+                ctx: C,
+                sig: &Signature,
+            ) -> Result<crate::VerificationResult, crate::ContextTooLong> {
+                let message: &[u8] = m.as_ref();
+                let ctx: &[u8] = ctx.as_ref();
+                if ctx.len() > CONTEXTMAXBYTES {
+                    return Err(crate::ContextTooLong);
+                }
+
+                // SAFETY: `self.ppk` was prepared by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and live long enough
+                let res = unsafe {
+                    detect_arch_fips204::crypto_sign_verify_prepared_ctx(
+                        self.backend,
+                        sig.0.as_ref(),
+                        message,
+                        ctx,
+                        &self.ppk,
+                    )
+                };
+
+                if res == 0 {
+                    Ok(Ok(crate::VerificationOk))
+                } else {
+                    Ok(Err(crate::VerificationFailure::from_code(res)))
+                }
+            }
+
+            /// Verify HashML-DSA signature `sig` for the digest `digest` of a message,
+            /// computed with the hash function `hash`, and context string `ctx`, with the
+            /// same result as [`verify_prehashed`].
+            pub fn verify_prehashed<D: AsRef<[u8]>, C: AsRef<[u8]>>(
+                &self,
+                hash: crate::prehash::HashAlgorithm,
+                digest: D,
+                ctx: C,
+                sig: &Signature,
+            ) -> Result<crate::VerificationResult, crate::prehash::PrehashError> {
+                let digest: &[u8] = digest.as_ref();
+                let pre = crate::prehash::Prefix::new(hash, digest, ctx.as_ref())?;
+
+                // SAFETY: `self.ppk` was prepared by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and live long enough
+                let res = unsafe {
+                    detect_arch_fips204::crypto_sign_verify_prepared_internal(
+                        self.backend,
+                        sig.0.as_ref(),
+                        digest,
+                        pre.as_ref(),
+                        &self.ppk,
+                    )
+                };
+
+                if res == 0 {
+                    Ok(Ok(crate::VerificationOk))
+                } else {
+                    Ok(Err(crate::VerificationFailure::from_code(res)))
+                }
+            }
+        }
+
+        impl ExpandedSecretKey {
+            /// Sign message `m` with context string `ctx`, with the same result as
+            /// [`sign_with_context`].
+            pub fn sign_with_context<M: AsRef<[u8]>, C: AsRef<[u8]>>(
+                &self,
+                m: M,
+                ctx: C,
+            ) -> Result<Signature, crate::ContextTooLong> {
+                let mut sig = Signature::empty();
+                let message: &[u8] = m.as_ref();
+                let ctx: &[u8] = ctx.as_ref();
+                if ctx.len() > CONTEXTMAXBYTES {
+                    return Err(crate::ContextTooLong);
+                }
+
+                // SAFETY: `self.esk` was expanded by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and writes and live long
+                // enough
+                unsafe {
+                    detect_arch_fips204::crypto_sign_signature_expanded_ctx(
+                        self.backend,
+                        sig.0.as_mut(),
+                        message,
+                        ctx,
+                        &self.esk,
+                    )
+                };
+
+                Ok(sig)
+            }
+
+            /// Sign the digest `digest` of a message, computed with the hash function
+            /// `hash`, with context string `ctx`, with the same result as
+            /// [`sign_prehashed`].
+            pub fn sign_prehashed<D: AsRef<[u8]>, C: AsRef<[u8]>>(
+                &self,
+                hash: crate::prehash::HashAlgorithm,
+                digest: D,
+                ctx: C,
+            ) -> Result<Signature, crate::prehash::PrehashError> {
+                let mut sig = Signature::empty();
+                let digest: &[u8] = digest.as_ref();
+                let pre = crate::prehash::Prefix::new(hash, digest, ctx.as_ref())?;
+
+                // SAFETY: `self.esk` was expanded by `self.backend`, which was selected for
+                // this CPU, and the buffers are valid for reads and writes and live long
+                // enough
+                unsafe {
+                    detect_arch_fips204::crypto_sign_signature_expanded_internal(
+                        self.backend,
+                        sig.0.as_mut(),
+                        digest,
+                        pre.as_ref(),
+                        &[0; RNDBYTES],
+                        &self.esk,
+                    )
+                };
+
+                Ok(sig)
+            }
+        }
+
+        #[cfg(test)]
+        mod fips204_tests {
+            use super::*;
+
+            #[test]
+            fn test_sign_verify_with_context() {
+                let msg = b"hello world";
+                let ctx = b"firmware manifest";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let sig = sign_with_context(msg, ctx, &seckey).unwrap();
+
+                let res = verify_with_context(msg, ctx, &sig, &pubkey).unwrap();
+                assert!(res.is_ok(), "Invalid signature crated!");
+
+                let res = verify_with_context(msg, b"api token", &sig, &pubkey).unwrap();
+                assert!(res.is_err(), "Signature accepted under other context!");
+                assert!(verify(msg, &sig, &pubkey).is_err());
+            }
+
+            #[test]
+            fn test_empty_context() {
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let sig = sign_with_context(msg, b"", &seckey).unwrap();
+                assert_eq!(sig, sign(msg, &seckey));
+                assert!(verify_with_context(msg, b"", &sig, &pubkey)
+                    .unwrap()
+                    .is_ok());
+            }
+
+            #[test]
+            fn test_context_length() {
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let ctx = [1u8; CONTEXTMAXBYTES];
+                let sig = sign_with_context(msg, ctx, &seckey).unwrap();
+                assert!(verify_with_context(msg, ctx, &sig, &pubkey)
+                    .unwrap()
+                    .is_ok());
+
+                let ctx = [1u8; CONTEXTMAXBYTES + 1];
+                assert_eq!(
+                    sign_with_context(msg, ctx, &seckey),
+                    Err(crate::ContextTooLong)
+                );
+                assert_eq!(
+                    verify_with_context(msg, ctx, &sig, &pubkey).err(),
+                    Some(crate::ContextTooLong)
+                );
+            }
+
+            #[test]
+            fn test_keygen_from_seed_is_xi() {
+                // for ML-DSA the seed is the keygen randomness ξ
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let seed = KeySeed::new([37u8; SEEDBYTES]);
+                assert_eq!(
+                    generate_keypair_from_seed(&seed),
+                    generate_keypair(&mut random)
+                );
+            }
+
+            #[test]
+            fn test_sign_randomized_zero() {
+                // deterministic ML-DSA signing is hedged signing with `rnd` all zero
+                let msg = b"hello world";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+
+                let (_, seckey) = generate_keypair(&mut random);
+                assert_eq!(
+                    sign_randomized(msg, &seckey, &[0; RNDBYTES]),
+                    sign(msg, &seckey)
+                );
+            }
+
+            #[test]
+            fn test_sign_verify_prehashed() {
+                use crate::prehash::{HashAlgorithm, PrehashError, Shake256};
+
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+
+                let mut hasher = Shake256::new();
+                hasher.update(b"hello ");
+                hasher.update(b"world");
+                let digest = hasher.finalize();
+                let sig = sign_prehashed(HashAlgorithm::Shake256, digest, b"ctx", &seckey).unwrap();
+
+                let res = verify_prehashed(HashAlgorithm::Shake256, digest, b"ctx", &sig, &pubkey);
+                assert!(res.unwrap().is_ok(), "Invalid signature crated!");
+                // the hash algorithm is bound into the signature
+                let res = verify_prehashed(HashAlgorithm::Sha512, digest, b"ctx", &sig, &pubkey);
+                assert!(res.unwrap().is_err(), "Signature accepted for other hash!");
+                let res = verify_prehashed(HashAlgorithm::Shake256, digest, b"", &sig, &pubkey);
+                assert!(
+                    res.unwrap().is_err(),
+                    "Signature accepted under other context!"
+                );
+                // HashML-DSA and ML-DSA signatures are domain separated
+                assert!(verify_with_context(digest, b"ctx", &sig, &pubkey)
+                    .unwrap()
+                    .is_err());
+
+                assert_eq!(
+                    sign_prehashed(HashAlgorithm::Sha256, digest, b"", &seckey),
+                    Err(PrehashError::InvalidDigestLength {
+                        expected: 32,
+                        actual: 64
+                    })
+                );
+            }
+
+            #[test]
+            fn test_prepared_public_key_fips204() {
+                use crate::prehash::{HashAlgorithm, Shake256};
+
+                let msg = b"hello world";
+                let ctx = b"firmware manifest";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (pubkey, seckey) = generate_keypair(&mut random);
+                let prepared = PreparedPublicKey::new(&pubkey);
+
+                let sig = sign_with_context(msg, ctx, &seckey).unwrap();
+                let res = prepared.verify_with_context(msg, ctx, &sig).unwrap();
+                assert!(res.is_ok(), "Invalid signature crated!");
+                let res = prepared
+                    .verify_with_context(msg, b"api token", &sig)
+                    .unwrap();
+                assert!(res.is_err(), "Signature accepted under other context!");
+                assert_eq!(
+                    prepared.verify_with_context(msg, [0; CONTEXTMAXBYTES + 1], &sig),
+                    Err(crate::ContextTooLong)
+                );
+
+                let mut hasher = Shake256::new();
+                hasher.update(msg);
+                let digest = hasher.finalize();
+                let sig = sign_prehashed(HashAlgorithm::Shake256, digest, ctx, &seckey).unwrap();
+                let res = prepared.verify_prehashed(HashAlgorithm::Shake256, digest, ctx, &sig);
+                assert!(res.unwrap().is_ok(), "Invalid signature crated!");
+                let res = prepared.verify_prehashed(HashAlgorithm::Sha512, digest, ctx, &sig);
+                assert!(res.unwrap().is_err(), "Signature accepted for other hash!");
+            }
+
+            #[test]
+            fn test_expanded_secret_key_fips204() {
+                use crate::prehash::{HashAlgorithm, Shake256};
+
+                let msg = b"hello world";
+                let ctx = b"firmware manifest";
+                let mut random = [37u8; KEYGENRANDOMBYTES];
+                let (_, seckey) = generate_keypair(&mut random);
+                let expanded = ExpandedSecretKey::from(&seckey);
+
+                assert_eq!(
+                    expanded.sign_with_context(msg, ctx),
+                    sign_with_context(msg, ctx, &seckey)
+                );
+                assert_eq!(
+                    expanded.sign_with_context(msg, [0; CONTEXTMAXBYTES + 1]),
+                    Err(crate::ContextTooLong)
+                );
+
+                let mut hasher = Shake256::new();
+                hasher.update(msg);
+                let digest = hasher.finalize();
+                assert_eq!(
+                    expanded.sign_prehashed(HashAlgorithm::Shake256, digest, ctx),
+                    sign_prehashed(HashAlgorithm::Shake256, digest, ctx, &seckey)
+                );
+            }
+        }
+
+        #[cfg(test)]
+        mod known_answer_test {
+            use super::*;
+
+            fn hex(value: &serde_json::Value) -> Vec<u8> {
+                let hex = value.as_str().expect("expected a hex string");
+                (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex"))
+                    .collect()
+            }
+
+            #[test]
+            fn test_known_answers() {
+                let json_str = include_str!($kat_file);
+                let kat: serde_json::Value = serde_json::from_str(json_str)
+                    .expect("could not deserialize known answer file");
+
+                for test in kat["keyGen"].as_array().unwrap() {
+                    let mut seed: [u8; KEYGENRANDOMBYTES] = hex(&test["seed"]).try_into().unwrap();
+                    let (pubkey, seckey) = generate_keypair(&mut seed);
+                    assert_eq!(pubkey.as_ref(), hex(&test["pk"]), "keyGen {}", test["tcId"]);
+                    assert_eq!(seckey.as_ref(), hex(&test["sk"]), "keyGen {}", test["tcId"]);
+                }
+
+                for test in kat["sigGen"].as_array().unwrap() {
+                    let seckey = SecretKey::from_bytes(&hex(&test["sk"])).unwrap();
+                    let sig =
+                        sign_with_context(hex(&test["message"]), hex(&test["context"]), &seckey)
+                            .unwrap();
+                    assert_eq!(
+                        sig.as_ref(),
+                        hex(&test["signature"]),
+                        "sigGen {}",
+                        test["tcId"]
+                    );
+                }
+
+                for test in kat["sigVer"].as_array().unwrap() {
+                    let pubkey = PublicKey::from_bytes(&hex(&test["pk"])).unwrap();
+                    let sig = Signature::from_bytes(&hex(&test["signature"])).unwrap();
+                    let res = verify_with_context(
+                        hex(&test["message"]),
+                        hex(&test["context"]),
+                        &sig,
+                        &pubkey,
+                    )
+                    .unwrap();
+                    assert_eq!(
+                        Some(res.is_ok()),
+                        test["testPassed"].as_bool(),
+                        "sigVer {}",
+                        test["tcId"]
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87"))]
+pub(crate) use impl_fips204_module;
@@ -0,0 +1,211 @@
+//! Trait for code generic over the parameter sets.
+//!
+//! Each enabled parameter set module has a zero-sized marker type implementing
+//! [`ParameterSet`], e.g. [`Dilithium2`] for [`dilithium2`](crate::dilithium2).
+
+use crate::{Algorithm, InvalidLength, VerificationResult};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A parameter set, giving access to the types and functions of its module.
+///
+/// This trait is sealed: it is only implemented by the marker types in this
+/// module.
+pub trait ParameterSet:
+    sealed::Sealed + Copy + Clone + Default + core::fmt::Debug + PartialEq + Eq + 'static
+{
+    /// The algorithm.
+    const ALGORITHM: Algorithm;
+    /// Length of a public key in bytes.
+    const PUBLICKEYBYTES: usize;
+    /// Length of a secret key in bytes.
+    const SECRETKEYBYTES: usize;
+    /// Length of a signature in bytes.
+    const SIGNATUREBYTES: usize;
+    /// Length of the randomness for [`generate_keypair`](Self::generate_keypair)
+    /// in bytes.
+    const KEYGENRANDOMBYTES: usize;
+    /// Length of a seed for
+    /// [`generate_keypair_from_seed`](Self::generate_keypair_from_seed) in bytes.
+    const SEEDBYTES: usize = 32;
+
+    /// Public key.
+    type PublicKey: Clone
+        + core::fmt::Debug
+        + Eq
+        + AsRef<[u8]>
+        + for<'a> TryFrom<&'a [u8], Error = InvalidLength>;
+    /// Secret key.
+    type SecretKey: AsRef<[u8]> + for<'a> TryFrom<&'a [u8], Error = InvalidLength>;
+    /// Signature.
+    type Signature: Clone
+        + core::fmt::Debug
+        + Eq
+        + AsRef<[u8]>
+        + for<'a> TryFrom<&'a [u8], Error = InvalidLength>;
+    /// Seed from which a keypair is derived deterministically; zeroized on drop
+    /// with the `zeroize` feature.
+    type KeySeed: AsRef<[u8]> + From<[u8; 32]>;
+
+    /// Generate a new keypair from the buffer `random` filled with
+    /// [`KEYGENRANDOMBYTES`](Self::KEYGENRANDOMBYTES) cryptographically secure
+    /// random bytes.
+    ///
+    /// Fails if `random` has the wrong length.
+    fn generate_keypair(
+        random: &mut [u8],
+    ) -> Result<(Self::PublicKey, Self::SecretKey), InvalidLength>;
+
+    /// Generate a keypair deterministically from the seed `seed`.
+    fn generate_keypair_from_seed(seed: &Self::KeySeed) -> (Self::PublicKey, Self::SecretKey);
+
+    /// Sign message `m` with secret key `sk`.
+    fn sign<M: AsRef<[u8]>>(m: M, sk: &Self::SecretKey) -> Self::Signature;
+
+    /// Verify signature `sig` for message `m` and public key `pk`.
+    fn verify<M: AsRef<[u8]>>(
+        m: M,
+        sig: &Self::Signature,
+        pk: &Self::PublicKey,
+    ) -> VerificationResult;
+}
+
+macro_rules! impl_parameter_set {
+    ($($feature:literal, $marker:ident, $module:ident;)*) => {
+        $(
+            #[doc = concat!("Marker type for the [`", stringify!($module), "`](crate::", stringify!($module), ") parameter set.")]
+            #[cfg(feature = $feature)]
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+            pub struct $marker;
+
+            #[cfg(feature = $feature)]
+            impl sealed::Sealed for $marker {}
+
+            #[cfg(feature = $feature)]
+            impl ParameterSet for $marker {
+                const ALGORITHM: Algorithm = Algorithm::$marker;
+                const PUBLICKEYBYTES: usize = crate::ffi::$module::PUBLICKEYBYTES;
+                const SECRETKEYBYTES: usize = crate::ffi::$module::SECRETKEYBYTES;
+                const SIGNATUREBYTES: usize = crate::ffi::$module::SIGNATUREBYTES;
+                const KEYGENRANDOMBYTES: usize = crate::ffi::$module::KEYGENRANDOMBYTES;
+
+                type PublicKey = crate::$module::PublicKey;
+                type SecretKey = crate::$module::SecretKey;
+                type Signature = crate::$module::Signature;
+                type KeySeed = crate::$module::KeySeed;
+
+                fn generate_keypair(
+                    random: &mut [u8],
+                ) -> Result<(Self::PublicKey, Self::SecretKey), InvalidLength> {
+                    let actual = random.len();
+                    let random = <&mut [u8; crate::ffi::$module::KEYGENRANDOMBYTES]>::try_from(random)
+                        .map_err(|_| InvalidLength {
+                            expected: Self::KEYGENRANDOMBYTES,
+                            actual,
+                        })?;
+                    Ok(crate::$module::generate_keypair(random))
+                }
+
+                fn generate_keypair_from_seed(
+                    seed: &Self::KeySeed,
+                ) -> (Self::PublicKey, Self::SecretKey) {
+                    crate::$module::generate_keypair_from_seed(seed)
+                }
+
+                fn sign<M: AsRef<[u8]>>(m: M, sk: &Self::SecretKey) -> Self::Signature {
+                    crate::$module::sign(m, sk)
+                }
+
+                fn verify<M: AsRef<[u8]>>(
+                    m: M,
+                    sig: &Self::Signature,
+                    pk: &Self::PublicKey,
+                ) -> VerificationResult {
+                    crate::$module::verify(m, sig, pk)
+                }
+            }
+        )*
+    };
+}
+
+impl_parameter_set! {
+    "dilithium2", Dilithium2, dilithium2;
+    "dilithium3", Dilithium3, dilithium3;
+    "dilithium5", Dilithium5, dilithium5;
+    "ml_dsa_44", MlDsa44, ml_dsa_44;
+    "ml_dsa_65", MlDsa65, ml_dsa_65;
+    "ml_dsa_87", MlDsa87, ml_dsa_87;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    fn roundtrip<P: ParameterSet>() {
+        let seed = P::KeySeed::from([37; 32]);
+        let (pk, sk) = P::generate_keypair_from_seed(&seed);
+        assert_eq!(seed.as_ref(), [37; 32]);
+        assert_eq!(pk.as_ref().len(), P::PUBLICKEYBYTES);
+        assert_eq!(sk.as_ref().len(), P::SECRETKEYBYTES);
+
+        let mut random = [38u8; 128];
+        let (pk2, _) = P::generate_keypair(&mut random[..P::KEYGENRANDOMBYTES]).unwrap();
+        assert_ne!(pk, pk2);
+        assert_eq!(
+            P::generate_keypair(&mut random[..P::KEYGENRANDOMBYTES - 1]).err(),
+            Some(InvalidLength {
+                expected: P::KEYGENRANDOMBYTES,
+                actual: P::KEYGENRANDOMBYTES - 1,
+            })
+        );
+
+        let msg = b"hello world";
+        let sig = P::sign(msg, &sk);
+        assert_eq!(sig.as_ref().len(), P::SIGNATUREBYTES);
+        assert!(P::verify(msg, &sig, &pk).is_ok());
+        assert!(P::verify(msg, &sig, &pk2).is_err());
+
+        let sig2 = P::Signature::try_from(sig.as_ref()).unwrap();
+        assert_eq!(sig, sig2);
+        assert_eq!(P::ALGORITHM.signature_len(), P::SIGNATUREBYTES);
+    }
+
+    #[cfg(feature = "dilithium2")]
+    #[test]
+    fn test_dilithium2() {
+        roundtrip::<Dilithium2>();
+    }
+
+    #[cfg(feature = "dilithium3")]
+    #[test]
+    fn test_dilithium3() {
+        roundtrip::<Dilithium3>();
+    }
+
+    #[cfg(feature = "dilithium5")]
+    #[test]
+    fn test_dilithium5() {
+        roundtrip::<Dilithium5>();
+    }
+
+    #[cfg(feature = "ml_dsa_44")]
+    #[test]
+    fn test_ml_dsa_44() {
+        roundtrip::<MlDsa44>();
+    }
+
+    #[cfg(feature = "ml_dsa_65")]
+    #[test]
+    fn test_ml_dsa_65() {
+        roundtrip::<MlDsa65>();
+    }
+
+    #[cfg(feature = "ml_dsa_87")]
+    #[test]
+    fn test_ml_dsa_87() {
+        roundtrip::<MlDsa87>();
+    }
+}
@@ -0,0 +1,440 @@
+//! DER encoding of keys as PKCS#8 private keys and X.509 SubjectPublicKeyInfo.
+//!
+//! Implements the traits of the [`pkcs8`] and [`spki`] crates:
+//! `PublicKey` implements [`EncodePublicKey`] and [`DecodePublicKey`],
+//! `SecretKey` and `KeySeed` implement [`EncodePrivateKey`] and
+//! [`DecodePrivateKey`], which provide `to_public_key_der`,
+//! `from_public_key_der`, `to_pkcs8_der` and `from_pkcs8_der`.
+//!
+//! The ML-DSA parameter sets use the NIST OIDs `2.16.840.1.101.3.4.3.{17,18,19}`,
+//! the round 3 dilithium parameter sets the OIDs also used by Open Quantum Safe
+//! and BouncyCastle, `1.3.6.1.4.1.2.267.7.{4.4,6.5,8.7}`. The algorithm parameters
+//! are always absent.
+//!
+//! The `privateKey` field of a PKCS#8 document holds the private key `CHOICE` of
+//! the IETF LAMPS ML-DSA draft:
+//! ```text
+//! PrivateKey ::= CHOICE {
+//!   seed [0] OCTET STRING (SIZE (32)),
+//!   expandedKey OCTET STRING,
+//!   both SEQUENCE {
+//!     seed OCTET STRING (SIZE (32)),
+//!     expandedKey OCTET STRING
+//!   }
+//! }
+//! ```
+//! A `SecretKey` is encoded as `expandedKey` and a `KeySeed` as `seed`. When
+//! decoding a `SecretKey`, all three alternatives are accepted: the key is
+//! derived from a `seed`, and for `both` the derived key must match the
+//! `expandedKey`. A `KeySeed` can only be decoded from `seed` or `both`, again
+//! checking `both` for consistency.
+//!
+//! [`EncodePublicKey`]: pkcs8::EncodePublicKey
+//! [`DecodePublicKey`]: pkcs8::DecodePublicKey
+//! [`EncodePrivateKey`]: pkcs8::EncodePrivateKey
+//! [`DecodePrivateKey`]: pkcs8::DecodePrivateKey
+
+use crate::InvalidLength;
+use pkcs8::der::asn1::{BitStringRef, ContextSpecific, OctetStringRef};
+use pkcs8::der::zeroize::Zeroizing;
+use pkcs8::der::{AnyRef, Decode, Encode, SecretDocument, Tag, TagMode, TagNumber, Tagged};
+use pkcs8::spki::{
+    self, AlgorithmIdentifierRef, AssociatedAlgorithmIdentifier, SubjectPublicKeyInfoRef,
+};
+use pkcs8::{
+    AssociatedOid, Document, EncodePrivateKey, EncodePublicKey, ObjectIdentifier, PrivateKeyInfo,
+};
+
+/// Reason a key failed to decode, mapped to the error types of [`pkcs8`] and
+/// [`spki`], or to the more descriptive
+/// [`PemError`](crate::pem::PemError).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DecodeError {
+    /// The key is for the algorithm with this OID.
+    AlgorithmMismatch(ObjectIdentifier),
+    /// The algorithm parameters are present.
+    Parameters,
+    /// The key or seed has the wrong length.
+    InvalidLength(InvalidLength),
+    /// The private key `CHOICE` is malformed or inconsistent.
+    Malformed,
+}
+
+impl From<InvalidLength> for DecodeError {
+    fn from(err: InvalidLength) -> Self {
+        Self::InvalidLength(err)
+    }
+}
+
+impl From<DecodeError> for spki::Error {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::AlgorithmMismatch(oid) => spki::Error::OidUnknown { oid },
+            _ => spki::Error::KeyMalformed,
+        }
+    }
+}
+
+impl From<DecodeError> for pkcs8::Error {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::AlgorithmMismatch(oid) => spki::Error::OidUnknown { oid }.into(),
+            DecodeError::Parameters => pkcs8::Error::ParametersMalformed,
+            _ => pkcs8::Error::KeyMalformed,
+        }
+    }
+}
+
+/// Decoded private key `CHOICE`.
+enum PrivateKeyChoice<'a> {
+    Seed(&'a [u8; 32]),
+    ExpandedKey(&'a [u8]),
+    Both {
+        seed: &'a [u8; 32],
+        expanded_key: &'a [u8],
+    },
+}
+
+impl<'a> PrivateKeyChoice<'a> {
+    fn decode(private_key: &'a [u8]) -> Result<Self, DecodeError> {
+        let any = AnyRef::from_der(private_key).map_err(|_| DecodeError::Malformed)?;
+        match any.tag() {
+            Tag::ContextSpecific {
+                constructed: false,
+                number: TagNumber::N0,
+            } => Ok(Self::Seed(seed_array(any.value())?)),
+            Tag::OctetString => Ok(Self::ExpandedKey(any.value())),
+            Tag::Sequence => {
+                let (seed, expanded_key) = any
+                    .sequence(|reader| {
+                        let seed = OctetStringRef::decode(reader)?;
+                        let expanded_key = OctetStringRef::decode(reader)?;
+                        Ok((seed, expanded_key))
+                    })
+                    .map_err(|_| DecodeError::Malformed)?;
+                Ok(Self::Both {
+                    seed: seed_array(seed.as_bytes())?,
+                    expanded_key: expanded_key.as_bytes(),
+                })
+            }
+            _ => Err(DecodeError::Malformed),
+        }
+    }
+}
+
+/// Check the length of the seed of the private key `CHOICE`.
+fn seed_array(seed: &[u8]) -> Result<&[u8; 32], InvalidLength> {
+    seed.try_into().map_err(|_| InvalidLength {
+        expected: 32,
+        actual: seed.len(),
+    })
+}
+
+/// Check the algorithm identifier of a private key and decode its `CHOICE`.
+fn decode_private_key<'a>(
+    info: &PrivateKeyInfo<'a>,
+    oid: ObjectIdentifier,
+) -> Result<PrivateKeyChoice<'a>, DecodeError> {
+    check_algorithm(&info.algorithm, oid)?;
+    PrivateKeyChoice::decode(info.private_key)
+}
+
+/// Check that the secret key `sk` derived from the seed of the `both` alternative
+/// matches its `expandedKey`.
+fn check_expanded_key(sk: &impl AsRef<[u8]>, expanded_key: &[u8]) -> Result<(), DecodeError> {
+    if sk.as_ref() != expanded_key {
+        return Err(DecodeError::Malformed);
+    }
+    Ok(())
+}
+
+/// Encode a PKCS#8 document with the private key `CHOICE` `private_key`.
+fn encode_private_key(
+    algorithm: AlgorithmIdentifierRef<'static>,
+    private_key: &impl Encode,
+) -> pkcs8::Result<SecretDocument> {
+    let private_key = Zeroizing::new(private_key.to_der()?);
+    PrivateKeyInfo::new(algorithm, &private_key).try_into()
+}
+
+/// The `seed` alternative of the private key `CHOICE`.
+fn seed_choice(seed: &[u8]) -> pkcs8::Result<ContextSpecific<OctetStringRef<'_>>> {
+    Ok(ContextSpecific {
+        tag_number: TagNumber::N0,
+        tag_mode: TagMode::Implicit,
+        value: OctetStringRef::new(seed)?,
+    })
+}
+
+/// Check for the OID `oid` with absent parameters.
+fn check_algorithm(
+    algorithm: &AlgorithmIdentifierRef<'_>,
+    oid: ObjectIdentifier,
+) -> Result<(), DecodeError> {
+    if algorithm.oid != oid {
+        return Err(DecodeError::AlgorithmMismatch(algorithm.oid));
+    }
+    match algorithm.parameters {
+        None => Ok(()),
+        Some(_) => Err(DecodeError::Parameters),
+    }
+}
+
+/// Check the algorithm identifier of a public key and return its bytes.
+fn decode_public_key<'a>(
+    spki: &SubjectPublicKeyInfoRef<'a>,
+    oid: ObjectIdentifier,
+) -> Result<&'a [u8], DecodeError> {
+    check_algorithm(&spki.algorithm, oid)?;
+    spki.subject_public_key
+        .as_bytes()
+        .ok_or(DecodeError::Malformed)
+}
+
+/// Encode `public_key` as SubjectPublicKeyInfo.
+fn encode_public_key(
+    algorithm: AlgorithmIdentifierRef<'static>,
+    public_key: &[u8],
+) -> spki::Result<Document> {
+    SubjectPublicKeyInfoRef {
+        algorithm,
+        subject_public_key: BitStringRef::from_bytes(public_key)?,
+    }
+    .try_into()
+}
+
+/// Algorithm identifier with the OID of `P` and absent parameters.
+const fn algorithm_identifier<P: AssociatedOid>() -> AlgorithmIdentifierRef<'static> {
+    AlgorithmIdentifierRef {
+        oid: P::OID,
+        parameters: None,
+    }
+}
+
+macro_rules! impl_pkcs8 {
+    ($($feature:literal, $marker:ident, $module:ident, $oid:literal, $name:literal;)*) => {
+        /// Name of the algorithm with OID `oid`, also if its parameter set is not
+        /// enabled in this build.
+        #[cfg(feature = "pem")]
+        pub(crate) fn algorithm_name(oid: &ObjectIdentifier) -> Option<&'static str> {
+            $(
+                if *oid == ObjectIdentifier::new_unwrap($oid) {
+                    return Some($name);
+                }
+            )*
+            None
+        }
+
+        $(
+            #[cfg(feature = $feature)]
+            impl AssociatedOid for crate::$marker {
+                const OID: ObjectIdentifier = ObjectIdentifier::new_unwrap($oid);
+            }
+
+            #[cfg(feature = $feature)]
+            impl AssociatedAlgorithmIdentifier for crate::$module::PublicKey {
+                type Params = AnyRef<'static>;
+
+                const ALGORITHM_IDENTIFIER: AlgorithmIdentifierRef<'static> =
+                    algorithm_identifier::<crate::$marker>();
+            }
+
+            #[cfg(feature = $feature)]
+            impl AssociatedAlgorithmIdentifier for crate::$module::SecretKey {
+                type Params = AnyRef<'static>;
+
+                const ALGORITHM_IDENTIFIER: AlgorithmIdentifierRef<'static> =
+                    algorithm_identifier::<crate::$marker>();
+            }
+
+            #[cfg(feature = $feature)]
+            impl AssociatedAlgorithmIdentifier for crate::$module::KeySeed {
+                type Params = AnyRef<'static>;
+
+                const ALGORITHM_IDENTIFIER: AlgorithmIdentifierRef<'static> =
+                    algorithm_identifier::<crate::$marker>();
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::PublicKey {
+                pub(crate) fn from_spki(spki: &SubjectPublicKeyInfoRef<'_>) -> Result<Self, DecodeError> {
+                    let public_key = decode_public_key(spki, Self::ALGORITHM_IDENTIFIER.oid)?;
+                    Ok(Self::from_bytes(public_key)?)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl EncodePublicKey for crate::$module::PublicKey {
+                fn to_public_key_der(&self) -> spki::Result<Document> {
+                    encode_public_key(Self::ALGORITHM_IDENTIFIER, self.as_ref())
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl TryFrom<SubjectPublicKeyInfoRef<'_>> for crate::$module::PublicKey {
+                type Error = spki::Error;
+
+                fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+                    Ok(Self::from_spki(&spki)?)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::SecretKey {
+                pub(crate) fn from_private_key_info(info: &PrivateKeyInfo<'_>) -> Result<Self, DecodeError> {
+                    match decode_private_key(info, Self::ALGORITHM_IDENTIFIER.oid)? {
+                        PrivateKeyChoice::Seed(seed) => {
+                            Ok(Self::from_seed(&crate::$module::KeySeed::new(*seed)))
+                        }
+                        PrivateKeyChoice::ExpandedKey(expanded_key) => Ok(Self::from_bytes(expanded_key)?),
+                        PrivateKeyChoice::Both { seed, expanded_key } => {
+                            let sk = Self::from_seed(&crate::$module::KeySeed::new(*seed));
+                            check_expanded_key(&sk, expanded_key)?;
+                            Ok(sk)
+                        }
+                    }
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl EncodePrivateKey for crate::$module::SecretKey {
+                fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+                    encode_private_key(Self::ALGORITHM_IDENTIFIER, &OctetStringRef::new(self.as_ref())?)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl TryFrom<PrivateKeyInfo<'_>> for crate::$module::SecretKey {
+                type Error = pkcs8::Error;
+
+                fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+                    Ok(Self::from_private_key_info(&info)?)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl crate::$module::KeySeed {
+                pub(crate) fn from_private_key_info(info: &PrivateKeyInfo<'_>) -> Result<Self, DecodeError> {
+                    match decode_private_key(info, Self::ALGORITHM_IDENTIFIER.oid)? {
+                        PrivateKeyChoice::Seed(seed) => Ok(Self::new(*seed)),
+                        PrivateKeyChoice::ExpandedKey(_) => Err(DecodeError::Malformed),
+                        PrivateKeyChoice::Both { seed, expanded_key } => {
+                            let seed = Self::new(*seed);
+                            check_expanded_key(&crate::$module::SecretKey::from_seed(&seed), expanded_key)?;
+                            Ok(seed)
+                        }
+                    }
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl EncodePrivateKey for crate::$module::KeySeed {
+                fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+                    encode_private_key(Self::ALGORITHM_IDENTIFIER, &seed_choice(self.as_ref())?)
+                }
+            }
+
+            #[cfg(feature = $feature)]
+            impl TryFrom<PrivateKeyInfo<'_>> for crate::$module::KeySeed {
+                type Error = pkcs8::Error;
+
+                fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+                    Ok(Self::from_private_key_info(&info)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_pkcs8! {
+    "dilithium2", Dilithium2, dilithium2, "1.3.6.1.4.1.2.267.7.4.4", "Dilithium2";
+    "dilithium3", Dilithium3, dilithium3, "1.3.6.1.4.1.2.267.7.6.5", "Dilithium3";
+    "dilithium5", Dilithium5, dilithium5, "1.3.6.1.4.1.2.267.7.8.7", "Dilithium5";
+    "ml_dsa_44", MlDsa44, ml_dsa_44, "2.16.840.1.101.3.4.3.17", "ML-DSA-44";
+    "ml_dsa_65", MlDsa65, ml_dsa_65, "2.16.840.1.101.3.4.3.18", "ML-DSA-65";
+    "ml_dsa_87", MlDsa87, ml_dsa_87, "2.16.840.1.101.3.4.3.19", "ML-DSA-87";
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+
+    #[cfg(feature = "ml_dsa_44")]
+    #[test]
+    fn test_ml_dsa_44() {
+        use crate::ml_dsa_44::{generate_keypair_from_seed, KeySeed, PublicKey, SecretKey};
+
+        let mut bytes = [0; 32];
+        for (b, i) in bytes.iter_mut().zip(0..) {
+            *b = i;
+        }
+        let seed = KeySeed::new(bytes);
+        let (pk, sk) = generate_keypair_from_seed(&seed);
+
+        // seed-only encoding, see the examples of the LAMPS draft
+        let der = seed.to_pkcs8_der().unwrap();
+        let mut expected = [
+            0x30, 0x34, 0x02, 0x01, 0x00, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65,
+            0x03, 0x04, 0x03, 0x11, 0x04, 0x22, 0x80, 0x20,
+        ]
+        .to_vec();
+        expected.extend(0..32);
+        assert_eq!(der.as_bytes(), &expected[..]);
+        assert_eq!(KeySeed::from_pkcs8_der(der.as_bytes()).unwrap(), seed);
+        assert_eq!(SecretKey::from_pkcs8_der(der.as_bytes()).unwrap(), sk);
+
+        // expanded encoding
+        let der = sk.to_pkcs8_der().unwrap();
+        assert_eq!(SecretKey::from_pkcs8_der(der.as_bytes()).unwrap(), sk);
+        assert!(KeySeed::from_pkcs8_der(der.as_bytes()).is_err());
+
+        // both, as written by OpenSSL
+        let mut inner = vec![0x30, 0x82, 0x0a, 0x26, 0x04, 0x20];
+        inner.extend(0..32);
+        inner.extend([0x04, 0x82, 0x0a, 0x00]);
+        inner.extend(sk.to_bytes());
+        let alg = pkcs8::spki::AlgorithmIdentifierRef {
+            oid: "2.16.840.1.101.3.4.3.17".parse().unwrap(),
+            parameters: None,
+        };
+        let der = pkcs8::der::Encode::to_der(&pkcs8::PrivateKeyInfo::new(alg, &inner)).unwrap();
+        assert_eq!(SecretKey::from_pkcs8_der(&der).unwrap(), sk);
+        assert_eq!(KeySeed::from_pkcs8_der(&der).unwrap(), seed);
+        let last = inner.len() - 1;
+        inner[last] ^= 1;
+        let der = pkcs8::der::Encode::to_der(&pkcs8::PrivateKeyInfo::new(alg, &inner)).unwrap();
+        assert!(SecretKey::from_pkcs8_der(&der).is_err());
+        assert!(KeySeed::from_pkcs8_der(&der).is_err());
+
+        let der = pk.to_public_key_der().unwrap();
+        assert_eq!(
+            &der.as_bytes()[..22],
+            &[
+                0x30, 0x82, 0x05, 0x32, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03,
+                0x04, 0x03, 0x11, 0x03, 0x82, 0x05, 0x21, 0x00,
+            ]
+        );
+        assert_eq!(PublicKey::from_public_key_der(der.as_bytes()).unwrap(), pk);
+    }
+
+    #[cfg(all(feature = "dilithium2", feature = "ml_dsa_44"))]
+    #[test]
+    fn test_algorithm_mismatch() {
+        let seed = crate::dilithium2::KeySeed::new([37; 32]);
+        let (pk, sk) = crate::dilithium2::generate_keypair_from_seed(&seed);
+
+        let der = pk.to_public_key_der().unwrap();
+        assert!(crate::dilithium2::PublicKey::from_public_key_der(der.as_bytes()).is_ok());
+        assert!(crate::ml_dsa_44::PublicKey::from_public_key_der(der.as_bytes()).is_err());
+        let der = sk.to_pkcs8_der().unwrap();
+        assert!(crate::dilithium2::SecretKey::from_pkcs8_der(der.as_bytes()).is_ok());
+        assert!(crate::ml_dsa_44::SecretKey::from_pkcs8_der(der.as_bytes()).is_err());
+        let der = seed.to_pkcs8_der().unwrap();
+        assert_eq!(
+            crate::dilithium2::SecretKey::from_pkcs8_der(der.as_bytes()).unwrap(),
+            sk
+        );
+        assert!(crate::ml_dsa_44::KeySeed::from_pkcs8_der(der.as_bytes()).is_err());
+    }
+}
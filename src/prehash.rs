@@ -0,0 +1,341 @@
+//! Pre-hash support for HashML-DSA (FIPS 204, section 5.4).
+//!
+//! In HashML-DSA the signer signs the digest `PH(M)` of the message instead of
+//! the message itself, so large messages can be hashed incrementally (or by a
+//! different party). The digest is bound to the hash algorithm used through
+//! the DER encoded OID of the hash function, which is part of the signed
+//! message `M' = 1 || len(ctx) || ctx || OID || PH(M)`.
+
+use cty::size_t;
+
+/// Hash function used to compute the digest for HashML-DSA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// SHA-224 (FIPS 180-4).
+    Sha224,
+    /// SHA-256 (FIPS 180-4).
+    Sha256,
+    /// SHA-384 (FIPS 180-4).
+    Sha384,
+    /// SHA-512 (FIPS 180-4).
+    Sha512,
+    /// SHA-512/224 (FIPS 180-4).
+    Sha512_224,
+    /// SHA-512/256 (FIPS 180-4).
+    Sha512_256,
+    /// SHA3-224 (FIPS 202).
+    Sha3_224,
+    /// SHA3-256 (FIPS 202).
+    Sha3_256,
+    /// SHA3-384 (FIPS 202).
+    Sha3_384,
+    /// SHA3-512 (FIPS 202).
+    Sha3_512,
+    /// SHAKE128 (FIPS 202) with 256 bits of output, see [`Shake128`].
+    Shake128,
+    /// SHAKE256 (FIPS 202) with 512 bits of output, see [`Shake256`].
+    Shake256,
+}
+
+/// Length of the DER encoding of the hash algorithm OIDs.
+pub const OIDBYTES: usize = 11;
+
+impl HashAlgorithm {
+    /// DER encoding of the object identifier of the hash algorithm.
+    ///
+    /// All supported hash functions live under the NIST arc
+    /// `2.16.840.1.101.3.4.2`.
+    pub const fn oid(self) -> &'static [u8; OIDBYTES] {
+        match self {
+            Self::Sha256 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+            ],
+            Self::Sha384 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02,
+            ],
+            Self::Sha512 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03,
+            ],
+            Self::Sha224 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04,
+            ],
+            Self::Sha512_224 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x05,
+            ],
+            Self::Sha512_256 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x06,
+            ],
+            Self::Sha3_224 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x07,
+            ],
+            Self::Sha3_256 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x08,
+            ],
+            Self::Sha3_384 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x09,
+            ],
+            Self::Sha3_512 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a,
+            ],
+            Self::Shake128 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0b,
+            ],
+            Self::Shake256 => &[
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0c,
+            ],
+        }
+    }
+
+    /// Length in bytes of the digest produced by the hash algorithm.
+    pub const fn digest_len(self) -> usize {
+        match self {
+            Self::Sha224 | Self::Sha512_224 | Self::Sha3_224 => 28,
+            Self::Sha256 | Self::Sha512_256 | Self::Sha3_256 | Self::Shake128 => 32,
+            Self::Sha384 | Self::Sha3_384 => 48,
+            Self::Sha512 | Self::Sha3_512 | Self::Shake256 => 64,
+        }
+    }
+}
+
+/// Error returned by the HashML-DSA sign and verify functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrehashError {
+    /// The context string is longer than 255 bytes.
+    ContextTooLong,
+    /// The length of the digest does not match the output length of the hash
+    /// algorithm.
+    InvalidDigestLength {
+        /// Digest length of the hash algorithm.
+        expected: usize,
+        /// Length of the digest that was passed.
+        actual: usize,
+    },
+}
+
+impl From<crate::ContextTooLong> for PrehashError {
+    fn from(_: crate::ContextTooLong) -> Self {
+        Self::ContextTooLong
+    }
+}
+
+impl core::fmt::Display for PrehashError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ContextTooLong => core::fmt::Display::fmt(&crate::ContextTooLong, f),
+            Self::InvalidDigestLength { expected, actual } => write!(
+                f,
+                "digest has length {}, but the hash algorithm produces {} bytes",
+                actual, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrehashError {}
+
+/// The prefix `1 || len(ctx) || ctx || OID` of the formatted message `M'`.
+#[cfg(any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87"))]
+pub(crate) struct Prefix {
+    buf: [u8; 2 + 255 + OIDBYTES],
+    len: usize,
+}
+
+#[cfg(any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87"))]
+impl Prefix {
+    pub(crate) fn new(
+        hash: HashAlgorithm,
+        digest: &[u8],
+        ctx: &[u8],
+    ) -> Result<Self, PrehashError> {
+        let ctx_len = u8::try_from(ctx.len()).map_err(|_| PrehashError::ContextTooLong)?;
+        if digest.len() != hash.digest_len() {
+            return Err(PrehashError::InvalidDigestLength {
+                expected: hash.digest_len(),
+                actual: digest.len(),
+            });
+        }
+
+        let mut buf = [0; 2 + 255 + OIDBYTES];
+        buf[0] = 1;
+        buf[1] = ctx_len;
+        buf[2..2 + ctx.len()].copy_from_slice(ctx);
+        buf[2 + ctx.len()..2 + ctx.len() + OIDBYTES].copy_from_slice(hash.oid());
+        Ok(Self {
+            buf,
+            len: 2 + ctx.len() + OIDBYTES,
+        })
+    }
+}
+
+#[cfg(any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87"))]
+impl AsRef<[u8]> for Prefix {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+// Incremental SHAKE from the vendored `fips202.c`; the Keccak state and the
+// number of buffered bytes are stored inline.
+#[repr(C)]
+struct ShakeIncCtx {
+    ctx: [u64; 26],
+}
+
+#[link(name = "pqclean_common_helpers")]
+extern "C" {
+    fn shake128_inc_init(state: *mut ShakeIncCtx);
+    fn shake128_inc_absorb(state: *mut ShakeIncCtx, input: *const u8, inlen: size_t);
+    fn shake128_inc_finalize(state: *mut ShakeIncCtx);
+    fn shake128_inc_squeeze(output: *mut u8, outlen: size_t, state: *mut ShakeIncCtx);
+    fn shake128_inc_ctx_release(state: *mut ShakeIncCtx);
+
+    fn shake256_inc_init(state: *mut ShakeIncCtx);
+    fn shake256_inc_absorb(state: *mut ShakeIncCtx, input: *const u8, inlen: size_t);
+    fn shake256_inc_finalize(state: *mut ShakeIncCtx);
+    fn shake256_inc_squeeze(output: *mut u8, outlen: size_t, state: *mut ShakeIncCtx);
+    fn shake256_inc_ctx_release(state: *mut ShakeIncCtx);
+}
+
+macro_rules! impl_shake_hasher {
+    ($name:ident, $alg:expr, $init:ident, $absorb:ident, $finalize:ident, $squeeze:ident, $release:ident) => {
+        impl $name {
+            /// Start a new hash computation.
+            pub fn new() -> Self {
+                let mut state = ShakeIncCtx { ctx: [0; 26] };
+                // SAFETY: `state` has the layout of the C context and is valid for
+                // writes; the state is stored inline, so it can be moved afterwards
+                unsafe { $init(&mut state) };
+                Self(state)
+            }
+
+            /// Absorb `data` into the hash state.
+            pub fn update<D: AsRef<[u8]>>(&mut self, data: D) {
+                let data: &[u8] = data.as_ref();
+                // SAFETY: `self.0` was initialized in `new`, and `data` is valid for
+                // reads of `data.len()` bytes
+                unsafe { $absorb(&mut self.0, data.as_ptr(), data.len()) };
+            }
+
+            /// Finish the computation and return the digest, to be used with
+            #[doc = concat!("[`", stringify!($alg), "`].")]
+            pub fn finalize(mut self) -> [u8; $alg.digest_len()] {
+                let mut digest = [0; $alg.digest_len()];
+                // SAFETY: `self.0` was initialized in `new` and is finalized only once,
+                // as `self` is consumed, and `digest` is valid for writes of
+                // `digest.len()` bytes
+                unsafe {
+                    $finalize(&mut self.0);
+                    $squeeze(digest.as_mut_ptr(), digest.len(), &mut self.0);
+                }
+                digest
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                // SAFETY: `self.0` was initialized in `new` and is not used afterwards
+                unsafe { $release(&mut self.0) };
+            }
+        }
+    };
+}
+
+/// Incremental SHAKE128 hasher producing the 32 byte digest for
+/// [`HashAlgorithm::Shake128`].
+pub struct Shake128(ShakeIncCtx);
+
+impl_shake_hasher!(
+    Shake128,
+    HashAlgorithm::Shake128,
+    shake128_inc_init,
+    shake128_inc_absorb,
+    shake128_inc_finalize,
+    shake128_inc_squeeze,
+    shake128_inc_ctx_release
+);
+
+/// Incremental SHAKE256 hasher producing the 64 byte digest for
+/// [`HashAlgorithm::Shake256`].
+pub struct Shake256(ShakeIncCtx);
+
+impl_shake_hasher!(
+    Shake256,
+    HashAlgorithm::Shake256,
+    shake256_inc_init,
+    shake256_inc_absorb,
+    shake256_inc_finalize,
+    shake256_inc_squeeze,
+    shake256_inc_ctx_release
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shake128_empty() {
+        let expected = [
+            0x7f, 0x9c, 0x2b, 0xa4, 0xe8, 0x8f, 0x82, 0x7d, 0x61, 0x60, 0x45, 0x50, 0x76, 0x05,
+            0x85, 0x3e, 0xd7, 0x3b, 0x80, 0x93, 0xf6, 0xef, 0xbc, 0x88, 0xeb, 0x1a, 0x6e, 0xac,
+            0xfa, 0x66, 0xef, 0x26,
+        ];
+        assert_eq!(Shake128::new().finalize(), expected);
+    }
+
+    #[test]
+    fn test_shake256_empty() {
+        let expected = [
+            0x46, 0xb9, 0xdd, 0x2b, 0x0b, 0xa8, 0x8d, 0x13, 0x23, 0x3b, 0x3f, 0xeb, 0x74, 0x3e,
+            0xeb, 0x24, 0x3f, 0xcd, 0x52, 0xea, 0x62, 0xb8, 0x1b, 0x82, 0xb5, 0x0c, 0x27, 0x64,
+            0x6e, 0xd5, 0x76, 0x2f, 0xd7, 0x5d, 0xc4, 0xdd, 0xd8, 0xc0, 0xf2, 0x00, 0xcb, 0x05,
+            0x01, 0x9d, 0x67, 0xb5, 0x92, 0xf6, 0xfc, 0x82, 0x1c, 0x49, 0x47, 0x9a, 0xb4, 0x86,
+            0x40, 0x29, 0x2e, 0xac, 0xb3, 0xb7, 0xc4, 0xbe,
+        ];
+        assert_eq!(Shake256::new().finalize(), expected);
+    }
+
+    #[test]
+    fn test_shake_incremental() {
+        let mut hasher = Shake256::new();
+        hasher.update(b"hello ");
+        hasher.update(b"world");
+        let mut other = Shake256::new();
+        other.update(b"hello world");
+        assert_eq!(hasher.finalize(), other.finalize());
+        // dropping an unfinished hasher releases the state
+        let mut unfinished = Shake128::new();
+        unfinished.update(b"hello world");
+    }
+
+    #[cfg(any(feature = "ml_dsa_44", feature = "ml_dsa_65", feature = "ml_dsa_87"))]
+    #[test]
+    fn test_prefix() {
+        let digest = [0u8; 32];
+        let prefix = Prefix::new(HashAlgorithm::Sha256, &digest, b"ctx").unwrap();
+        assert_eq!(
+            prefix.as_ref(),
+            [
+                1, 3, b'c', b't', b'x', 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x01
+            ]
+        );
+        assert_eq!(
+            Prefix::new(HashAlgorithm::Sha512, &digest, b"").err(),
+            Some(PrehashError::InvalidDigestLength {
+                expected: 64,
+                actual: 32
+            })
+        );
+        assert_eq!(
+            Prefix::new(HashAlgorithm::Sha256, &digest, &[0u8; 256]).err(),
+            Some(PrehashError::ContextTooLong)
+        );
+    }
+}
@@ -1,6 +1,6 @@
-// Wrapper around a fixed length array of bytes which implements
-// [`serde::Serialize`] and [`serde::Deserialize`] as the *byte array* type in
-// the `serde` data model.
+// Wrapper around a fixed length array of bytes. With the `serde` feature it is
+// the *byte array* type of the `serde` data model in binary formats and a base64
+// string in human-readable formats, see the `serde` module below.
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
 pub struct ByteArray<const N: usize>(pub [u8; N]);
 
@@ -10,6 +10,12 @@ impl<const N: usize> AsRef<[u8; N]> for ByteArray<N> {
     }
 }
 
+impl<const N: usize> AsRef<ByteArray<N>> for ByteArray<N> {
+    fn as_ref(&self) -> &ByteArray<N> {
+        self
+    }
+}
+
 impl<const N: usize> AsMut<[u8; N]> for ByteArray<N> {
     fn as_mut(&mut self) -> &mut [u8; N] {
         &mut self.0
@@ -34,35 +40,149 @@ impl<const N: usize> From<[u8; N]> for ByteArray<N> {
     }
 }
 
+impl<const N: usize> TryFrom<&[u8]> for ByteArray<N> {
+    type Error = crate::InvalidLength;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; N]>::try_from(slice)
+            .map(Self)
+            .map_err(|_| crate::InvalidLength {
+                expected: N,
+                actual: slice.len(),
+            })
+    }
+}
+
 impl<const N: usize> ByteArray<N> {
     pub fn new(arr: [u8; N]) -> Self {
         Self(arr)
     }
 }
 
+#[link(name = "pqclean_common_helpers")]
+extern "C" {
+    fn pqclean_cleanse(ptr: *mut cty::c_void, len: usize);
+}
+
+/// Overwrite `buf` with zeros, in a way the compiler does not optimize away.
+pub(crate) fn cleanse(buf: &mut [u8]) {
+    // SAFETY: `buf` is valid for writes of `buf.len()` bytes
+    unsafe { pqclean_cleanse(buf.as_mut_ptr().cast(), buf.len()) };
+}
+
+#[cfg(feature = "zeroize")]
+impl<const N: usize> zeroize::Zeroize for ByteArray<N> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytearray_try_from() {
+        let bytes = b"hello";
+        assert_eq!(ByteArray::<5>::try_from(&bytes[..]), Ok(ByteArray(*bytes)));
+        assert_eq!(
+            ByteArray::<4>::try_from(&bytes[..]),
+            Err(crate::InvalidLength {
+                expected: 4,
+                actual: 5
+            })
+        );
+    }
+}
+
 #[cfg(feature = "serde")]
 pub mod serde {
+    //! Serde support for [`ByteArray`] and the key and signature types.
+    //!
+    //! In binary formats a [`ByteArray`] is the *byte array* type of the `serde`
+    //! data model. In human-readable formats, where that type would end up as e.g.
+    //! a long JSON array of integers, it is a standard base64 string with padding
+    //! instead; this is also how RON writes byte arrays. The [`hex`] and
+    //! [`base64`] modules select the human-readable encoding explicitly with
+    //! `#[serde(with = "..")]` and leave the binary format unchanged.
+    //!
+    //! Sequences of integers, as written for a [`ByteArray`] by earlier versions
+    //! in human-readable formats, are still accepted when deserializing.
+
     use super::ByteArray;
+    use base64ct::{Base64, Encoding as _};
     use core::fmt;
-    use serde::de::{Error, Visitor};
+    use serde::de::{Error, IgnoredAny, SeqAccess, Visitor};
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+    /// Encoding of a [`ByteArray`] in a human-readable format.
+    #[derive(Clone, Copy)]
+    enum Encoding {
+        Hex,
+        Base64,
+    }
+
+    /// Hex or base64 encoding of `bytes`, written without allocating.
+    struct Encoded<'a>(&'a [u8], Encoding);
+
+    impl fmt::Display for Encoded<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.1 {
+                Encoding::Hex => self.0.iter().try_for_each(|b| write!(f, "{:02x}", b)),
+                // chunks of a multiple of 3 bytes encode without padding
+                Encoding::Base64 => self.0.chunks(48).try_for_each(|chunk| {
+                    let mut buf = [0; 64];
+                    f.write_str(Base64::encode(chunk, &mut buf).map_err(|_| fmt::Error)?)
+                }),
+            }
+        }
+    }
+
+    fn serialize<S: Serializer, const N: usize>(
+        bytes: &ByteArray<N>,
+        serializer: S,
+        encoding: Encoding,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&Encoded(&bytes.0[..], encoding))
+        } else {
+            serializer.serialize_bytes(&bytes.0[..])
+        }
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+        encoding: Encoding,
+    ) -> Result<ByteArray<N>, D::Error> {
+        if deserializer.is_human_readable() {
+            // not `deserialize_str`, to also accept a sequence of integers
+            deserializer.deserialize_any(ByteArrayVisitor(Some(encoding)))
+        } else {
+            deserializer.deserialize_bytes(ByteArrayVisitor(None))
+        }
+    }
+
     impl<const N: usize> Serialize for ByteArray<N> {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: Serializer,
         {
-            serializer.serialize_bytes(&self.0[..])
+            serialize(self, serializer, Encoding::Base64)
         }
     }
 
-    struct ByteArrayVisitor<const N: usize>;
+    /// Visitor for a byte array, or a string with the given encoding.
+    struct ByteArrayVisitor<const N: usize>(Option<Encoding>);
 
     impl<'de, const N: usize> Visitor<'de> for ByteArrayVisitor<N> {
         type Value = ByteArray<N>;
 
         fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(formatter, "a byte array of length {}", N)
+            match self.0 {
+                None => write!(formatter, "a byte array of length {}", N),
+                Some(Encoding::Hex) => write!(formatter, "a hex string of {} bytes", N),
+                Some(Encoding::Base64) => write!(formatter, "a base64 string of {} bytes", N),
+            }
         }
 
         fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
@@ -74,6 +194,53 @@ pub mod serde {
                 Err(E::invalid_length(v.len(), &self))
             }
         }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            let mut bytes = [0; N];
+            match self.0 {
+                None => return Err(E::invalid_type(serde::de::Unexpected::Str(v), &self)),
+                Some(Encoding::Hex) => {
+                    if v.len() != 2 * N {
+                        return Err(E::invalid_length(v.len() / 2, &self));
+                    }
+                    let digit = |c: u8| {
+                        char::from(c)
+                            .to_digit(16)
+                            .ok_or_else(|| E::custom("invalid hex"))
+                    };
+                    for (b, pair) in bytes.iter_mut().zip(v.as_bytes().chunks(2)) {
+                        let value = digit(pair[0])? << 4 | digit(pair[1])?;
+                        *b = u8::try_from(value).map_err(|_| E::custom("invalid hex"))?;
+                    }
+                }
+                Some(Encoding::Base64) => {
+                    let padding = v.bytes().rev().take_while(|&b| b == b'=').count();
+                    let actual = (v.len() / 4 * 3).saturating_sub(padding);
+                    if actual != N {
+                        return Err(E::invalid_length(actual, &self));
+                    }
+                    Base64::decode(v, &mut bytes).map_err(|_| E::custom("invalid base64"))?;
+                }
+            }
+            Ok(ByteArray(bytes))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = [0; N];
+            for (i, b) in bytes.iter_mut().enumerate() {
+                *b = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            let mut len = N;
+            while seq.next_element::<IgnoredAny>()?.is_some() {
+                len += 1;
+            }
+            if len != N {
+                return Err(A::Error::invalid_length(len, &self));
+            }
+            Ok(ByteArray(bytes))
+        }
     }
 
     impl<'de, const N: usize> Deserialize<'de> for ByteArray<N> {
@@ -81,36 +248,165 @@ pub mod serde {
         where
             D: Deserializer<'de>,
         {
-            deserializer.deserialize_bytes(ByteArrayVisitor)
+            deserialize(deserializer, Encoding::Base64)
         }
     }
 
+    macro_rules! encoding_module {
+        ($module:ident, $encoding:ident, $doc:literal) => {
+            #[doc = concat!("Serialize keys and signatures as ", $doc, " strings in human-readable formats.")]
+            ///
+            /// For use with `#[serde(with = "..")]` on a key, seed or signature, e.g. a
+            /// `PublicKey`, or on a [`ByteArray`]. Binary formats use the *byte array*
+            /// type of the `serde` data model, as without this module.
+            pub mod $module {
+                use super::{ByteArray, Encoding};
+                use serde::{Deserializer, Serializer};
+
+                #[doc = concat!("Serialize `value` as ", $doc, " string in human-readable formats.")]
+                pub fn serialize<T, S, const N: usize>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    T: AsRef<ByteArray<N>>,
+                    S: Serializer,
+                {
+                    super::serialize(value.as_ref(), serializer, Encoding::$encoding)
+                }
+
+                #[doc = concat!("Deserialize from ", $doc, " string in human-readable formats.")]
+                pub fn deserialize<'de, T, D, const N: usize>(deserializer: D) -> Result<T, D::Error>
+                where
+                    T: From<ByteArray<N>>,
+                    D: Deserializer<'de>,
+                {
+                    super::deserialize(deserializer, Encoding::$encoding).map(T::from)
+                }
+            }
+        };
+    }
+
+    encoding_module!(hex, Hex, "a lower case hex");
+    encoding_module!(base64, Base64, "a standard, padded base64");
+
     #[cfg(test)]
     mod tests {
         use super::*;
-        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+        use serde_test::{
+            assert_de_tokens, assert_de_tokens_error, assert_tokens, Configure, Token,
+        };
 
         #[test]
         fn test_bytearray_tokens() {
             let bytes = b"hello";
             let serde_bytearr = ByteArray(*bytes);
 
-            assert_tokens(&serde_bytearr, &[Token::Bytes(bytes.as_ref())]);
+            assert_tokens(&serde_bytearr.compact(), &[Token::Bytes(bytes.as_ref())]);
+            assert_tokens(&serde_bytearr.readable(), &[Token::Str("aGVsbG8=")]);
+            assert_de_tokens(&serde_bytearr.readable(), &[Token::Bytes(bytes.as_ref())]);
         }
 
         #[test]
         fn test_bytearray_len_error() {
             let bytes = b"hello";
 
-            assert_de_tokens_error::<ByteArray<4>>(
+            assert_de_tokens_error::<serde_test::Compact<ByteArray<4>>>(
                 &[Token::Bytes(bytes.as_ref())],
                 "invalid length 5, expected a byte array of length 4",
             );
 
-            assert_de_tokens_error::<ByteArray<6>>(
+            assert_de_tokens_error::<serde_test::Compact<ByteArray<6>>>(
                 &[Token::Bytes(bytes.as_ref())],
                 "invalid length 5, expected a byte array of length 6",
             );
+
+            assert_de_tokens_error::<serde_test::Readable<ByteArray<4>>>(
+                &[Token::Str("aGVsbG8=")],
+                "invalid length 5, expected a base64 string of 4 bytes",
+            );
+            assert_de_tokens_error::<serde_test::Readable<ByteArray<5>>>(
+                &[Token::Str("aGVsbG*=")],
+                "invalid base64",
+            );
+        }
+
+        #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+        struct Encodings {
+            #[serde(with = "hex")]
+            hex: ByteArray<5>,
+            #[serde(with = "base64")]
+            base64: ByteArray<5>,
+        }
+
+        #[test]
+        fn test_encoding_modules() {
+            let bytes = ByteArray(*b"hello");
+            let encodings = Encodings {
+                hex: bytes,
+                base64: bytes,
+            };
+            let fields = |hex, base64| {
+                [
+                    Token::Struct {
+                        name: "Encodings",
+                        len: 2,
+                    },
+                    Token::Str("hex"),
+                    hex,
+                    Token::Str("base64"),
+                    base64,
+                    Token::StructEnd,
+                ]
+            };
+
+            assert_tokens(
+                &encodings.readable(),
+                &fields(Token::Str("68656c6c6f"), Token::Str("aGVsbG8=")),
+            );
+            assert_tokens(
+                &encodings.compact(),
+                &fields(Token::Bytes(b"hello"), Token::Bytes(b"hello")),
+            );
+            assert_de_tokens(
+                &encodings.readable(),
+                &fields(Token::Str("68656C6C6F"), Token::Str("aGVsbG8=")),
+            );
+            assert_de_tokens_error::<serde_test::Readable<Encodings>>(
+                &fields(Token::Str("68656c6c6g"), Token::Str("aGVsbG8="))[..3],
+                "invalid hex",
+            );
+            assert_de_tokens_error::<serde_test::Readable<Encodings>>(
+                &fields(Token::Str("+8656c6c6f"), Token::Str("aGVsbG8="))[..3],
+                "invalid hex",
+            );
+            assert_de_tokens_error::<serde_test::Readable<Encodings>>(
+                &fields(Token::Str("68656c6c"), Token::Str("aGVsbG8="))[..3],
+                "invalid length 4, expected a hex string of 5 bytes",
+            );
+        }
+
+        #[test]
+        fn test_bytearray_seq() {
+            let bytes = ByteArray(*b"hi");
+            let seq = |elements: &[u8]| {
+                let mut tokens = vec![Token::Seq {
+                    len: Some(elements.len()),
+                }];
+                tokens.extend(elements.iter().map(|&b| Token::U8(b)));
+                tokens.push(Token::SeqEnd);
+                tokens
+            };
+
+            assert_de_tokens(&bytes.readable(), &seq(b"hi"));
+            assert_de_tokens(&bytes.compact(), &seq(b"hi"));
+            assert_de_tokens_error::<serde_test::Readable<ByteArray<2>>>(
+                &seq(b"h"),
+                "invalid length 1, expected a base64 string of 2 bytes",
+            );
+            assert_de_tokens_error::<serde_test::Readable<ByteArray<2>>>(
+                &seq(b"hi!"),
+                "invalid length 3, expected a base64 string of 2 bytes",
+            );
+            let json = serde_json::to_string(&[104u8, 105]).unwrap();
+            assert_eq!(serde_json::from_str::<ByteArray<2>>(&json).unwrap(), bytes);
         }
     }
 }
@@ -0,0 +1,120 @@
+//! Tests changing the global avx2 override, in a test binary of their own so
+//! they do not race the library tests.
+
+use dilithium_raw::cpu::override_avx2;
+use dilithium_raw::ml_dsa_44::*;
+use std::sync::{Mutex, MutexGuard};
+
+/// Held by every test for as long as it overrides the detection.
+static OVERRIDE: Mutex<()> = Mutex::new(());
+
+/// Restores automatic detection when dropped, also if the test panics.
+struct RestoreDetection {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl RestoreDetection {
+    fn lock() -> Self {
+        Self {
+            _guard: OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+impl Drop for RestoreDetection {
+    fn drop(&mut self) {
+        // SAFETY: `None` never selects avx2 on a CPU without it
+        unsafe { override_avx2(None) };
+    }
+}
+
+fn avx2_detected() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+#[test]
+fn test_override_avx2() {
+    let _restore = RestoreDetection::lock();
+
+    let msg = b"hello world";
+    let mut random = [37u8; 32];
+    let (pubkey, seckey) = generate_keypair(&mut random);
+    let sig = sign(msg, &seckey);
+
+    let mut overrides = vec![Some(false), None];
+    if avx2_detected() {
+        overrides.push(Some(true));
+    }
+    for supported in overrides {
+        // SAFETY: only ever enables avx2 if it was detected
+        unsafe { override_avx2(supported) };
+        let (pk, sk) = generate_keypair(&mut random);
+        assert_eq!(pk, pubkey);
+        assert_eq!(sk.to_bytes(), seckey.to_bytes());
+        assert_eq!(sign(msg, &seckey), sig);
+        assert!(verify(msg, &sig, &pubkey).is_ok());
+    }
+}
+
+#[test]
+fn test_prepared_public_key_keeps_backend() {
+    if !avx2_detected() {
+        return;
+    }
+    let _restore = RestoreDetection::lock();
+
+    let msg = b"hello world";
+    let mut random = [37u8; 32];
+    let (pubkey, seckey) = generate_keypair(&mut random);
+    let sig = sign(msg, &seckey);
+    let sig_ctx = sign_with_context(msg, b"context", &seckey).unwrap();
+
+    // SAFETY: avx2 support was detected
+    unsafe { override_avx2(Some(true)) };
+    let prepared = PreparedPublicKey::new(&pubkey);
+
+    // SAFETY: disabling avx2 is always sound
+    unsafe { override_avx2(Some(false)) };
+    assert!(prepared.verify(msg, &sig).is_ok());
+    assert!(prepared
+        .verify_with_context(msg, b"context", &sig_ctx)
+        .unwrap()
+        .is_ok());
+    assert_eq!(
+        prepared.verify(b"hello warld", &sig),
+        verify(b"hello warld", &sig, &pubkey)
+    );
+}
+
+#[test]
+fn test_expanded_secret_key_keeps_backend() {
+    if !avx2_detected() {
+        return;
+    }
+    let _restore = RestoreDetection::lock();
+
+    let msg = b"hello world";
+    let mut random = [37u8; 32];
+    let (_, seckey) = generate_keypair(&mut random);
+    let sig = sign(msg, &seckey);
+    let sig_ctx = sign_with_context(msg, b"context", &seckey).unwrap();
+
+    // SAFETY: avx2 support was detected
+    unsafe { override_avx2(Some(true)) };
+    let expanded = ExpandedSecretKey::new(&seckey);
+
+    // SAFETY: disabling avx2 is always sound
+    unsafe { override_avx2(Some(false)) };
+    assert_eq!(expanded.sign(msg), sig);
+    assert_eq!(
+        expanded.sign_with_context(msg, b"context").unwrap(),
+        sig_ctx
+    );
+}